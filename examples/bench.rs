@@ -0,0 +1,9 @@
+//! Measure id throughput on this machine:
+//!
+//! ```sh
+//! cargo run --release --example bench
+//! ```
+
+fn main() {
+    println!("{}", ttid::bench::run_quick());
+}
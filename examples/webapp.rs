@@ -0,0 +1,127 @@
+//! TTIDs end to end: an axum JSON API backed by a Postgres table via sqlx.
+//!
+//! Ids travel as `<type-name>_<shortuuid>` strings in URLs and JSON, and are
+//! stored in a native `UUID` column.
+//!
+//! ```sh
+//! DATABASE_URL=postgres://localhost/ttid cargo run --example webapp --features serde
+//! curl -X POST localhost:3000/users -H 'content-type: application/json' -d '{"name":"ada"}'
+//! curl localhost:3000/users/user_...
+//! ```
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use ttid::{IdType, Ttid};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    User,
+}
+
+impl IdType for Kind {
+    const TYPE_NAMES: &'static [&'static str] = &["user"];
+
+    fn to_type_id(self) -> u16 {
+        match self {
+            Self::User => 1,
+        }
+    }
+
+    fn from_type_id(id: u16) -> Option<Self> {
+        match id {
+            1 => Some(Self::User),
+            _ => None,
+        }
+    }
+
+    fn as_type_name(self) -> &'static str {
+        match self {
+            Self::User => "user",
+        }
+    }
+
+    fn from_type_name(name: &str) -> Option<Self> {
+        match name {
+            "user" => Some(Self::User),
+            _ => None,
+        }
+    }
+}
+
+type UserId = Ttid<Kind>;
+
+#[derive(Deserialize)]
+struct CreateUser {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct User {
+    id: UserId,
+    name: String,
+}
+
+type ApiResult<T> = Result<T, (StatusCode, String)>;
+
+fn internal(err: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+async fn create_user(
+    State(db): State<PgPool>,
+    Json(input): Json<CreateUser>,
+) -> ApiResult<Json<User>> {
+    let id = UserId::new(Kind::User).map_err(internal)?;
+
+    sqlx::query("INSERT INTO users (id, name) VALUES ($1, $2)")
+        .bind(id.as_uuid())
+        .bind(&input.name)
+        .execute(&db)
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(User {
+        id,
+        name: input.name,
+    }))
+}
+
+/// `Path<UserId>` parses and validates the id, malformed or foreign ids are
+/// rejected with `400 Bad Request` before the handler runs.
+async fn get_user(State(db): State<PgPool>, Path(id): Path<UserId>) -> ApiResult<Json<User>> {
+    let row = sqlx::query("SELECT id, name FROM users WHERE id = $1")
+        .bind(id.as_uuid())
+        .fetch_optional(&db)
+        .await
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, format!("{id} not found")))?;
+
+    Ok(Json(User {
+        id: UserId::from_uuid(row.get("id")).map_err(internal)?,
+        name: row.get("name"),
+    }))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let url = std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL must be set")?;
+    let db = PgPool::connect(&url).await?;
+
+    sqlx::query("CREATE TABLE IF NOT EXISTS users (id UUID PRIMARY KEY, name TEXT NOT NULL)")
+        .execute(&db)
+        .await?;
+
+    let app = Router::new()
+        .route("/users", post(create_user))
+        .route("/users/{id}", get(get_user))
+        .with_state(db);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+    println!("listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
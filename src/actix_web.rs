@@ -0,0 +1,322 @@
+//! [`actix-web`](https://docs.rs/actix-web) extraction with descriptive
+//! rejections.
+//!
+//! [`TtidPath`] extracts the single path parameter of a route as an id and
+//! rejects invalid ids with `400 Bad Request` and a message naming the
+//! expected form, e.g.
+//! ``invalid id `org_...`: unknown TTID type name, expected prefix `user_` ``.
+//! [`TtidQuery`] does the same for the `id` query parameter, or the one set
+//! with [`TtidConfig::query_param`]. Register a [`TtidConfig`] as app data to
+//! customize the error response.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     const TYPE_NAMES: &'static [&'static str] = &["user"];
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use actix_web::{App, HttpResponse, error, web};
+//! use ttid::actix_web::{TtidConfig, TtidPath, TtidQuery};
+//!
+//! async fn get_user(TtidPath(id): TtidPath<User>) -> String {
+//!     id.to_string()
+//! }
+//!
+//! async fn find_user(TtidQuery(id): TtidQuery<User>) -> String {
+//!     id.to_string()
+//! }
+//!
+//! let app = App::new()
+//!     .app_data(
+//!         TtidConfig::default()
+//!             .query_param("user")
+//!             .error_handler(|rejection, _req| {
+//!                 let body = format!("{{\"error\": {:?}}}", rejection.to_string());
+//!                 error::InternalError::from_response(
+//!                     rejection,
+//!                     HttpResponse::BadRequest().body(body),
+//!                 )
+//!                 .into()
+//!             }),
+//!     )
+//!     .route("/users/{id}", web::get().to(get_user))
+//!     .route("/users", web::get().to(find_user));
+//! ```
+//!
+//! Routes with several parameters can be extracted as strings, e.g. with
+//! `web::Path<(String, String)>`, and parsed with [`parse_param`]: its
+//! [`TtidRejection`] converts into an `actix_web::Error` with the same
+//! response.
+
+use std::fmt;
+use std::future::{Ready, ready};
+use std::sync::Arc;
+
+use ::actix_web::dev::Payload;
+use ::actix_web::http::StatusCode;
+use ::actix_web::{FromRequest, HttpRequest, ResponseError, web};
+
+use crate::integrations::expected_prefixes;
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// Extractor for a route with a single TTID path parameter, see the
+/// [module docs](self).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtidPath<T: IdType>(pub Ttid<T>);
+
+impl<T: IdType> FromRequest for TtidPath<T> {
+    type Error = ::actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let mut params = req.match_info().iter();
+        let result = match (params.next(), params.next()) {
+            (Some((_, param)), None) => parse_param(param),
+            _ => Err(TtidRejection::NoSingleParam),
+        };
+        ready(result.map(Self).map_err(|rejection| reject(rejection, req)))
+    }
+}
+
+/// Extractor for a TTID query parameter, `id` unless configured with
+/// [`TtidConfig::query_param`], see the [module docs](self).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtidQuery<T: IdType>(pub Ttid<T>);
+
+impl<T: IdType> FromRequest for TtidQuery<T> {
+    type Error = ::actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let name = req
+            .app_data::<TtidConfig>()
+            .and_then(|c| c.query_param.as_deref())
+            .unwrap_or(DEFAULT_QUERY_PARAM);
+        let query = web::Query::<Vec<(String, String)>>::from_query(req.query_string());
+        let result = match query.ok().and_then(|query| {
+            query
+                .into_inner()
+                .into_iter()
+                .find_map(|(key, value)| (key == name).then_some(value))
+        }) {
+            Some(param) => parse_param(&param),
+            None => Err(TtidRejection::MissingQueryParam(name.to_owned())),
+        };
+        ready(result.map(Self).map_err(|rejection| reject(rejection, req)))
+    }
+}
+
+/// Query parameter read by [`TtidQuery`] by default.
+const DEFAULT_QUERY_PARAM: &str = "id";
+
+/// Turn `rejection` into an error with the configured handler, if any.
+fn reject(rejection: TtidRejection, req: &HttpRequest) -> ::actix_web::Error {
+    match req
+        .app_data::<TtidConfig>()
+        .and_then(|c| c.error_handler.as_ref())
+    {
+        Some(handler) => handler(rejection, req),
+        None => rejection.into(),
+    }
+}
+
+/// Handler turning rejections into error responses, see
+/// [`TtidConfig::error_handler`].
+type ErrorHandler = dyn Fn(TtidRejection, &HttpRequest) -> ::actix_web::Error + Send + Sync;
+
+/// App data configuring [`TtidPath`] and [`TtidQuery`] extraction.
+#[derive(Clone, Default)]
+pub struct TtidConfig {
+    error_handler: Option<Arc<ErrorHandler>>,
+    query_param: Option<String>,
+}
+
+impl TtidConfig {
+    /// Read [`TtidQuery`] ids from the query parameter `name` instead of
+    /// `id`.
+    pub fn query_param(mut self, name: impl Into<String>) -> Self {
+        self.query_param = Some(name.into());
+        self
+    }
+
+    /// Build error responses for rejected ids with `handler`, instead of the
+    /// default `400 Bad Request` with the rejection message.
+    pub fn error_handler(
+        mut self,
+        handler: impl Fn(TtidRejection, &HttpRequest) -> ::actix_web::Error + Send + Sync + 'static,
+    ) -> Self {
+        self.error_handler = Some(Arc::new(handler));
+        self
+    }
+}
+
+/// Parse a request parameter as an id of domain `T`, with a rejection
+/// describing the expected form.
+pub fn parse_param<T: IdType>(param: &str) -> Result<Ttid<T>, TtidRejection> {
+    param.parse().map_err(|error| TtidRejection::Invalid {
+        param: param.to_owned(),
+        error,
+        expected: expected_prefixes::<T>(),
+    })
+}
+
+/// Rejection of [`TtidPath`], [`TtidQuery`] and [`parse_param`].
+#[derive(Debug)]
+pub enum TtidRejection {
+    /// The route does not have exactly one path parameter.
+    NoSingleParam,
+    /// The request has no query parameter with the given name.
+    MissingQueryParam(String),
+    /// The parameter is not a valid id of the expected domain.
+    Invalid {
+        /// The rejected parameter.
+        param: String,
+        /// Why it was rejected.
+        error: ParseTtidError,
+        /// Description of valid ids, e.g. ``expected prefix `user_` ``, if
+        /// the domain lists its [`IdType::TYPE_NAMES`].
+        expected: Option<String>,
+    },
+}
+
+impl fmt::Display for TtidRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSingleParam => f.write_str("expected a route with a single id path parameter"),
+            Self::MissingQueryParam(name) => write!(f, "missing query parameter `{name}`"),
+            Self::Invalid {
+                param,
+                error,
+                expected,
+            } => {
+                write!(f, "invalid id `{param}`: {error}")?;
+                if let Some(expected) = expected {
+                    write!(f, ", {expected}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TtidRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoSingleParam | Self::MissingQueryParam(_) => None,
+            Self::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+/// `400 Bad Request` with the [`Display`](fmt::Display) message for invalid
+/// or missing ids, `500 Internal Server Error` for misconfigured routes.
+impl ResponseError for TtidRejection {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NoSingleParam => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::MissingQueryParam(_) | Self::Invalid { .. } => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::actix_web::test::TestRequest;
+    use ::actix_web::{HttpResponse, error};
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[::actix_web::test]
+    async fn extracts_ids_and_describes_rejections() {
+        let user = Ttid::<NarrowType>::from_parts(1_700_000_000_000, NarrowType::User, 7).unwrap();
+        let req = TestRequest::default()
+            .param("id", user.to_string())
+            .to_http_request();
+        assert_eq!(
+            TtidPath::<NarrowType>::extract(&req).await.unwrap(),
+            TtidPath(user)
+        );
+
+        let req = TestRequest::default().param("id", "nope").to_http_request();
+        let err = TtidPath::<NarrowType>::extract(&req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid id `nope`: invalid TTID string format, expected <type>_<shortuuid>"
+        );
+
+        let req = TestRequest::default().to_http_request();
+        let err = TtidPath::<NarrowType>::extract(&req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[::actix_web::test]
+    async fn error_handler_builds_responses() {
+        let config = TtidConfig::default().error_handler(|rejection, _req| {
+            error::InternalError::from_response(rejection, HttpResponse::NotFound().finish()).into()
+        });
+        let req = TestRequest::default()
+            .param("id", "nope")
+            .app_data(config)
+            .to_http_request();
+
+        let err = TtidPath::<MyType>::extract(&req).await.unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[::actix_web::test]
+    async fn extracts_query_params() {
+        let user = Ttid::<NarrowType>::from_parts(1_700_000_000_000, NarrowType::User, 7).unwrap();
+        let req = TestRequest::with_uri(&format!("/users?page=2&id={user}")).to_http_request();
+        assert_eq!(
+            TtidQuery::<NarrowType>::extract(&req).await.unwrap(),
+            TtidQuery(user)
+        );
+
+        let req = TestRequest::with_uri(&format!("/users?user={user}"))
+            .app_data(TtidConfig::default().query_param("user"))
+            .to_http_request();
+        assert_eq!(
+            TtidQuery::<NarrowType>::extract(&req).await.unwrap(),
+            TtidQuery(user)
+        );
+
+        let req = TestRequest::with_uri("/users?id=nope").to_http_request();
+        let err = TtidQuery::<NarrowType>::extract(&req).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid id `nope`: invalid TTID string format, expected <type>_<shortuuid>"
+        );
+
+        let req = TestRequest::with_uri(&format!("/users?user={user}")).to_http_request();
+        let err = TtidQuery::<NarrowType>::extract(&req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(err.to_string(), "missing query parameter `id`");
+    }
+
+    #[test]
+    fn rejection_lists_type_names() {
+        let org = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        let err = parse_param::<MyType>(&format!("team_{}", org.short_uuid())).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert!(
+            err.to_string()
+                .ends_with("expected one of the prefixes `user_`, `org_`, `session_`, `max_`")
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Routing ids to actors, for actor frameworks that address messages by
+//! entity id.
+//!
+//! A [`Topology`] maps the type id and randomness of an id to a
+//! `(shard, worker)` address. The randomness is uniformly distributed, so
+//! entities spread evenly, and the address is stable: every message for an
+//! entity reaches the same actor as long as the topology is unchanged.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use ttid::actor::{self, Uniform};
+//!
+//! let id = Ttid::from_parts(1_700_000_000_000, User, 42).unwrap();
+//! let (shard, worker) = actor::address(&id, &Uniform::new(4, 8));
+//! assert_eq!((shard, worker), (2, 2));
+//! ```
+
+use crate::{IdType, Ttid};
+
+/// Mapping of ids to `(shard, worker)` addresses.
+///
+/// Implemented for closures taking the type id and the randomness.
+pub trait Topology {
+    /// Address of the actor owning the entity with `type_id` and
+    /// `randomness`.
+    fn route(&self, type_id: u16, randomness: u64) -> (u32, u32);
+}
+
+impl<F: Fn(u16, u64) -> (u32, u32)> Topology for F {
+    fn route(&self, type_id: u16, randomness: u64) -> (u32, u32) {
+        self(type_id, randomness)
+    }
+}
+
+/// Address of the actor owning `id` in `topology`.
+pub fn address<T: IdType, P: Topology + ?Sized>(id: &Ttid<T>, topology: &P) -> (u32, u32) {
+    topology.route(id.type_id(), id.randomness())
+}
+
+/// Spreads ids of all types evenly over every worker of every shard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Uniform {
+    shards: u32,
+    workers: u32,
+}
+
+impl Uniform {
+    /// Topology of `shards` shards with `workers` workers each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` or `workers` is `0`.
+    pub fn new(shards: u32, workers: u32) -> Self {
+        assert!(shards > 0, "shards must be non-zero");
+        assert!(workers > 0, "workers must be non-zero");
+        Self { shards, workers }
+    }
+}
+
+impl Topology for Uniform {
+    fn route(&self, _type_id: u16, randomness: u64) -> (u32, u32) {
+        let shard = randomness % u64::from(self.shards);
+        let worker = (randomness / u64::from(self.shards)) % u64::from(self.workers);
+        (shard as u32, worker as u32)
+    }
+}
+
+/// Dedicates one shard to each type, addressed by the type id, and spreads
+/// the ids of a type over its workers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PerType {
+    workers: u32,
+}
+
+impl PerType {
+    /// Topology with `workers` workers per type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is `0`.
+    pub fn new(workers: u32) -> Self {
+        assert!(workers > 0, "workers must be non-zero");
+        Self { workers }
+    }
+}
+
+impl Topology for PerType {
+    fn route(&self, type_id: u16, randomness: u64) -> (u32, u32) {
+        let worker = randomness % u64::from(self.workers);
+        (u32::from(type_id), worker as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    fn id(ty: MyType, randomness: u64) -> Ttid<MyType> {
+        Ttid::from_parts(1_700_000_000_000, ty, randomness).unwrap()
+    }
+
+    #[test]
+    fn uniform_uses_every_address() {
+        let topology = Uniform::new(3, 5);
+        let mut seen = [[0u32; 5]; 3];
+        for randomness in 0..15 {
+            let (shard, worker) = address(&id(MyType::User, randomness), &topology);
+            seen[shard as usize][worker as usize] += 1;
+        }
+        assert_eq!(seen, [[1; 5]; 3]);
+
+        let session = id(MyType::Session, 7);
+        assert_eq!(address(&session, &topology), (1, 2));
+        assert_eq!(
+            address(&session.with_type(MyType::Org).unwrap(), &topology),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn per_type_and_custom_topologies() {
+        let topology = PerType::new(4);
+        assert_eq!(address(&id(MyType::Session, 7), &topology), (777, 3));
+        assert_eq!(address(&id(MyType::User, 8), &topology), (1, 0));
+
+        let hot_sessions = |type_id: u16, randomness: u64| match type_id {
+            777 => (0, (randomness % 16) as u32),
+            _ => (1, 0),
+        };
+        assert_eq!(address(&id(MyType::Session, 21), &hot_sessions), (0, 5));
+        assert_eq!(address(&id(MyType::Org, 21), &hot_sessions), (1, 0));
+
+        let dynamic: &dyn Topology = &topology;
+        assert_eq!(address(&id(MyType::User, 8), dynamic), (1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "workers must be non-zero")]
+    fn rejects_zero_workers() {
+        Uniform::new(1, 0);
+    }
+}
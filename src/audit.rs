@@ -0,0 +1,179 @@
+//! Hash-linked sequences of ids for append-only audit trails.
+//!
+//! Each [`Link`] carries a running hash over the previous link's hash and its
+//! id, so editing, removing or reordering an entry changes every later hash.
+//! Storing the current [`Chain::head`] out of band makes truncation
+//! detectable too.
+//!
+//! The hash is the crate's stable 64-bit hash, which detects corruption and
+//! naive edits but is not a cryptographic commitment. Where entries may be
+//! forged deliberately, additionally sign or anchor the heads.
+
+use std::marker::PhantomData;
+
+use crate::hash::StableHasher;
+use crate::{ChainError, IdType, Ttid};
+
+/// Head of an empty chain.
+pub const GENESIS: u64 = 0;
+
+/// An id with the running hash of the chain up to and including it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Link<T: IdType> {
+    /// The appended id.
+    pub id: Ttid<T>,
+    /// Running hash over all ids up to and including `id`.
+    pub hash: u64,
+}
+
+/// Builder of a hash-linked id sequence, see the [module docs](self).
+///
+/// Ids must be appended in time order, ids sharing a millisecond may be
+/// appended in any order.
+#[derive(Clone, Copy, Debug)]
+pub struct Chain<T: IdType> {
+    head: u64,
+    len: usize,
+    last_timestamp_ms: u64,
+    marker: PhantomData<T>,
+}
+
+impl<T: IdType> Chain<T> {
+    /// Start an empty chain.
+    pub fn new() -> Self {
+        Self {
+            head: GENESIS,
+            len: 0,
+            last_timestamp_ms: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Continue a chain after its stored last link, with `len` links so far.
+    pub fn resume(last: Link<T>, len: usize) -> Self {
+        Self {
+            head: last.hash,
+            len,
+            last_timestamp_ms: last.id.timestamp_ms(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Hash of the last link, or [`GENESIS`] for an empty chain.
+    pub fn head(&self) -> u64 {
+        self.head
+    }
+
+    /// Number of appended links.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no link was appended yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append `id` and return its link.
+    ///
+    /// Fails with [`ChainError::OutOfOrder`] if `id` is older than the
+    /// previous id.
+    pub fn append(&mut self, id: Ttid<T>) -> Result<Link<T>, ChainError> {
+        if id.timestamp_ms() < self.last_timestamp_ms {
+            return Err(ChainError::OutOfOrder { index: self.len });
+        }
+
+        self.head = link_hash(self.head, id);
+        self.len += 1;
+        self.last_timestamp_ms = id.timestamp_ms();
+        Ok(Link {
+            id,
+            hash: self.head,
+        })
+    }
+
+    /// Verify a complete chain from its first link, returning the head.
+    ///
+    /// Compare the head with a stored one to also detect truncation.
+    pub fn verify<'a>(links: impl IntoIterator<Item = &'a Link<T>>) -> Result<u64, ChainError>
+    where
+        T: 'a,
+    {
+        let mut chain = Self::new();
+        for link in links {
+            let index = chain.len;
+            if chain.append(link.id)?.hash != link.hash {
+                return Err(ChainError::HashMismatch { index });
+            }
+        }
+        Ok(chain.head)
+    }
+}
+
+impl<T: IdType> Default for Chain<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn link_hash<T: IdType>(prev: u64, id: Ttid<T>) -> u64 {
+    StableHasher::new()
+        .write(&prev.to_le_bytes())
+        .write(id.as_uuid().as_bytes())
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    fn ids() -> Vec<Ttid<MyType>> {
+        (0..4)
+            .map(|n| Ttid::from_parts(1_000 + n / 2, MyType::User, n).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn verify_detects_edits_and_reordering() {
+        let mut chain = Chain::new();
+        let links: Vec<_> = ids()
+            .into_iter()
+            .map(|id| chain.append(id).unwrap())
+            .collect();
+
+        assert_eq!(chain.len(), 4);
+        assert_eq!(Chain::verify(&links), Ok(chain.head()));
+        assert_eq!(Chain::<MyType>::verify(&[]), Ok(GENESIS));
+
+        let mut edited = links.clone();
+        edited[2].id = edited[2].id.with_randomness(99);
+        assert_eq!(
+            Chain::verify(&edited),
+            Err(ChainError::HashMismatch { index: 2 })
+        );
+
+        let mut swapped = links.clone();
+        swapped.swap(0, 1);
+        assert_eq!(
+            Chain::verify(&swapped),
+            Err(ChainError::HashMismatch { index: 0 })
+        );
+    }
+
+    #[test]
+    fn append_rejects_older_ids_and_resumes() {
+        let ids = ids();
+        let mut chain = Chain::new();
+        let first = chain.append(ids[2]).unwrap();
+        assert_eq!(
+            chain.append(ids[0]),
+            Err(ChainError::OutOfOrder { index: 1 })
+        );
+
+        let mut resumed = Chain::resume(first, 1);
+        assert_eq!(resumed.append(ids[3]), chain.append(ids[3]));
+        assert_eq!(resumed.head(), chain.head());
+        assert_eq!(resumed.len(), 2);
+    }
+}
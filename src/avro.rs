@@ -0,0 +1,159 @@
+//! Apache Avro support via the `apache-avro` crate.
+//!
+//! A TTID field is written in one of two encodings, chosen per field with
+//! [`AvroEncoding`]:
+//!
+//! - [`AvroEncoding::Uuid`]: a string with the `uuid` logical type, holding
+//!   the hyphenated UUID, readable by any Avro consumer as a native UUID,
+//! - [`AvroEncoding::Prefixed`]: a plain string holding the
+//!   `<type-name>_<shortuuid>` form.
+//!
+//! [`from_value`] reads both, so the encoding of a field can change between
+//! schema versions.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use ttid::avro::{AvroEncoding, from_value};
+//!
+//! let id = Ttid::new(User).unwrap();
+//! let value = AvroEncoding::Uuid.to_value(id);
+//! assert_eq!(from_value::<User>(&value).unwrap(), id);
+//! ```
+
+use apache_avro::Schema;
+use apache_avro::types::Value;
+use uuid::Uuid;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// Avro encoding of a TTID field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AvroEncoding {
+    /// `{"type": "string", "logicalType": "uuid"}` holding the hyphenated
+    /// UUID.
+    #[default]
+    Uuid,
+    /// `"string"` holding `<type-name>_<shortuuid>`.
+    Prefixed,
+}
+
+impl AvroEncoding {
+    /// Schema of a field in this encoding.
+    pub fn schema(self) -> Schema {
+        match self {
+            Self::Uuid => Schema::parse_str(r#"{"type": "string", "logicalType": "uuid"}"#)
+                .expect("uuid schema is valid"),
+            Self::Prefixed => Schema::String,
+        }
+    }
+
+    /// Convert `id` to an Avro value in this encoding.
+    pub fn to_value<T: IdType>(self, id: Ttid<T>) -> Value {
+        match self {
+            Self::Uuid => Value::Uuid(id.as_uuid()),
+            Self::Prefixed => Value::String(id.to_string()),
+        }
+    }
+}
+
+/// Read a TTID from an Avro value in either [`AvroEncoding`].
+///
+/// Accepts UUID values, strings in the TTID or hyphenated UUID form, and
+/// unions holding one of these, e.g. for nullable fields. Validates the TTID
+/// layout and type id.
+pub fn from_value<T: IdType>(value: &Value) -> Result<Ttid<T>, ParseTtidError> {
+    match value {
+        Value::Uuid(uuid) => Ok(Ttid::from_uuid(*uuid)?),
+        Value::String(text) if text.contains('_') => text.parse(),
+        Value::String(text) => {
+            let uuid = Uuid::try_parse(text).map_err(|_| ParseTtidError::InvalidFormat)?;
+            Ok(Ttid::from_uuid(uuid)?)
+        }
+        Value::Union(_, inner) => from_value(inner),
+        _ => Err(ParseTtidError::InvalidFormat),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::{Reader, Writer};
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    fn record_schema() -> Schema {
+        Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "event",
+                "fields": [
+                    {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+                    {"name": "actor", "type": "string"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn schemas_match_encodings() {
+        let Schema::Record(record) = record_schema() else {
+            panic!("expected record schema");
+        };
+        assert_eq!(record.fields[0].schema, AvroEncoding::Uuid.schema());
+        assert_eq!(record.fields[1].schema, AvroEncoding::Prefixed.schema());
+    }
+
+    #[test]
+    fn record_roundtrip_with_mixed_encodings() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        let actor = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 8).unwrap();
+
+        let schema = record_schema();
+        let mut writer = Writer::new(&schema, Vec::new());
+        writer
+            .append(Value::Record(vec![
+                ("id".into(), AvroEncoding::Uuid.to_value(id)),
+                ("actor".into(), AvroEncoding::Prefixed.to_value(actor)),
+            ]))
+            .unwrap();
+        let bytes = writer.into_inner().unwrap();
+
+        let mut reader = Reader::new(&bytes[..]).unwrap();
+        let Value::Record(fields) = reader.next().unwrap().unwrap() else {
+            panic!("expected record");
+        };
+        assert_eq!(fields[0].1, Value::Uuid(id.as_uuid()));
+        assert_eq!(fields[1].1, Value::String(actor.to_string()));
+        assert_eq!(from_value::<MyType>(&fields[0].1).unwrap(), id);
+        assert_eq!(from_value::<MyType>(&fields[1].1).unwrap(), actor);
+    }
+
+    #[test]
+    fn from_value_accepts_either_string_and_validates() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        let hyphenated = Value::String(id.as_uuid().to_string());
+        assert_eq!(from_value::<MyType>(&hyphenated).unwrap(), id);
+        let nullable = Value::Union(1, Box::new(Value::Uuid(id.as_uuid())));
+        assert_eq!(from_value::<MyType>(&nullable).unwrap(), id);
+
+        assert!(from_value::<NarrowType>(&Value::Uuid(id.as_uuid())).is_err());
+        assert_eq!(
+            from_value::<MyType>(&Value::Null),
+            Err(ParseTtidError::InvalidFormat)
+        );
+        assert_eq!(
+            from_value::<MyType>(&Value::String("garbage".into())),
+            Err(ParseTtidError::InvalidFormat)
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! [`axum`](https://docs.rs/axum) extraction with descriptive rejections.
+//!
+//! `Path<Ttid<T>>` works with the `serde` feature, but rejects invalid ids
+//! with axum's generic path deserialization error. [`TtidPath`] instead
+//! answers `400 Bad Request` with a message naming the expected form, e.g.
+//! ``invalid id `org_...`: unknown TTID type name, expected prefix `user_` ``.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     const TYPE_NAMES: &'static [&'static str] = &["user"];
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use axum::Router;
+//! use axum::routing::get;
+//! use ttid::axum::TtidPath;
+//!
+//! async fn get_user(TtidPath(id): TtidPath<User>) -> String {
+//!     id.to_string()
+//! }
+//!
+//! let app: Router = Router::new().route("/users/{id}", get(get_user));
+//! ```
+//!
+//! For routes with several parameters, extract them as strings and parse
+//! each with [`parse_param`].
+
+use std::fmt;
+
+use ::axum::extract::rejection::PathRejection;
+use ::axum::extract::{FromRequestParts, Path};
+use ::axum::http::StatusCode;
+use ::axum::http::request::Parts;
+use ::axum::response::{IntoResponse, Response};
+
+use crate::integrations::expected_prefixes;
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// Extractor for a route with a single TTID path parameter, see the
+/// [module docs](self).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtidPath<T: IdType>(pub Ttid<T>);
+
+impl<S: Send + Sync, T: IdType + Send + Sync> FromRequestParts<S> for TtidPath<T> {
+    type Rejection = TtidRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(param) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(TtidRejection::Path)?;
+        parse_param(&param).map(Self)
+    }
+}
+
+/// Parse a request parameter as an id of domain `T`, with a rejection
+/// describing the expected form.
+pub fn parse_param<T: IdType>(param: &str) -> Result<Ttid<T>, TtidRejection> {
+    param.parse().map_err(|error| TtidRejection::Invalid {
+        param: param.to_owned(),
+        error,
+        expected: expected_prefixes::<T>(),
+    })
+}
+
+/// Rejection of [`TtidPath`] and [`parse_param`].
+#[derive(Debug)]
+pub enum TtidRejection {
+    /// The route has no single path parameter, or it is not valid UTF-8.
+    Path(PathRejection),
+    /// The parameter is not a valid id of the expected domain.
+    Invalid {
+        /// The rejected parameter.
+        param: String,
+        /// Why it was rejected.
+        error: ParseTtidError,
+        /// Description of valid ids, e.g. ``expected prefix `user_` ``, if
+        /// the domain lists its [`IdType::TYPE_NAMES`].
+        expected: Option<String>,
+    },
+}
+
+impl fmt::Display for TtidRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(rejection) => write!(f, "{rejection}"),
+            Self::Invalid {
+                param,
+                error,
+                expected,
+            } => {
+                write!(f, "invalid id `{param}`: {error}")?;
+                if let Some(expected) = expected {
+                    write!(f, ", {expected}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TtidRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Path(rejection) => Some(rejection),
+            Self::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+/// `400 Bad Request` with the [`Display`](fmt::Display) message for invalid
+/// ids, axum's response for path rejections.
+impl IntoResponse for TtidRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Path(rejection) => rejection.into_response(),
+            invalid @ Self::Invalid { .. } => {
+                (StatusCode::BAD_REQUEST, invalid.to_string()).into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::axum::Router;
+    use ::axum::body::{Body, to_bytes};
+    use ::axum::http::Request;
+    use ::axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    async fn send(app: Router, uri: &str) -> (StatusCode, String) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn extracts_ids_and_describes_rejections() {
+        async fn handler(TtidPath(id): TtidPath<NarrowType>) -> String {
+            id.to_string()
+        }
+        let app = Router::new().route("/users/{id}", get(handler));
+
+        let user = Ttid::<NarrowType>::from_parts(1_700_000_000_000, NarrowType::User, 7).unwrap();
+        let (status, body) = send(app.clone(), &format!("/users/{user}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, user.to_string());
+
+        let (status, body) = send(app, "/users/nope").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            "invalid id `nope`: invalid TTID string format, expected <type>_<shortuuid>"
+        );
+    }
+
+    #[test]
+    fn rejection_lists_type_names() {
+        let org = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        let err = parse_param::<MyType>(&format!("team_{}", org.short_uuid())).unwrap_err();
+        assert!(matches!(
+            &err,
+            TtidRejection::Invalid {
+                error: ParseTtidError::UnknownTypeName,
+                ..
+            }
+        ));
+        assert!(
+            err.to_string()
+                .ends_with("expected one of the prefixes `user_`, `org_`, `session_`, `max_`")
+        );
+        assert_eq!(parse_param::<MyType>(&org.to_string()).unwrap(), org);
+    }
+}
@@ -0,0 +1,148 @@
+//! Quick throughput measurement on the current machine.
+//!
+//! For evaluating the crate for high-throughput ingestion without writing a
+//! benchmark harness, and for coarse regression checks in CI. See the
+//! `bench` example:
+//!
+//! ```no_run
+//! let report = ttid::bench::run_quick();
+//! println!("{report}");
+//! assert!(report.parse.ops_per_sec() > 100_000.0);
+//! ```
+
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{IdType, Ttid};
+
+/// Measuring time per operation of [`run_quick`].
+pub const QUICK_DURATION: Duration = Duration::from_millis(200);
+
+/// Number of operations between clock readings.
+const CHUNK: u64 = 1_024;
+
+/// Operations completed in a measured time span.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Throughput {
+    /// Number of operations.
+    pub ops: u64,
+    /// Time taken by all operations.
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    /// Operations per second.
+    pub fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Average time per operation.
+    pub fn per_op(&self) -> Duration {
+        self.elapsed
+            .checked_div(u32::try_from(self.ops).unwrap_or(u32::MAX))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0} ops/s ({:?}/op)",
+            self.ops_per_sec(),
+            self.per_op()
+        )
+    }
+}
+
+/// Throughput of the core operations, returned by [`run_quick`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+    /// [`Ttid::new`].
+    pub generate: Throughput,
+    /// Formatting in `<type-name>_<shortuuid>` form.
+    pub encode: Throughput,
+    /// Parsing the `<type-name>_<shortuuid>` form.
+    pub parse: Throughput,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "generate: {}", self.generate)?;
+        writeln!(f, "encode:   {}", self.encode)?;
+        write!(f, "parse:    {}", self.parse)
+    }
+}
+
+/// Measure each operation for [`QUICK_DURATION`], under a second in total.
+///
+/// Results vary with load and build profile; measure release builds.
+pub fn run_quick() -> Report {
+    run_for(QUICK_DURATION)
+}
+
+/// Measure each operation for at least `duration`.
+pub fn run_for(duration: Duration) -> Report {
+    let id = Ttid::new(Bench).expect("system clock within the 48-bit range");
+    let text = id.to_string();
+    Report {
+        generate: measure(duration, || Ttid::new(black_box(Bench))),
+        encode: measure(duration, || black_box(&id).to_string()),
+        parse: measure(duration, || black_box(text.as_str()).parse::<Ttid<Bench>>()),
+    }
+}
+
+/// Run `op` in chunks until `duration` elapsed.
+fn measure<R>(duration: Duration, mut op: impl FnMut() -> R) -> Throughput {
+    let start = Instant::now();
+    let mut ops = 0;
+    loop {
+        for _ in 0..CHUNK {
+            black_box(op());
+        }
+        ops += CHUNK;
+        let elapsed = start.elapsed();
+        if elapsed >= duration {
+            return Throughput { ops, elapsed };
+        }
+    }
+}
+
+/// Single-type domain with a typical name length.
+#[derive(Clone, Copy, Debug)]
+struct Bench;
+
+impl IdType for Bench {
+    fn to_type_id(self) -> u16 {
+        1
+    }
+
+    fn from_type_id(id: u16) -> Option<Self> {
+        (id == 1).then_some(Self)
+    }
+
+    fn as_type_name(self) -> &'static str {
+        "account"
+    }
+
+    fn from_type_name(name: &str) -> Option<Self> {
+        (name == "account").then_some(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_every_operation() {
+        let report = run_for(Duration::from_millis(1));
+        for throughput in [report.generate, report.encode, report.parse] {
+            assert!(throughput.ops >= CHUNK);
+            assert!(throughput.elapsed >= Duration::from_millis(1));
+            assert!(throughput.ops_per_sec() > 0.0);
+        }
+        assert_eq!(report.to_string().lines().count(), 3);
+    }
+}
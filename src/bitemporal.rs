@@ -0,0 +1,146 @@
+//! Ids of versioned entities.
+//!
+//! A [`VersionedId`] pairs the stable id of an entity with the id of one of
+//! its versions, e.g. a document and a revision. Since version ids embed
+//! their creation time, the versions of an entity sort chronologically.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// Separator between entity and version id in the string form of a
+/// [`VersionedId`], not part of any id with a
+/// [valid](crate::validate_type_name) type name.
+const VERSION_SEPARATOR: char = '@';
+
+/// An entity id combined with the id of one of its versions.
+///
+/// The string form is `<entity>@<version>`, e.g.
+/// `doc_hnP4K7MiDaGjM9R6vMshtY@rev_5Gq9RmvqjvsvRbT3eJgQ1u`. Ordering is by
+/// `entity`, then `version`, each in binary UUID order, so all versions of an
+/// entity are adjacent and ordered by creation time.
+#[derive(Clone, Copy, Debug)]
+pub struct VersionedId<T: IdType, V: IdType> {
+    /// Stable id of the entity.
+    pub entity: Ttid<T>,
+    /// Id of the version.
+    pub version: Ttid<V>,
+}
+
+impl<T: IdType, V: IdType> VersionedId<T, V> {
+    /// Combine an entity id with a version id.
+    pub fn new(entity: Ttid<T>, version: Ttid<V>) -> Self {
+        Self { entity, version }
+    }
+
+    /// The same entity at another version.
+    pub fn with_version(&self, version: Ttid<V>) -> Self {
+        Self::new(self.entity, version)
+    }
+
+    /// Whether both ids refer to the same entity, in any version.
+    pub fn same_entity(&self, other: &Self) -> bool {
+        self.entity.as_uuid() == other.entity.as_uuid()
+    }
+}
+
+impl<T: IdType, V: IdType> From<(Ttid<T>, Ttid<V>)> for VersionedId<T, V> {
+    fn from((entity, version): (Ttid<T>, Ttid<V>)) -> Self {
+        Self::new(entity, version)
+    }
+}
+
+impl<T: IdType, V: IdType> PartialEq for VersionedId<T, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: IdType, V: IdType> Eq for VersionedId<T, V> {}
+
+impl<T: IdType, V: IdType> PartialOrd for VersionedId<T, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: IdType, V: IdType> Ord for VersionedId<T, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.entity.as_uuid(), self.version.as_uuid())
+            .cmp(&(other.entity.as_uuid(), other.version.as_uuid()))
+    }
+}
+
+impl<T: IdType, V: IdType> Hash for VersionedId<T, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.entity.as_uuid().hash(state);
+        self.version.as_uuid().hash(state);
+    }
+}
+
+impl<T: IdType, V: IdType> fmt::Display for VersionedId<T, V> {
+    /// Formats as `<entity>@<version>`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{VERSION_SEPARATOR}{}", self.entity, self.version)
+    }
+}
+
+impl<T: IdType, V: IdType> FromStr for VersionedId<T, V> {
+    type Err = ParseTtidError;
+
+    /// Parses `<entity>@<version>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (entity, version) = s
+            .split_once(VERSION_SEPARATOR)
+            .ok_or(ParseTtidError::InvalidFormat)?;
+        Ok(Self::new(entity.parse()?, version.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn string_form_roundtrips() {
+        let entity = Ttid::<MyType>::from_parts(1_000, MyType::Org, 1).unwrap();
+        let version = Ttid::<NarrowType>::from_parts(2_000, NarrowType::User, 2).unwrap();
+        let id = VersionedId::new(entity, version);
+
+        let text = id.to_string();
+        assert_eq!(text, format!("{entity}@{version}"));
+        assert_eq!(text.parse::<VersionedId<MyType, NarrowType>>().unwrap(), id);
+
+        assert!(matches!(
+            entity
+                .to_string()
+                .parse::<VersionedId<MyType, NarrowType>>(),
+            Err(ParseTtidError::InvalidFormat)
+        ));
+        assert!(
+            format!("{version}@{entity}")
+                .parse::<VersionedId<MyType, NarrowType>>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn versions_of_an_entity_are_adjacent_and_chronological() {
+        let doc = |timestamp_ms| Ttid::<MyType>::from_parts(timestamp_ms, MyType::Org, 1).unwrap();
+        let rev = |timestamp_ms| Ttid::<MyType>::from_parts(timestamp_ms, MyType::User, 1).unwrap();
+
+        let a1 = VersionedId::new(doc(1_000), rev(5_000));
+        let a2 = a1.with_version(rev(6_000));
+        let b1 = VersionedId::new(doc(2_000), rev(3_000));
+        assert!(a1.same_entity(&a2));
+        assert!(!a1.same_entity(&b1));
+
+        let mut ids = vec![b1, a2, a1];
+        ids.sort();
+        assert_eq!(ids, vec![a1, a2, b1]);
+    }
+}
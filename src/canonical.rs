@@ -0,0 +1,209 @@
+use short_uuid::ShortUuid;
+use uuid::Uuid;
+
+use crate::registry::{DynTtid, TypeRegistry};
+use crate::{IdType, ParseTtidError, RawTtid};
+
+/// Crockford base32 alphabet used by TypeID suffixes.
+const TYPEID_ALPHABET: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+/// Length of a TypeID suffix, 128 bits in base32.
+const TYPEID_SUFFIX_LEN: usize = 26;
+
+/// Normalize an id in any accepted form to the canonical
+/// `<type-name>_<shortuuid>` string of domain `T`.
+///
+/// Accepted forms, surrounding whitespace is ignored:
+/// - `<type-name>_<shortuuid>`, the canonical form,
+/// - `<type-name>_<suffix>` and bare `<suffix>` TypeIDs, with the 26
+///   character lowercase base32 suffix,
+/// - UUID strings: hyphenated, braced, `urn:uuid:` and plain hex, optionally
+///   prefixed with `0x`.
+///
+/// Prefixed inputs must name the type encoded in the id. All inputs must hold
+/// a valid TTID of domain `T`.
+pub fn canonicalize<T: IdType>(s: &str) -> Result<String, ParseTtidError> {
+    let (type_name, raw) = decode_any(s)?;
+    let ttid = raw.typed::<T>()?;
+
+    if let Some(type_name) = type_name {
+        let ty = T::from_type_name(type_name).ok_or(ParseTtidError::UnknownTypeName)?;
+        if ty.to_type_id() != ttid.type_id() {
+            return Err(ParseTtidError::TypeMismatch);
+        }
+    }
+
+    Ok(ttid.to_string())
+}
+
+/// Whether two id strings refer to the same entity, with each string in any
+/// form accepted by [`canonicalize`].
+///
+/// Type names are resolved through `registry`, so ids from systems with
+/// different conventions can be joined without a compile-time domain. Both
+/// ids must be valid and of a type known by `registry`.
+pub fn same_entity(a: &str, b: &str, registry: &TypeRegistry) -> Result<bool, ParseTtidError> {
+    Ok(resolve_dyn(a, registry)? == resolve_dyn(b, registry)?)
+}
+
+/// Decode any accepted form into a [`DynTtid`] of `registry`.
+fn resolve_dyn(s: &str, registry: &TypeRegistry) -> Result<DynTtid, ParseTtidError> {
+    let (type_name, raw) = decode_any(s)?;
+    let id = DynTtid::from_raw(raw, registry)?;
+
+    if let Some(type_name) = type_name {
+        let type_id = registry
+            .id_of(type_name)
+            .ok_or(ParseTtidError::UnknownTypeName)?;
+        if type_id != id.type_id() {
+            return Err(ParseTtidError::TypeMismatch);
+        }
+    }
+
+    Ok(id)
+}
+
+/// Decode any form accepted by [`canonicalize`] into the type name prefix, if
+/// present, and the validated raw id.
+pub(crate) fn decode_any(s: &str) -> Result<(Option<&str>, RawTtid), ParseTtidError> {
+    let s = s.trim();
+
+    if let Some((type_name, suffix)) = s.split_once('_') {
+        let uuid = match decode_typeid_suffix(suffix) {
+            Some(uuid) => uuid,
+            None => ShortUuid::parse_str(suffix)
+                .map_err(|_| ParseTtidError::InvalidShortUuid)?
+                .to_uuid(),
+        };
+        return Ok((Some(type_name), RawTtid::from_uuid(uuid)?));
+    }
+
+    let uuid = match decode_typeid_suffix(s) {
+        Some(uuid) => uuid,
+        None => {
+            let hex = s
+                .strip_prefix("0x")
+                .or_else(|| s.strip_prefix("0X"))
+                .unwrap_or(s);
+            Uuid::try_parse(hex).map_err(|_| ParseTtidError::InvalidFormat)?
+        }
+    };
+    Ok((None, RawTtid::from_uuid(uuid)?))
+}
+
+/// Decode a TypeID suffix, `None` if `suffix` is not one.
+fn decode_typeid_suffix(suffix: &str) -> Option<Uuid> {
+    let bytes = suffix.as_bytes();
+    // 26 digits hold 130 bits, the first one may only use the low 3.
+    if bytes.len() != TYPEID_SUFFIX_LEN || bytes[0] > b'7' {
+        return None;
+    }
+
+    let mut value = 0u128;
+    for byte in bytes {
+        let digit = TYPEID_ALPHABET.iter().position(|c| c == byte)?;
+        value = (value << 5) | digit as u128;
+    }
+    Some(Uuid::from_u128(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+    use crate::{Ttid, TtidError};
+
+    /// Encode as TypeID suffix, the inverse of `decode_typeid_suffix`.
+    fn typeid_suffix(uuid: Uuid) -> String {
+        let value = uuid.as_u128();
+        (0..TYPEID_SUFFIX_LEN)
+            .rev()
+            .map(|index| TYPEID_ALPHABET[(value >> (index * 5)) as usize & 31] as char)
+            .collect()
+    }
+
+    #[test]
+    fn normalizes_all_input_forms() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+        let canonical = id.to_string();
+        let uuid = id.as_uuid();
+
+        let inputs = [
+            canonical.clone(),
+            format!("  {canonical}\n"),
+            uuid.hyphenated().to_string(),
+            uuid.hyphenated().to_string().to_uppercase(),
+            uuid.braced().to_string(),
+            uuid.urn().to_string(),
+            uuid.simple().to_string(),
+            format!("0x{}", uuid.simple()),
+            format!("user_{}", typeid_suffix(uuid)),
+            typeid_suffix(uuid),
+        ];
+        for input in inputs {
+            assert_eq!(
+                canonicalize::<MyType>(&input).unwrap(),
+                canonical,
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_mismatches_and_garbage() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+        let suffix = typeid_suffix(id.as_uuid());
+
+        assert_eq!(
+            canonicalize::<MyType>(&format!("user_{suffix}")),
+            Err(ParseTtidError::TypeMismatch)
+        );
+        assert_eq!(
+            canonicalize::<MyType>(&format!("nope_{}", id.short_uuid())),
+            Err(ParseTtidError::UnknownTypeName)
+        );
+        assert!(matches!(
+            canonicalize::<NarrowType>(&id.as_uuid().to_string()),
+            Err(ParseTtidError::Ttid(_))
+        ));
+        assert!(matches!(
+            canonicalize::<MyType>(&Uuid::nil().to_string()),
+            Err(ParseTtidError::Ttid(_))
+        ));
+        assert_eq!(
+            canonicalize::<MyType>("not an id"),
+            Err(ParseTtidError::InvalidFormat)
+        );
+        assert_eq!(
+            canonicalize::<MyType>("user_0OIl"),
+            Err(ParseTtidError::InvalidShortUuid)
+        );
+    }
+
+    #[test]
+    fn same_entity_across_formats() {
+        let registry = TypeRegistry::new([(1, "user"), (777, "session")]).unwrap();
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+        let other = id.with_randomness(43);
+
+        let text = id.to_string();
+        let hyphenated = id.as_uuid().to_string();
+        let typeid = format!("user_{}", typeid_suffix(id.as_uuid()));
+        assert!(same_entity(&text, &hyphenated, &registry).unwrap());
+        assert!(same_entity(&typeid, &hyphenated, &registry).unwrap());
+        assert!(!same_entity(&text, &other.as_uuid().to_string(), &registry).unwrap());
+
+        let session = id.with_type(MyType::Session).unwrap();
+        assert!(!same_entity(&text, &session.to_string(), &registry).unwrap());
+
+        let org = id.with_type(MyType::Org).unwrap();
+        assert_eq!(
+            same_entity(&text, &org.as_uuid().to_string(), &registry),
+            Err(ParseTtidError::Ttid(TtidError::UnknownTypeId(2)))
+        );
+        assert_eq!(
+            same_entity(&format!("session_{}", id.short_uuid()), &text, &registry),
+            Err(ParseTtidError::TypeMismatch)
+        );
+    }
+}
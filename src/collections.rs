@@ -0,0 +1,303 @@
+use crate::slice::sort_by_time;
+use crate::{CodecError, IdType, Ttid, codec};
+
+/// Sorted, deduplicated set of TTIDs with a compact binary form.
+///
+/// Ids are kept in binary UUID order, which is time order. Meant for
+/// persisting large id lists such as fanout lists or ACLs: [`Self::to_bytes`]
+/// uses the [`codec`](crate::codec) form, about 10 bytes per id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TtidSet<T: IdType> {
+    ids: Vec<Ttid<T>>,
+}
+
+impl<T: IdType> TtidSet<T> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self { ids: Vec::new() }
+    }
+
+    /// Number of ids in the set.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the set contains no ids.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Insert an id, returning whether it was not present yet.
+    pub fn insert(&mut self, id: Ttid<T>) -> bool {
+        match self.position(&id) {
+            Ok(_) => false,
+            Err(index) => {
+                self.ids.insert(index, id);
+                true
+            }
+        }
+    }
+
+    /// Remove an id, returning whether it was present.
+    pub fn remove(&mut self, id: &Ttid<T>) -> bool {
+        match self.position(id) {
+            Ok(index) => {
+                self.ids.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the set contains `id`.
+    pub fn contains(&self, id: &Ttid<T>) -> bool {
+        self.position(id).is_ok()
+    }
+
+    /// Iterate in time order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Ttid<T>> {
+        self.ids.iter()
+    }
+
+    /// Borrow the ids in time order.
+    pub fn as_slice(&self) -> &[Ttid<T>] {
+        &self.ids
+    }
+
+    /// Return the ids in time order.
+    pub fn into_vec(self) -> Vec<Ttid<T>> {
+        self.ids
+    }
+
+    /// Encode in the compact binary form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::compress_sorted(&self.ids)
+    }
+
+    /// Decode the compact binary form, produced by [`Self::to_bytes`] or
+    /// [`TtidVec::to_bytes`].
+    ///
+    /// Unsorted input and duplicates are accepted and normalized.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CodecError> {
+        Ok(codec::decompress(bytes)?.into_iter().collect())
+    }
+
+    fn position(&self, id: &Ttid<T>) -> Result<usize, usize> {
+        self.ids
+            .binary_search_by_key(&id.as_uuid(), |id| id.as_uuid())
+    }
+}
+
+impl<T: IdType> Default for TtidSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: IdType> FromIterator<Ttid<T>> for TtidSet<T> {
+    fn from_iter<I: IntoIterator<Item = Ttid<T>>>(iter: I) -> Self {
+        let mut ids: Vec<_> = iter.into_iter().collect();
+        sort_by_time(&mut ids);
+        ids.dedup_by_key(|id| id.as_uuid());
+        Self { ids }
+    }
+}
+
+impl<T: IdType> Extend<Ttid<T>> for TtidSet<T> {
+    fn extend<I: IntoIterator<Item = Ttid<T>>>(&mut self, iter: I) {
+        self.ids.extend(iter);
+        sort_by_time(&mut self.ids);
+        self.ids.dedup_by_key(|id| id.as_uuid());
+    }
+}
+
+impl<T: IdType> IntoIterator for TtidSet<T> {
+    type Item = Ttid<T>;
+    type IntoIter = std::vec::IntoIter<Ttid<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.into_iter()
+    }
+}
+
+impl<'a, T: IdType> IntoIterator for &'a TtidSet<T> {
+    type Item = &'a Ttid<T>;
+    type IntoIter = std::slice::Iter<'a, Ttid<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.iter()
+    }
+}
+
+/// List of TTIDs in insertion order with a compact binary form.
+///
+/// Unlike [`TtidSet`], order and duplicates are preserved. The binary form is
+/// the same, it is smallest when the ids are roughly in time order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TtidVec<T: IdType> {
+    ids: Vec<Ttid<T>>,
+}
+
+impl<T: IdType> TtidVec<T> {
+    /// Create an empty list.
+    pub fn new() -> Self {
+        Self { ids: Vec::new() }
+    }
+
+    /// Number of ids in the list.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the list contains no ids.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Append an id.
+    pub fn push(&mut self, id: Ttid<T>) {
+        self.ids.push(id);
+    }
+
+    /// Iterate in list order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Ttid<T>> {
+        self.ids.iter()
+    }
+
+    /// Borrow the ids in list order.
+    pub fn as_slice(&self) -> &[Ttid<T>] {
+        &self.ids
+    }
+
+    /// Return the ids in list order.
+    pub fn into_vec(self) -> Vec<Ttid<T>> {
+        self.ids
+    }
+
+    /// Encode in the compact binary form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        codec::compress_sorted(&self.ids)
+    }
+
+    /// Decode the compact binary form, produced by [`Self::to_bytes`] or
+    /// [`TtidSet::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CodecError> {
+        Ok(Self {
+            ids: codec::decompress(bytes)?,
+        })
+    }
+}
+
+impl<T: IdType> Default for TtidVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: IdType> From<Vec<Ttid<T>>> for TtidVec<T> {
+    fn from(ids: Vec<Ttid<T>>) -> Self {
+        Self { ids }
+    }
+}
+
+impl<T: IdType> From<TtidVec<T>> for Vec<Ttid<T>> {
+    fn from(value: TtidVec<T>) -> Self {
+        value.ids
+    }
+}
+
+impl<T: IdType> FromIterator<Ttid<T>> for TtidVec<T> {
+    fn from_iter<I: IntoIterator<Item = Ttid<T>>>(iter: I) -> Self {
+        Self {
+            ids: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: IdType> Extend<Ttid<T>> for TtidVec<T> {
+    fn extend<I: IntoIterator<Item = Ttid<T>>>(&mut self, iter: I) {
+        self.ids.extend(iter);
+    }
+}
+
+impl<T: IdType> IntoIterator for TtidVec<T> {
+    type Item = Ttid<T>;
+    type IntoIter = std::vec::IntoIter<Ttid<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.into_iter()
+    }
+}
+
+impl<'a, T: IdType> IntoIterator for &'a TtidVec<T> {
+    type Item = &'a Ttid<T>;
+    type IntoIter = std::slice::Iter<'a, Ttid<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TtidError;
+    use crate::tests::{MyType, NarrowType};
+
+    fn id(ts: u64, rand: u64) -> Ttid<MyType> {
+        Ttid::from_parts(ts, MyType::User, rand).unwrap()
+    }
+
+    #[test]
+    fn set_keeps_ids_sorted_and_unique() {
+        let mut set: TtidSet<MyType> = [id(3, 1), id(1, 1), id(3, 1)].into_iter().collect();
+        assert_eq!(set.as_slice(), [id(1, 1), id(3, 1)]);
+
+        assert!(set.insert(id(2, 1)));
+        assert!(!set.insert(id(2, 1)));
+        assert!(set.contains(&id(2, 1)));
+        assert_eq!(set.as_slice(), [id(1, 1), id(2, 1), id(3, 1)]);
+
+        assert!(set.remove(&id(1, 1)));
+        assert!(!set.remove(&id(1, 1)));
+        set.extend([id(0, 1), id(3, 1)]);
+        assert_eq!(set.into_vec(), vec![id(0, 1), id(2, 1), id(3, 1)]);
+    }
+
+    #[test]
+    fn binary_form_roundtrips_and_is_compact() {
+        let set: TtidSet<MyType> = (0..1_000)
+            .map(|n| {
+                id(
+                    1_700_000_000_000 + n * 37,
+                    n.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+                )
+            })
+            .collect();
+
+        let bytes = set.to_bytes();
+        assert!(bytes.len() < set.len() * 11);
+        assert_eq!(TtidSet::from_bytes(&bytes).unwrap(), set);
+
+        let list: TtidVec<MyType> = vec![id(5, 1), id(1, 2), id(5, 1)].into();
+        let decoded = TtidVec::<MyType>::from_bytes(&list.to_bytes()).unwrap();
+        assert_eq!(decoded, list);
+        assert_eq!(
+            TtidSet::<MyType>::from_bytes(&list.to_bytes())
+                .unwrap()
+                .into_vec(),
+            vec![id(1, 2), id(5, 1)]
+        );
+    }
+
+    #[test]
+    fn binary_form_validates_domain() {
+        let org = Ttid::<MyType>::from_parts(1, MyType::Org, 1).unwrap();
+        let bytes = TtidVec::from(vec![org]).to_bytes();
+
+        assert_eq!(
+            TtidSet::<NarrowType>::from_bytes(&bytes),
+            Err(CodecError::Ttid(TtidError::UnknownTypeId(2)))
+        );
+    }
+}
@@ -8,78 +8,134 @@ pub(super) const TIMESTAMP_MAX: u64 = (1u64 << TIMESTAMP_BITS) - 1;
 pub(super) const TYPE_ID_MAX: u16 = u16::MAX;
 pub(super) const RANDOM_MASK: u64 = (1u64 << RANDOM_BITS) - 1;
 
-const PAYLOAD_BITS: u32 = TIMESTAMP_BITS + TYPE_BITS + RANDOM_BITS;
+// The payload fills the UUID bits around the fixed version (`76..80`) and
+// variant (`62..64`) fields, split into three runs from the top.
+const HIGH_SHIFT: u32 = 80;
+const MID_SHIFT: u32 = 64;
+const MID_BITS: u32 = 12;
+const LOW_BITS: u32 = 62;
+const MID_MASK: u128 = (1 << MID_BITS) - 1;
+const LOW_MASK: u128 = (1 << LOW_BITS) - 1;
+
+/// UUIDv8 version field (`1000`).
+const VERSION_BITS: u128 = 0b1000 << 76;
+const VERSION_MASK: u128 = 0b1111 << 76;
+/// RFC variant bits (`10`).
+const VARIANT_BITS: u128 = 0b10 << 62;
+const VARIANT_MASK: u128 = 0b11 << 62;
+
+/// Pack components into a payload. `timestamp_ms` must be range-checked by the
+/// caller, `randomness` is masked to the low 58 bits.
+pub(super) fn pack_payload(timestamp_ms: u64, type_id: u16, randomness: u64) -> u128 {
+    ((timestamp_ms as u128) << (TYPE_BITS + RANDOM_BITS))
+        | ((type_id as u128) << RANDOM_BITS)
+        | ((randomness & RANDOM_MASK) as u128)
+}
 
 pub(super) fn encode_payload_to_uuid(payload: u128) -> Uuid {
-    let mut bytes = [0u8; 16];
-
-    let mut payload_bit_idx = PAYLOAD_BITS as i32 - 1;
-    for uuid_bit_pos in (0..128).rev() {
-        if is_fixed_uuid_bit(uuid_bit_pos) {
-            continue;
-        }
-
-        let bit = ((payload >> payload_bit_idx) & 1) as u8;
-        set_bit(&mut bytes, uuid_bit_pos, bit);
-        payload_bit_idx -= 1;
-    }
-
-    // UUIDv8 version field (`1000`)
-    set_bit(&mut bytes, 79, 1);
-    set_bit(&mut bytes, 78, 0);
-    set_bit(&mut bytes, 77, 0);
-    set_bit(&mut bytes, 76, 0);
+    let high = (payload >> (MID_BITS + LOW_BITS)) << HIGH_SHIFT;
+    let mid = ((payload >> LOW_BITS) & MID_MASK) << MID_SHIFT;
+    let low = payload & LOW_MASK;
+    Uuid::from_u128(high | mid | low | VERSION_BITS | VARIANT_BITS)
+}
 
-    // RFC variant bits (`10`)
-    set_bit(&mut bytes, 63, 1);
-    set_bit(&mut bytes, 62, 0);
+pub(super) fn decode_payload_from_uuid(uuid: Uuid) -> Option<u128> {
+    let value = uuid.as_u128();
+    let valid = value & VERSION_MASK == VERSION_BITS && value & VARIANT_MASK == VARIANT_BITS;
+    valid.then(|| payload_of(uuid))
+}
 
-    Uuid::from_bytes(bytes)
+/// Extract the payload without checking the version and variant fields, for
+/// UUIDs validated on construction.
+pub(super) fn payload_of(uuid: Uuid) -> u128 {
+    let value = uuid.as_u128();
+    ((value >> HIGH_SHIFT) << (MID_BITS + LOW_BITS))
+        | (((value >> MID_SHIFT) & MID_MASK) << LOW_BITS)
+        | (value & LOW_MASK)
 }
 
-pub(super) fn decode_payload_from_uuid(uuid: Uuid) -> Option<u128> {
-    let bytes = uuid.as_bytes();
+/// Assert the layout invariants for `payload`: encoding sets the UUIDv8
+/// version and RFC variant fields, and decoding is the exact inverse of
+/// encoding on the 122 payload bits.
+///
+/// Shared by the property tests and the Kani harness, and public as
+/// [`crate::testing::codec_invariants`] so forks changing the layout can run
+/// it from their own property tests.
+#[cfg(any(test, kani, feature = "testing"))]
+pub fn codec_invariants(payload: u128) {
+    let payload = payload & ((1 << (TIMESTAMP_BITS + TYPE_BITS + RANDOM_BITS)) - 1);
+    let uuid = encode_payload_to_uuid(payload);
+
+    assert_eq!(uuid.get_version_num(), 8);
+    assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+    assert_eq!(decode_payload_from_uuid(uuid), Some(payload));
+    assert_eq!(payload_of(uuid), payload);
+}
 
-    if !is_valid_ttid_uuid(bytes) {
-        return None;
+#[cfg(kani)]
+mod verification {
+    #[kani::proof]
+    fn codec_roundtrips_for_all_payloads() {
+        super::codec_invariants(kani::any());
     }
 
-    let mut payload = 0u128;
-    for uuid_bit_pos in (0..128).rev() {
-        if is_fixed_uuid_bit(uuid_bit_pos) {
-            continue;
+    #[kani::proof]
+    fn decode_accepts_exactly_ttid_uuids() {
+        let value: u128 = kani::any();
+        let uuid = uuid::Uuid::from_u128(value);
+        let is_ttid = uuid.get_version_num() == 8 && uuid.get_variant() == uuid::Variant::RFC4122;
+
+        match super::decode_payload_from_uuid(uuid) {
+            Some(payload) => {
+                assert!(is_ttid);
+                assert_eq!(super::encode_payload_to_uuid(payload), uuid);
+            }
+            None => assert!(!is_ttid),
         }
-
-        payload <<= 1;
-        payload |= get_bit(bytes, uuid_bit_pos) as u128;
     }
-
-    Some(payload)
 }
 
-fn is_valid_ttid_uuid(bytes: &[u8; 16]) -> bool {
-    let version_ok = (bytes[6] >> 4) == 0b1000;
-    let variant_ok = (bytes[8] & 0b1100_0000) == 0b1000_0000;
-    version_ok && variant_ok
-}
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use uuid::{Uuid, Variant};
 
-fn is_fixed_uuid_bit(bit_pos: usize) -> bool {
-    matches!(bit_pos, 79 | 78 | 77 | 76 | 63 | 62)
-}
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn codec_roundtrips_for_all_payloads(payload in any::<u128>()) {
+            codec_invariants(payload);
+        }
 
-fn set_bit(bytes: &mut [u8; 16], bit_pos: usize, value: u8) {
-    let byte_idx = 15 - (bit_pos / 8);
-    let bit_idx = bit_pos % 8;
+        #[test]
+        fn decode_accepts_exactly_ttid_uuids(value in any::<u128>()) {
+            let uuid = Uuid::from_u128(value);
+            let is_ttid = uuid.get_version_num() == 8 && uuid.get_variant() == Variant::RFC4122;
+
+            match decode_payload_from_uuid(uuid) {
+                Some(payload) => {
+                    prop_assert!(is_ttid);
+                    prop_assert_eq!(encode_payload_to_uuid(payload), uuid);
+                }
+                None => prop_assert!(!is_ttid),
+            }
+        }
 
-    if value == 0 {
-        bytes[byte_idx] &= !(1 << bit_idx);
-    } else {
-        bytes[byte_idx] |= 1 << bit_idx;
+        #[test]
+        fn fields_land_in_their_payload_bits(
+            timestamp_ms in 0..=TIMESTAMP_MAX,
+            type_id in any::<u16>(),
+            randomness in any::<u64>(),
+        ) {
+            let payload = pack_payload(timestamp_ms, type_id, randomness);
+            codec_invariants(payload);
+
+            let uuid = encode_payload_to_uuid(payload);
+            // Timestamp-first: the top 48 UUID bits are the timestamp.
+            prop_assert_eq!((uuid.as_u128() >> 80) as u64, timestamp_ms);
+            prop_assert_eq!(payload as u64 & RANDOM_MASK, randomness & RANDOM_MASK);
+            prop_assert_eq!((payload >> RANDOM_BITS) as u16, type_id);
+        }
     }
 }
-
-fn get_bit(bytes: &[u8; 16], bit_pos: usize) -> u8 {
-    let byte_idx = 15 - (bit_pos / 8);
-    let bit_idx = bit_pos % 8;
-    (bytes[byte_idx] >> bit_idx) & 1
-}
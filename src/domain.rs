@@ -0,0 +1,599 @@
+//! Composing large type domains from smaller [`IdType`] enums.
+//!
+//! Codebases with many id types can keep one enum per module or crate and
+//! compose them into a single domain, each part owning a range of type ids.
+//! A part's local type id `n` maps to `first + n` in the composed domain.
+//!
+//! [`compose_domain!`](crate::compose_domain) generates the composed enum and
+//! its [`IdType`] implementation, checking at compile time with
+//! a [`Builder`] that the ranges do not overlap or contain
+//! [`UNTYPED_TYPE_ID`], that each part's type ids fit its range, and that
+//! type names are unique across parts:
+//!
+//! ```
+//! use ttid::Ttid;
+//!
+//! ttid::id_type! {
+//!     #[derive(Debug, PartialEq, Eq)]
+//!     pub enum BillingType {
+//!         Invoice = 1 => "invoice",
+//!         Payment = 2 => "payment",
+//!     }
+//! }
+//!
+//! ttid::id_type! {
+//!     #[derive(Debug, PartialEq, Eq)]
+//!     pub enum AuthType {
+//!         User = 1 => "user",
+//!         Session = 2 => "session",
+//!     }
+//! }
+//!
+//! ttid::compose_domain! {
+//!     #[derive(Debug, PartialEq, Eq)]
+//!     pub enum AppType {
+//!         Billing(BillingType) = 0x0100..=0x01ff,
+//!         Auth(AuthType) = 0x0200..=0x02ff,
+//!     }
+//! }
+//!
+//! let id = Ttid::new(AppType::Auth(AuthType::Session)).unwrap();
+//! assert_eq!(id.type_id(), 0x0202);
+//! assert!(id.to_string().starts_with("session_"));
+//! assert_eq!(id.to_string().parse::<Ttid<AppType>>().unwrap(), id);
+//! ```
+//!
+//! Type ids and names are only checked for parts that declare
+//! [`TYPE_IDS`](crate::IdType::TYPE_IDS) and
+//! [`TYPE_NAMES`](crate::IdType::TYPE_NAMES), e.g. those declared with
+//! [`id_type!`](crate::id_type). Values of other parts whose local type id
+//! does not fit the range are rejected when parsing, and panic in
+//! `to_type_id`.
+//!
+//! For plugin architectures, crates register their range once with
+//! [`sub_domain!`](crate::sub_domain), and the application composes them
+//! without repeating ranges, see [`SubDomain`].
+
+use crate::id_type::str_eq;
+use crate::{IdType, UNTYPED_TYPE_ID};
+
+/// A part of a composed domain: an inclusive range of type ids and the type
+/// names of the part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Part {
+    first: u16,
+    last: u16,
+    type_names: &'static [&'static str],
+}
+
+impl Part {
+    /// First type id of the range, holding local type id `0`.
+    pub const fn first(&self) -> u16 {
+        self.first
+    }
+
+    /// Last type id of the range.
+    pub const fn last(&self) -> u16 {
+        self.last
+    }
+
+    /// Type names of the part.
+    pub const fn type_names(&self) -> &'static [&'static str] {
+        self.type_names
+    }
+
+    /// Type id in the composed domain of the part's local type id `local`.
+    ///
+    /// `None` if `local` is beyond the range.
+    pub const fn type_id(&self, local: u16) -> Option<u16> {
+        if local > self.last - self.first {
+            return None;
+        }
+        Some(self.first + local)
+    }
+
+    /// Whether `type_id` is in the range.
+    pub const fn contains(&self, type_id: u16) -> bool {
+        self.first <= type_id && type_id <= self.last
+    }
+}
+
+/// Const builder of a composed [`Domain`] of `N` parts.
+///
+/// Meant to be evaluated in a `const` item: invalid compositions panic in
+/// [`Self::part`] and [`Self::build`], which fails compilation.
+#[derive(Clone, Copy, Debug)]
+pub struct Builder<const N: usize> {
+    parts: [Part; N],
+    len: usize,
+}
+
+impl<const N: usize> Builder<N> {
+    /// Builder without parts.
+    pub const fn new() -> Self {
+        Self {
+            parts: [Part {
+                first: 0,
+                last: 0,
+                type_names: &[],
+            }; N],
+            len: 0,
+        }
+    }
+
+    /// Add a part owning the type ids `first..=last`, with the local
+    /// `type_ids` and `type_names` of its domain, either may be empty if
+    /// unknown.
+    ///
+    /// Panics if all `N` parts were added already, the range is empty or
+    /// contains [`UNTYPED_TYPE_ID`], or a local type id does not fit the
+    /// range.
+    pub const fn part(
+        mut self,
+        first: u16,
+        last: u16,
+        type_ids: &[u16],
+        type_names: &'static [&'static str],
+    ) -> Self {
+        assert!(self.len < N, "more parts than declared");
+        assert!(first <= last, "empty type id range");
+        assert!(
+            first > UNTYPED_TYPE_ID,
+            "type id range contains the untyped type id"
+        );
+        let mut i = 0;
+        while i < type_ids.len() {
+            assert!(
+                type_ids[i] <= last - first,
+                "local type id beyond the range of its part"
+            );
+            i += 1;
+        }
+        self.parts[self.len] = Part {
+            first,
+            last,
+            type_names,
+        };
+        self.len += 1;
+        self
+    }
+
+    /// Check the parts and build the domain.
+    ///
+    /// Panics if fewer than `N` parts were added, ranges overlap or a type
+    /// name occurs in more than one part.
+    pub const fn build(self) -> Domain<N> {
+        assert!(self.len == N, "fewer parts than declared");
+        let mut i = 0;
+        while i < N {
+            let mut j = i + 1;
+            while j < N {
+                let (a, b) = (&self.parts[i], &self.parts[j]);
+                assert!(
+                    a.last < b.first || b.last < a.first,
+                    "overlapping type id ranges"
+                );
+                assert!(
+                    !shares_name(a.type_names, b.type_names),
+                    "type name used by two parts"
+                );
+                j += 1;
+            }
+            i += 1;
+        }
+        Domain { parts: self.parts }
+    }
+}
+
+impl<const N: usize> Default for Builder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A checked composition of `N` parts, built with [`Builder`].
+#[derive(Clone, Copy, Debug)]
+pub struct Domain<const N: usize> {
+    parts: [Part; N],
+}
+
+impl<const N: usize> Domain<N> {
+    /// The parts, in the order they were added.
+    pub const fn parts(&self) -> &[Part; N] {
+        &self.parts
+    }
+
+    /// Index of the part owning `type_id`.
+    pub const fn part_of(&self, type_id: u16) -> Option<usize> {
+        let mut i = 0;
+        while i < N {
+            if self.parts[i].contains(type_id) {
+                return Some(i);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+/// A domain owning a registered range of the type ids of a federated domain.
+///
+/// Implemented with [`sub_domain!`](crate::sub_domain) by the crate defining
+/// the domain, so an application can compose independent crates with
+/// [`compose_domain!`](crate::compose_domain) without repeating their ranges.
+/// The composition fails to compile if registered ranges overlap.
+pub trait SubDomain: IdType {
+    /// First type id of the range, holding local type id `0`.
+    const FIRST: u16;
+
+    /// Last type id of the range.
+    const LAST: u16;
+}
+
+/// Register the range of type ids `first..=last` for a domain, implementing
+/// [`SubDomain`].
+///
+/// The domain must declare its [`TYPE_IDS`](crate::IdType::TYPE_IDS), e.g.
+/// by being declared with [`id_type!`](crate::id_type). Registration fails to
+/// compile if they are missing or do not fit the range.
+///
+/// ```
+/// ttid::id_type! {
+///     pub enum PluginType {
+///         Job = 1 => "job",
+///     }
+/// }
+///
+/// ttid::sub_domain!(PluginType, 0x8000..=0x80ff);
+///
+/// ttid::compose_domain! {
+///     pub enum AppType {
+///         Plugin(PluginType),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! sub_domain {
+    ($domain:ty, $first:literal ..= $last:literal) => {
+        impl $crate::domain::SubDomain for $domain {
+            const FIRST: u16 = $first;
+            const LAST: u16 = $last;
+        }
+
+        const _: () = assert!(
+            !<$domain as $crate::IdType>::TYPE_IDS.is_empty(),
+            "sub_domain! requires the domain to declare its TYPE_IDS",
+        );
+
+        const _: $crate::domain::Domain<1> = $crate::domain::Builder::new()
+            .part(
+                $first,
+                $last,
+                <$domain as $crate::IdType>::TYPE_IDS,
+                <$domain as $crate::IdType>::TYPE_NAMES,
+            )
+            .build();
+    };
+}
+
+/// Whether two name lists share a name.
+const fn shares_name(a: &[&str], b: &[&str]) -> bool {
+    let mut i = 0;
+    while i < a.len() {
+        let mut j = 0;
+        while j < b.len() {
+            if str_eq(a[i], b[j]) {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Declare an enum composing several [`IdType`] enums and
+/// implement [`IdType`] for it, see the
+/// [module docs](crate::domain).
+///
+/// Each variant is written `Variant(PartType) = <first>..=<last>` and holds a
+/// value of the part's domain. Local type ids of a part must fit into its
+/// range, which is checked at compile time for parts declaring
+/// [`TYPE_IDS`](crate::IdType::TYPE_IDS).
+///
+/// Without ranges, i.e. `Variant(PartType)` for every variant, the ranges
+/// registered by the parts with [`sub_domain!`](crate::sub_domain) are used.
+#[macro_export]
+macro_rules! compose_domain {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident($part:ty) = $first:literal ..= $last:literal
+            ),+ $(,)?
+        }
+    ) => {
+        $crate::compose_domain! {
+            @impl $(#[$meta])* $vis enum $name {
+                $($(#[$variant_meta])* $variant($part) = [$first, $last],)+
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident($part:ty)
+            ),+ $(,)?
+        }
+    ) => {
+        $crate::compose_domain! {
+            @impl $(#[$meta])* $vis enum $name {
+                $(
+                    $(#[$variant_meta])* $variant($part) = [
+                        <$part as $crate::domain::SubDomain>::FIRST,
+                        <$part as $crate::domain::SubDomain>::LAST
+                    ],
+                )+
+            }
+        }
+    };
+    (
+        @impl $(#[$meta:meta])* $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident($part:ty) = [$first:expr, $last:expr],)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant($part),
+            )+
+        }
+
+        const _: $crate::domain::Domain<{ [$(stringify!($variant)),+].len() }> =
+            $crate::domain::Builder::new()
+                $(.part(
+                    $first,
+                    $last,
+                    <$part as $crate::IdType>::TYPE_IDS,
+                    <$part as $crate::IdType>::TYPE_NAMES,
+                ))+
+                .build();
+
+        impl $crate::IdType for $name {
+            const MAX_TYPE_NAME_LEN: usize = {
+                let mut max = 0;
+                $(
+                    if <$part as $crate::IdType>::MAX_TYPE_NAME_LEN > max {
+                        max = <$part as $crate::IdType>::MAX_TYPE_NAME_LEN;
+                    }
+                )+
+                max
+            };
+
+            const TYPE_NAMES: &'static [&'static str] = &{
+                const LEN: usize = 0 $(+ <$part as $crate::IdType>::TYPE_NAMES.len())+;
+                let mut names = [""; LEN];
+                let mut len = 0;
+                $(
+                    let mut i = 0;
+                    while i < <$part as $crate::IdType>::TYPE_NAMES.len() {
+                        names[len] = <$part as $crate::IdType>::TYPE_NAMES[i];
+                        len += 1;
+                        i += 1;
+                    }
+                )+
+                names
+            };
+
+            const TYPE_IDS: &'static [u16] = &{
+                // Unknown unless every part lists its type ids.
+                const KNOWN: bool = true $(&& !<$part as $crate::IdType>::TYPE_IDS.is_empty())+;
+                const LEN: usize = if KNOWN {
+                    0 $(+ <$part as $crate::IdType>::TYPE_IDS.len())+
+                } else {
+                    0
+                };
+                let mut ids = [0; LEN];
+                let mut len = 0;
+                $(
+                    let mut i = 0;
+                    while len < LEN && i < <$part as $crate::IdType>::TYPE_IDS.len() {
+                        ids[len] = $first + <$part as $crate::IdType>::TYPE_IDS[i];
+                        len += 1;
+                        i += 1;
+                    }
+                )+
+                ids
+            };
+
+            fn to_type_id(self) -> u16 {
+                match self {
+                    $(
+                        Self::$variant(ty) => {
+                            const FIRST: u16 = $first;
+                            const LAST: u16 = $last;
+                            let local = $crate::IdType::to_type_id(ty);
+                            assert!(
+                                local <= LAST - FIRST,
+                                "local type id beyond the range of its part",
+                            );
+                            FIRST + local
+                        }
+                    )+
+                }
+            }
+
+            fn from_type_id(id: u16) -> ::core::option::Option<Self> {
+                $({
+                    const FIRST: u16 = $first;
+                    const LAST: u16 = $last;
+                    if (FIRST..=LAST).contains(&id) {
+                        return <$part as $crate::IdType>::from_type_id(id - FIRST)
+                            .map(Self::$variant);
+                    }
+                })+
+                ::core::option::Option::None
+            }
+
+            fn as_type_name(self) -> &'static str {
+                match self {
+                    $(Self::$variant(ty) => $crate::IdType::as_type_name(ty),)+
+                }
+            }
+
+            fn from_type_name(name: &str) -> ::core::option::Option<Self> {
+                $({
+                    const FIRST: u16 = $first;
+                    const LAST: u16 = $last;
+                    if let ::core::option::Option::Some(ty) =
+                        <$part as $crate::IdType>::from_type_name(name)
+                    {
+                        // Parsing must not reach the panic in `to_type_id`.
+                        return ($crate::IdType::to_type_id(ty) <= LAST - FIRST)
+                            .then_some(Self::$variant(ty));
+                    }
+                })+
+                ::core::option::Option::None
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::NarrowType;
+    use crate::{IdType, Ttid};
+
+    crate::id_type! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Wide {
+            Account = 1 => "account",
+            Invoice = 2 => "invoice",
+            Last = 0xefff => "last",
+        }
+    }
+
+    crate::compose_domain! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Composed {
+            Narrow(NarrowType) = 1..=0x0fff,
+            Wide(Wide) = 0x1000..=0xffff,
+        }
+    }
+
+    #[test]
+    fn composed_domains_offset_their_parts() {
+        assert_eq!(Composed::Narrow(NarrowType::User).to_type_id(), 2);
+        assert_eq!(Composed::Wide(Wide::Invoice).to_type_id(), 0x1002);
+        assert_eq!(
+            Composed::from_type_id(0xffff),
+            Some(Composed::Wide(Wide::Last))
+        );
+        assert_eq!(Composed::from_type_id(0x0fff), None);
+        assert_eq!(Composed::TYPE_NAMES, Wide::TYPE_NAMES);
+        // `NarrowType` does not list its ids, so the composition does not.
+        assert!(Composed::TYPE_IDS.is_empty());
+        assert_eq!(
+            Composed::from_type_name("invoice"),
+            Some(Composed::Wide(Wide::Invoice))
+        );
+
+        let id = Ttid::from_parts(1_000, Composed::Wide(Wide::Last), 3).unwrap();
+        assert_eq!(id.to_string().parse::<Ttid<Composed>>().unwrap(), id);
+    }
+
+    crate::compose_domain! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Cramped {
+            Narrow(NarrowType) = 1..=1,
+            Wide(Wide) = 2..=0xf001,
+        }
+    }
+
+    #[test]
+    fn unchecked_parts_that_do_not_fit_are_not_parsed() {
+        assert_eq!(Cramped::from_type_name("user"), None);
+        let text = Ttid::from_parts(1_000, NarrowType::User, 3)
+            .unwrap()
+            .to_string();
+        assert!(text.parse::<Ttid<Cramped>>().is_err());
+    }
+
+    crate::id_type! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Plugin {
+            Job = 1 => "job",
+        }
+    }
+
+    crate::sub_domain!(Plugin, 0x8000..=0x80ff);
+
+    crate::id_type! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Core {
+            User = 1 => "user",
+            Org = 2 => "org",
+        }
+    }
+
+    crate::sub_domain!(Core, 0x0001..=0x7fff);
+
+    crate::compose_domain! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Federated {
+            Core(Core),
+            Plugin(Plugin),
+        }
+    }
+
+    #[test]
+    fn federated_domains_use_registered_ranges() {
+        assert_eq!(Federated::Plugin(Plugin::Job).to_type_id(), 0x8001);
+        assert_eq!(Federated::Core(Core::Org).to_type_id(), 3);
+        assert_eq!(Federated::TYPE_IDS, [2, 3, 0x8001]);
+        assert_eq!(
+            Federated::from_type_id(0x8001),
+            Some(Federated::Plugin(Plugin::Job))
+        );
+        assert_eq!(Federated::from_type_id(0x8100), None);
+        assert_eq!(
+            Federated::from_type_name("job"),
+            Some(Federated::Plugin(Plugin::Job))
+        );
+    }
+
+    #[test]
+    fn builder_checks_ranges() {
+        let domain = Builder::<2>::new()
+            .part(1, 9, &[8], &["a"])
+            .part(10, 19, &[], &["b"])
+            .build();
+        assert_eq!(domain.part_of(10), Some(1));
+        assert_eq!(domain.part_of(20), None);
+        assert_eq!(domain.parts()[0].type_id(8), Some(9));
+        assert_eq!(domain.parts()[0].type_id(9), None);
+
+        let overlapping = std::panic::catch_unwind(|| {
+            Builder::<2>::new()
+                .part(1, 10, &[], &[])
+                .part(10, 19, &[], &[])
+                .build()
+        });
+        assert!(overlapping.is_err());
+        let shared_name = std::panic::catch_unwind(|| {
+            Builder::<2>::new()
+                .part(1, 9, &[], &["a"])
+                .part(10, 19, &[], &["a"])
+                .build()
+        });
+        assert!(shared_name.is_err());
+        let too_wide = std::panic::catch_unwind(|| Builder::<1>::new().part(1, 9, &[9], &[]));
+        assert!(too_wide.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Self-describing binary envelope for ids embedded in opaque blobs.
+//!
+//! Layout:
+//!
+//! - [`ENVELOPE_MAGIC`], 2 bytes,
+//! - layout version, 1 byte, currently `1`,
+//! - the 16 big-endian UUID bytes.
+
+use crate::{EnvelopeError, IdType, RawTtid, Ttid};
+
+/// Leading bytes of every envelope.
+pub const ENVELOPE_MAGIC: [u8; 2] = [0xC7, 0x1D];
+
+/// Length in bytes of an envelope.
+pub const ENVELOPE_LEN: usize = ENVELOPE_MAGIC.len() + 1 + 16;
+
+/// Version of the TTID bit layout written into envelopes.
+const LAYOUT_VERSION: u8 = 1;
+
+impl RawTtid {
+    /// Wrap into a self-describing envelope, see [`Ttid::to_envelope`].
+    pub fn to_envelope(&self) -> [u8; ENVELOPE_LEN] {
+        let mut envelope = [0; ENVELOPE_LEN];
+        envelope[..2].copy_from_slice(&ENVELOPE_MAGIC);
+        envelope[2] = LAYOUT_VERSION;
+        envelope[3..].copy_from_slice(self.as_uuid().as_bytes());
+        envelope
+    }
+
+    /// Unwrap an envelope of any type, e.g. to detect whether a blob holds a
+    /// TTID at all.
+    pub fn from_envelope(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let (magic, rest) = bytes
+            .split_first_chunk::<2>()
+            .ok_or(EnvelopeError::NotAnEnvelope)?;
+        if *magic != ENVELOPE_MAGIC {
+            return Err(EnvelopeError::NotAnEnvelope);
+        }
+        let (&version, rest) = rest.split_first().ok_or(EnvelopeError::NotAnEnvelope)?;
+        if version != LAYOUT_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(version));
+        }
+        let bytes: [u8; 16] = rest.try_into().map_err(|_| EnvelopeError::NotAnEnvelope)?;
+        Ok(Self::from_bytes(bytes)?)
+    }
+}
+
+impl<T: IdType> Ttid<T> {
+    /// Wrap into a self-describing [`ENVELOPE_LEN`]-byte envelope: the
+    /// [`ENVELOPE_MAGIC`] bytes, the layout version and the 16 UUID bytes.
+    ///
+    /// Consumers without context can tell an envelope from other data with
+    /// [`Self::from_envelope`] or [`RawTtid::from_envelope`].
+    pub fn to_envelope(&self) -> [u8; ENVELOPE_LEN] {
+        RawTtid::from(*self).to_envelope()
+    }
+
+    /// Unwrap an envelope produced by [`Self::to_envelope`].
+    ///
+    /// `bytes` must be exactly one envelope.
+    pub fn from_envelope(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        Ok(RawTtid::from_envelope(bytes)?.typed()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TtidError;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn envelope_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+        let envelope = id.to_envelope();
+        assert_eq!(envelope.len(), 19);
+        assert_eq!(envelope[..3], [0xC7, 0x1D, 1]);
+        assert_eq!(envelope[3..], *id.as_uuid().as_bytes());
+
+        assert_eq!(Ttid::<MyType>::from_envelope(&envelope), Ok(id));
+        assert_eq!(RawTtid::from_envelope(&envelope), Ok(RawTtid::from(id)));
+        assert_eq!(
+            Ttid::<NarrowType>::from_envelope(&envelope),
+            Err(EnvelopeError::Ttid(TtidError::UnknownTypeId(777)))
+        );
+    }
+
+    #[test]
+    fn detects_foreign_data() {
+        let envelope = Ttid::<MyType>::from_parts(1_000, MyType::User, 1)
+            .unwrap()
+            .to_envelope();
+
+        assert_eq!(
+            RawTtid::from_envelope(&envelope[..18]),
+            Err(EnvelopeError::NotAnEnvelope)
+        );
+        assert_eq!(
+            RawTtid::from_envelope(&[envelope.as_slice(), &[0]].concat()),
+            Err(EnvelopeError::NotAnEnvelope)
+        );
+        assert_eq!(
+            RawTtid::from_envelope(&envelope[3..]),
+            Err(EnvelopeError::NotAnEnvelope)
+        );
+
+        let mut future = envelope;
+        future[2] = 2;
+        assert_eq!(
+            RawTtid::from_envelope(&future),
+            Err(EnvelopeError::UnsupportedVersion(2))
+        );
+
+        let mut corrupt = envelope;
+        corrupt[3 + 6] = 0;
+        assert_eq!(
+            RawTtid::from_envelope(&corrupt),
+            Err(EnvelopeError::Ttid(TtidError::InvalidUuid))
+        );
+    }
+}
@@ -9,6 +9,8 @@ pub enum TtidError {
     InvalidUuid,
     /// Type id decoded from UUID is not known by `T`.
     UnknownTypeId(u16),
+    /// Type id is `UNTYPED_TYPE_ID`, which typed domains must not use.
+    ReservedTypeId,
 }
 
 impl fmt::Display for TtidError {
@@ -22,6 +24,7 @@ impl fmt::Display for TtidError {
                     "uuid contains unknown type id for this IdType: {type_id}"
                 )
             }
+            Self::ReservedTypeId => f.write_str("type id 0 is reserved for untyped TTIDs"),
         }
     }
 }
@@ -64,3 +67,257 @@ impl From<TtidError> for ParseTtidError {
         Self::Ttid(value)
     }
 }
+
+/// Errors returned by [`validate_type_name`](crate::validate_type_name).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeNameError {
+    /// Type name is empty.
+    Empty,
+    /// Type name is longer than `TYPE_NAME_MAX_LEN` bytes.
+    TooLong(usize),
+    /// Type name contains a byte outside `[A-Za-z0-9-]`.
+    InvalidCharacter {
+        /// Byte offset of the offending character.
+        index: usize,
+    },
+}
+
+impl fmt::Display for TypeNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("type name is empty"),
+            Self::TooLong(len) => write!(f, "type name is too long: {len} bytes"),
+            Self::InvalidCharacter { index } => {
+                write!(f, "type name contains invalid character at byte {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TypeNameError {}
+
+/// Errors returned when building a [`TypeRegistry`](crate::registry::TypeRegistry).
+///
+/// Lists every problem found, not just the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryError {
+    /// All conflicting or invalid entries, in input order.
+    pub conflicts: Vec<RegistryConflict>,
+}
+
+/// Single problem found while building a registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryConflict {
+    /// Type id is registered more than once.
+    DuplicateId {
+        /// The duplicated type id.
+        id: u16,
+        /// First registered name and the conflicting name.
+        names: (String, String),
+    },
+    /// Type name is registered more than once.
+    DuplicateName {
+        /// The duplicated type name.
+        name: String,
+        /// First registered id and the conflicting id.
+        ids: (u16, u16),
+    },
+    /// Entry uses `UNTYPED_TYPE_ID`.
+    ReservedId {
+        /// Name of the entry.
+        name: String,
+    },
+    /// Type name fails `validate_type_name`.
+    InvalidName {
+        /// Type id of the entry.
+        id: u16,
+        /// The invalid name.
+        name: String,
+        /// Validation failure.
+        error: TypeNameError,
+    },
+}
+
+impl fmt::Display for RegistryConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateId { id, names } => write!(
+                f,
+                "type id {id} is registered as both '{}' and '{}'",
+                names.0, names.1
+            ),
+            Self::DuplicateName { name, ids } => write!(
+                f,
+                "type name '{name}' is registered for both {} and {}",
+                ids.0, ids.1
+            ),
+            Self::ReservedId { name } => {
+                write!(f, "type name '{name}' uses reserved type id 0")
+            }
+            Self::InvalidName { id, name, error } => {
+                write!(f, "invalid type name '{name}' for type id {id}: {error}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid type registry ({} problems)",
+            self.conflicts.len()
+        )?;
+        for conflict in &self.conflicts {
+            write!(f, "\n- {conflict}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Errors returned when importing a registry from a serialized format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryFormatError {
+    /// Binary input does not start with the registry magic bytes.
+    InvalidMagic,
+    /// Format version is not supported by this crate version.
+    UnsupportedVersion(u32),
+    /// Binary input ends in the middle of an entry.
+    Truncated,
+    /// Binary input has bytes after the last entry.
+    TrailingData,
+    /// Type name is not valid UTF-8.
+    InvalidUtf8,
+    /// Registry has more entries or longer names than the binary format
+    /// can hold.
+    TooLarge,
+    /// JSON or TOML document is malformed.
+    Syntax(String),
+    /// Decoded entries do not form a valid registry.
+    Registry(RegistryError),
+}
+
+impl fmt::Display for RegistryFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => f.write_str("input is not a binary TTID registry"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported registry format version: {version}")
+            }
+            Self::Truncated => f.write_str("binary registry is truncated"),
+            Self::TrailingData => f.write_str("binary registry has trailing data"),
+            Self::InvalidUtf8 => f.write_str("type name is not valid UTF-8"),
+            Self::TooLarge => f.write_str("registry does not fit the binary format"),
+            Self::Syntax(msg) => write!(f, "malformed registry document: {msg}"),
+            Self::Registry(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RegistryFormatError {}
+
+impl From<RegistryError> for RegistryFormatError {
+    fn from(value: RegistryError) -> Self {
+        Self::Registry(value)
+    }
+}
+
+/// Errors returned when decoding the compact binary form of a TTID batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecError {
+    /// Format version is not supported by this crate version.
+    UnsupportedVersion(u8),
+    /// Input ends in the middle of an id.
+    Truncated,
+    /// Input has bytes after the last id.
+    TrailingData,
+    /// A varint or a decoded timestamp exceeds its value range.
+    Overflow,
+    /// A decoded id is not valid for the target domain.
+    Ttid(TtidError),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported TTID batch format version {version}")
+            }
+            Self::Truncated => f.write_str("TTID batch data is truncated"),
+            Self::TrailingData => f.write_str("unexpected data after TTID batch"),
+            Self::Overflow => f.write_str("TTID batch value out of range"),
+            Self::Ttid(err) => write!(f, "invalid TTID in batch: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<TtidError> for CodecError {
+    fn from(value: TtidError) -> Self {
+        Self::Ttid(value)
+    }
+}
+
+/// Errors returned when unwrapping a binary envelope, see
+/// [`Ttid::from_envelope`](crate::Ttid::from_envelope).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// Input does not start with the envelope magic or has the wrong length.
+    NotAnEnvelope,
+    /// Layout version is not supported by this crate version.
+    UnsupportedVersion(u8),
+    /// The wrapped id is not valid for the target domain.
+    Ttid(TtidError),
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnEnvelope => f.write_str("data is not a TTID envelope"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported TTID envelope layout version {version}")
+            }
+            Self::Ttid(err) => write!(f, "invalid TTID in envelope: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+impl From<TtidError> for EnvelopeError {
+    fn from(value: TtidError) -> Self {
+        Self::Ttid(value)
+    }
+}
+
+/// Errors returned when appending to or verifying an
+/// [`audit::Chain`](crate::audit::Chain).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+    /// The id at `index` is older than the previous id.
+    OutOfOrder {
+        /// Position of the offending link.
+        index: usize,
+    },
+    /// The stored hash at `index` does not match the recomputed one.
+    HashMismatch {
+        /// Position of the first mismatching link.
+        index: usize,
+    },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfOrder { index } => {
+                write!(f, "audit chain id at {index} is older than its predecessor")
+            }
+            Self::HashMismatch { index } => write!(f, "audit chain hash mismatch at {index}"),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
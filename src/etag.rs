@@ -0,0 +1,106 @@
+//! HTTP entity tags for resources versioned by an id.
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+impl<T: IdType> Ttid<T> {
+    /// Render as a strong entity tag, `"<type-name>_<shortuuid>"` including
+    /// the quotes, for `ETag` headers.
+    pub fn to_etag(&self) -> String {
+        format!("\"{self}\"")
+    }
+
+    /// Render as a weak entity tag, `W/"<type-name>_<shortuuid>"`.
+    pub fn to_weak_etag(&self) -> String {
+        format!("W/{}", self.to_etag())
+    }
+
+    /// Parse a strong or weak entity tag produced by [`Self::to_etag`] or
+    /// [`Self::to_weak_etag`].
+    pub fn from_etag(etag: &str) -> Result<Self, ParseTtidError> {
+        let (_, opaque) = split_etag(etag.trim()).ok_or(ParseTtidError::InvalidFormat)?;
+        opaque.parse()
+    }
+
+    /// Whether an `If-Match` header value matches this version.
+    ///
+    /// `If-Match` uses the strong comparison: `*` or a list of entity tags
+    /// containing this id as a strong tag. Weak and malformed tags never
+    /// match.
+    pub fn matches_if_match(&self, header: &str) -> bool {
+        self.matches_header(header, true)
+    }
+
+    /// Whether an `If-None-Match` header value matches this version, i.e.
+    /// the client's copy is current.
+    ///
+    /// `If-None-Match` uses the weak comparison: `*` or a list of entity
+    /// tags containing this id as a strong or weak tag.
+    pub fn matches_if_none_match(&self, header: &str) -> bool {
+        self.matches_header(header, false)
+    }
+
+    fn matches_header(&self, header: &str, strong: bool) -> bool {
+        let header = header.trim();
+        if header == "*" {
+            return true;
+        }
+        let own = self.to_string();
+        header
+            .split(',')
+            .filter_map(|tag| split_etag(tag.trim()))
+            .any(|(weak, opaque)| !(strong && weak) && opaque == own)
+    }
+}
+
+/// Split an entity tag into its weakness and the opaque tag without quotes.
+fn split_etag(etag: &str) -> Option<(bool, &str)> {
+    let (weak, quoted) = match etag.strip_prefix("W/") {
+        Some(quoted) => (true, quoted),
+        None => (false, etag),
+    };
+    let opaque = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    Some((weak, opaque))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::MyType;
+    use crate::{ParseTtidError, Ttid};
+
+    #[test]
+    fn etags_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        assert_eq!(id.to_etag(), format!("\"{id}\""));
+        assert_eq!(id.to_weak_etag(), format!("W/\"{id}\""));
+        assert_eq!(Ttid::from_etag(&id.to_etag()), Ok(id));
+        assert_eq!(Ttid::from_etag(&format!(" {} ", id.to_weak_etag())), Ok(id));
+
+        assert_eq!(
+            Ttid::<MyType>::from_etag(&id.to_string()),
+            Err(ParseTtidError::InvalidFormat)
+        );
+        assert_eq!(
+            Ttid::<MyType>::from_etag("\""),
+            Err(ParseTtidError::InvalidFormat)
+        );
+        assert!(Ttid::<MyType>::from_etag("\"user_\"").is_err());
+    }
+
+    #[test]
+    fn precondition_headers() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+        let other = id.with_randomness(8);
+
+        let list = format!("{}, {}", other.to_etag(), id.to_etag());
+        assert!(id.matches_if_match(&list));
+        assert!(id.matches_if_match(" * "));
+        assert!(!other.with_randomness(9).matches_if_match(&list));
+        assert!(!id.matches_if_match(&id.to_weak_etag()));
+        assert!(!id.matches_if_match(&id.to_string()));
+
+        assert!(id.matches_if_none_match(&id.to_weak_etag()));
+        assert!(id.matches_if_none_match(&list));
+        assert!(!other.matches_if_none_match(&id.to_etag()));
+    }
+}
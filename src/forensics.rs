@@ -0,0 +1,186 @@
+//! Helpers for incident analysis based on the timestamps embedded in ids.
+
+use crate::RawTtid;
+
+/// Name of an id producer, e.g. a host or service instance.
+pub type SourceLabel<'a> = &'a str;
+
+/// How reliable the position of an id in [`order_events`] output is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderConfidence {
+    /// No other id shares the millisecond, the position follows creation time.
+    Exact,
+    /// Other ids were created in the same millisecond. Their relative order
+    /// is by type id and randomness, not by creation.
+    SameMillisecond {
+        /// Number of ids sharing the millisecond, including this one.
+        group_size: usize,
+    },
+}
+
+/// Order ids of mixed types by embedded creation time.
+///
+/// Ids within the same millisecond cannot be ordered by creation and are
+/// flagged with [`OrderConfidence::SameMillisecond`].
+pub fn order_events(ids: &[RawTtid]) -> Vec<(RawTtid, OrderConfidence)> {
+    let mut sorted = ids.to_vec();
+    // Binary UUID order is timestamp-first.
+    sorted.sort_unstable();
+
+    let mut events = Vec::with_capacity(sorted.len());
+    for group in sorted.chunk_by(|a, b| a.timestamp_ms() == b.timestamp_ms()) {
+        let confidence = match group.len() {
+            1 => OrderConfidence::Exact,
+            group_size => OrderConfidence::SameMillisecond { group_size },
+        };
+        events.extend(group.iter().map(|id| (*id, confidence)));
+    }
+    events
+}
+
+/// Estimated clock offset of one source, see [`skew_report`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceSkew<'a> {
+    /// The source.
+    pub label: SourceLabel<'a>,
+    /// Number of ids of the source.
+    pub ids: usize,
+    /// Estimated clock offset in milliseconds relative to the reference
+    /// source, positive if this source's clock is ahead.
+    ///
+    /// `None` if this source or the reference has no ids.
+    pub offset_ms: Option<i64>,
+    /// Length in milliseconds of the time range shared with the reference
+    /// source, after correcting for `offset_ms`. Small overlaps make the
+    /// estimate unreliable.
+    pub overlap_ms: u64,
+}
+
+/// Estimate relative clock skew between producers of overlapping id streams.
+///
+/// The first group is the reference. Each group holds the ids one source
+/// produced during the same real-time window as the others, e.g. a batch
+/// read from a shared ingestion queue. The offset of a source is the
+/// difference of its median timestamp to the reference median, which is
+/// robust against a few outliers but assumes comparable id rates over the
+/// window.
+pub fn skew_report<'a>(groups: &[(SourceLabel<'a>, &[RawTtid])]) -> Vec<SourceSkew<'a>> {
+    let stats: Vec<_> = groups.iter().map(|(_, ids)| TimeStats::new(ids)).collect();
+    let reference = stats.first().copied().flatten();
+
+    groups
+        .iter()
+        .zip(&stats)
+        .map(|((label, ids), stats)| {
+            let (offset_ms, overlap_ms) = match (stats, reference) {
+                (Some(stats), Some(reference)) => {
+                    let offset = stats.median as i64 - reference.median as i64;
+                    let start = (stats.min as i64 - offset).max(reference.min as i64);
+                    let end = (stats.max as i64 - offset).min(reference.max as i64);
+                    (Some(offset), (end - start).max(0) as u64)
+                }
+                _ => (None, 0),
+            };
+            SourceSkew {
+                label,
+                ids: ids.len(),
+                offset_ms,
+                overlap_ms,
+            }
+        })
+        .collect()
+}
+
+/// Timestamp distribution of a non-empty id stream.
+#[derive(Clone, Copy)]
+struct TimeStats {
+    min: u64,
+    median: u64,
+    max: u64,
+}
+
+impl TimeStats {
+    fn new(ids: &[RawTtid]) -> Option<Self> {
+        let mut timestamps: Vec<_> = ids.iter().map(RawTtid::timestamp_ms).collect();
+        timestamps.sort_unstable();
+        Some(Self {
+            min: *timestamps.first()?,
+            median: timestamps[timestamps.len() / 2],
+            max: *timestamps.last()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ttid;
+    use crate::tests::MyType;
+
+    fn raw(ts: u64, ty: MyType, rand: u64) -> RawTtid {
+        Ttid::<MyType>::from_parts(ts, ty, rand).unwrap().into()
+    }
+
+    #[test]
+    fn orders_by_time_and_flags_ties() {
+        let first = raw(1_000, MyType::Session, 5);
+        let tie_a = raw(2_000, MyType::User, 9);
+        let tie_b = raw(2_000, MyType::Org, 1);
+        let last = raw(3_000, MyType::User, 1);
+
+        let events = order_events(&[last, tie_b, first, tie_a]);
+
+        let tie = OrderConfidence::SameMillisecond { group_size: 2 };
+        assert_eq!(
+            events,
+            vec![
+                (first, OrderConfidence::Exact),
+                (tie_a, tie),
+                (tie_b, tie),
+                (last, OrderConfidence::Exact),
+            ]
+        );
+        assert!(order_events(&[]).is_empty());
+    }
+
+    #[test]
+    fn skew_report_estimates_offsets_against_first_source() {
+        let stream = |offset: u64| -> Vec<RawTtid> {
+            (0..101)
+                .map(|n| raw(1_000_000 + offset + n * 10, MyType::User, n))
+                .collect()
+        };
+        let reference = stream(0);
+        let ahead = stream(250);
+        let mut behind: Vec<_> = stream(0)
+            .into_iter()
+            .map(|id| raw(id.timestamp_ms() - 40, MyType::Org, id.randomness()))
+            .collect();
+        // An outlier shifts the median by at most one sample.
+        behind.push(raw(9_000_000, MyType::Org, 0));
+
+        let report = skew_report(&[
+            ("api-1", &reference),
+            ("api-2", &ahead),
+            ("worker", &behind),
+            ("idle", &[]),
+        ]);
+
+        assert_eq!(report[0].offset_ms, Some(0));
+        assert_eq!(report[0].overlap_ms, 1_000);
+        assert_eq!(report[1].label, "api-2");
+        assert_eq!(report[1].offset_ms, Some(250));
+        assert_eq!(report[1].overlap_ms, 1_000);
+        assert_eq!(report[2].ids, 102);
+        assert_eq!(report[2].offset_ms, Some(-30));
+        assert_eq!(
+            report[3],
+            SourceSkew {
+                label: "idle",
+                ids: 0,
+                offset_ms: None,
+                overlap_ms: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Retention sweeps over TTID-keyed data.
+//!
+//! Ids of one type sort by their embedded timestamp, so the records older
+//! than a retention period form a contiguous key range below the
+//! [`cutoff`] id:
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+//! # struct Session;
+//! # impl IdType for Session {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Session) }
+//! #     fn as_type_name(self) -> &'static str { "session" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "session").then_some(Session) }
+//! # }
+//! let retention = Duration::from_secs(30 * 24 * 3600);
+//! let cutoff = ttid::gc::cutoff(Session, retention).unwrap();
+//! // DELETE FROM sessions WHERE id < $cutoff
+//! # let id = Ttid::new(Session).unwrap();
+//! assert!(id >= cutoff);
+//! assert!(!ttid::gc::expired(&id, retention));
+//! ```
+
+use std::time::Duration;
+
+use crate::{IdType, Ttid, TtidError, now_ms};
+
+/// The smallest id of type `ty` that is still retained at the current time.
+///
+/// Ids of type `ty` sorting before the cutoff were created more than
+/// `retention` ago. Fails with [`TtidError::TimestampOutOfRange`] if the
+/// system clock is before the Unix epoch or beyond the 48-bit range.
+pub fn cutoff<T: IdType>(ty: T, retention: Duration) -> Result<Ttid<T>, TtidError> {
+    cutoff_at(now_ms()?, ty, retention)
+}
+
+/// [`cutoff`] relative to `now_ms` instead of the current time.
+pub fn cutoff_at<T: IdType>(now_ms: u64, ty: T, retention: Duration) -> Result<Ttid<T>, TtidError> {
+    Ttid::from_parts(cutoff_ms(now_ms, retention), ty, 0)
+}
+
+/// Whether `id` was created more than `retention` ago.
+///
+/// Nothing expires if the system clock is before the Unix epoch.
+pub fn expired<T: IdType>(id: &Ttid<T>, retention: Duration) -> bool {
+    now_ms().is_ok_and(|now_ms| expired_at(now_ms, id, retention))
+}
+
+/// [`expired`] relative to `now_ms` instead of the current time.
+pub fn expired_at<T: IdType>(now_ms: u64, id: &Ttid<T>, retention: Duration) -> bool {
+    id.timestamp_ms() < cutoff_ms(now_ms, retention)
+}
+
+/// Oldest retained timestamp, saturating at the epoch.
+fn cutoff_ms(now_ms: u64, retention: Duration) -> u64 {
+    let retention_ms = u64::try_from(retention.as_millis()).unwrap_or(u64::MAX);
+    now_ms.saturating_sub(retention_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deser::TIMESTAMP_MAX;
+    use crate::tests::MyType;
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    #[test]
+    fn cutoff_separates_expired_ids() {
+        let retention = Duration::from_secs(60);
+        let cutoff = cutoff_at(NOW, MyType::Session, retention).unwrap();
+        assert_eq!(cutoff.timestamp_ms(), NOW - 60_000);
+        assert_eq!(cutoff.id_type(), MyType::Session);
+        assert_eq!(cutoff.randomness(), 0);
+
+        let id = |timestamp_ms| Ttid::from_parts(timestamp_ms, MyType::Session, u64::MAX).unwrap();
+        for (timestamp_ms, is_expired) in
+            [(NOW - 60_001, true), (NOW - 60_000, false), (NOW, false)]
+        {
+            let id = id(timestamp_ms);
+            assert_eq!(expired_at(NOW, &id, retention), is_expired);
+            assert_eq!(id < cutoff, is_expired);
+        }
+    }
+
+    #[test]
+    fn saturates_and_reports_clock_errors() {
+        let boundary = cutoff_at(NOW, MyType::User, Duration::MAX).unwrap();
+        assert_eq!(boundary.timestamp_ms(), 0);
+
+        let oldest = Ttid::from_parts(0, MyType::User, 0).unwrap();
+        assert!(!expired_at(NOW, &oldest, Duration::MAX));
+
+        assert_eq!(
+            cutoff_at(TIMESTAMP_MAX + 1, MyType::User, Duration::ZERO),
+            Err(TtidError::TimestampOutOfRange)
+        );
+
+        let now = Ttid::new(MyType::User).unwrap();
+        assert!(!expired(&now, Duration::from_secs(60)));
+        assert!(now >= cutoff(MyType::User, Duration::from_secs(60)).unwrap());
+    }
+}
@@ -0,0 +1,1036 @@
+//! Id generators with injectable clocks and ordering guarantees.
+//!
+//! Ids created by [`Ttid::new`] within the same millisecond order randomly
+//! among each other. [`MonotonicGenerator`] instead creates strictly
+//! increasing ids, for cursor pagination over ids created in bursts:
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+//! # struct Event;
+//! # impl IdType for Event {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Event) }
+//! #     fn as_type_name(self) -> &'static str { "event" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "event").then_some(Event) }
+//! # }
+//! use ttid::generator::MonotonicGenerator;
+//!
+//! let events = MonotonicGenerator::new(Event);
+//! let first = events.generate().unwrap();
+//! let second = events.generate().unwrap();
+//! assert!(first < second);
+//! ```
+//!
+//! Generators read the time from a [`Clock`], [`SystemClock`] by default.
+//! Tests and simulations inject a [`ManualClock`] to control timestamps:
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy)]
+//! # struct Event;
+//! # impl IdType for Event {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Event) }
+//! #     fn as_type_name(self) -> &'static str { "event" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "event").then_some(Event) }
+//! # }
+//! use std::time::Duration;
+//! use ttid::generator::{ManualClock, TtidGenerator};
+//!
+//! let clock = ManualClock::new(1_700_000_000_000);
+//! let ids = TtidGenerator::with_clock(&clock);
+//! assert_eq!(ids.generate(Event).unwrap().timestamp_ms(), 1_700_000_000_000);
+//!
+//! clock.advance(Duration::from_secs(1));
+//! assert_eq!(ids.generate(Event).unwrap().timestamp_ms(), 1_700_000_001_000);
+//! ```
+//!
+//! [`TtidGenerator::seeded`] combines a [`SteppingClock`] with
+//! [`SeededRandomness`] for fully reproducible ids, e.g. in snapshot tests.
+//!
+//! Fleets of generators can reserve randomness bits for a [`NodeId`], so ids
+//! reveal the instance that minted them and ids of different nodes never
+//! collide:
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy)]
+//! # struct Event;
+//! # impl IdType for Event {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Event) }
+//! #     fn as_type_name(self) -> &'static str { "event" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "event").then_some(Event) }
+//! # }
+//! use ttid::generator::{NodeId, TtidGenerator};
+//!
+//! let node = NodeId::new(10, 37).unwrap();
+//! let ids = TtidGenerator::new().with_node(node);
+//! let id = ids.generate(Event).unwrap();
+//! assert_eq!(NodeId::read(&id, 10), Some(node));
+//! ```
+//!
+//! # Concurrency
+//!
+//! Generators are `Sync` if their clock and randomness source are, and
+//! issue ids under a lock, one at a time. Calls to one generator are thus
+//! totally ordered, and if a call returns before another starts, e.g. on the
+//! same thread or after synchronizing through a channel or a join:
+//!
+//! - [`MonotonicGenerator`] issues the later id strictly greater, so ids
+//!   observed in that order are strictly increasing, on every thread and
+//!   globally;
+//! - [`TtidGenerator`] issues the later id with a timestamp no older, but
+//!   ids within one millisecond order randomly.
+//!
+//! The thread-local [`generate`] only orders ids of the same thread and
+//! type.
+//! These guarantees are model-checked with [loom](https://docs.rs/loom):
+//! `RUSTFLAGS="--cfg ttid_loom" cargo test --release --lib loom`.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::time::Duration;
+
+#[cfg(all(test, ttid_loom))]
+use loom::sync::Mutex;
+#[cfg(not(all(test, ttid_loom)))]
+use std::sync::Mutex;
+
+use crate::tombstone::{ENTITY_MASK, TOMBSTONE_FLAG};
+use crate::{
+    IdType, RANDOM_MASK, TIMESTAMP_MAX, Ttid, TtidError, fill_random_bits, now_ms, random_bits,
+};
+
+/// Declare a `const fn`, except under loom, whose `Mutex` cannot be created
+/// in constant contexts.
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(all(test, ttid_loom)))]
+        $(#[$attr])* $vis const fn $($rest)*
+        #[cfg(all(test, ttid_loom))]
+        $(#[$attr])* $vis fn $($rest)*
+    };
+}
+
+impl<T: IdType> Ttid<T> {
+    /// Create `n` ids of type `ty`, like `n` calls of [`Ttid::new`] but
+    /// reading the clock once and the randomness in bulk.
+    ///
+    /// All ids share the timestamp and order randomly among each other. Use
+    /// [`MonotonicGenerator::extend_batch`] for strictly increasing ids.
+    pub fn new_batch(ty: T, n: usize) -> Result<Vec<Self>, TtidError> {
+        let mut ids = Vec::with_capacity(n);
+        Self::extend_batch(ty, n, &mut ids)?;
+        Ok(ids)
+    }
+
+    /// [`Self::new_batch`] appending to `out`, to reuse its allocation.
+    ///
+    /// `out` is unchanged on error.
+    pub fn extend_batch(ty: T, n: usize, out: &mut Vec<Self>) -> Result<(), TtidError> {
+        let timestamp_ms = now_ms()?;
+        // Validates timestamp and type once for the whole batch.
+        let first = Self::from_parts(timestamp_ms, ty, 0)?;
+        let mut randomness = vec![0; n];
+        fill_random_bits(&mut randomness);
+        out.extend(
+            randomness
+                .into_iter()
+                .map(|bits| first.with_randomness(bits)),
+        );
+        Ok(())
+    }
+}
+
+/// Source of the current time for generators.
+pub trait Clock {
+    /// Current Unix timestamp in milliseconds.
+    ///
+    /// Fails with [`TtidError::TimestampOutOfRange`] if the time cannot be
+    /// represented, e.g. before the Unix epoch.
+    fn now_ms(&self) -> Result<u64, TtidError>;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now_ms(&self) -> Result<u64, TtidError> {
+        (**self).now_ms()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now_ms(&self) -> Result<u64, TtidError> {
+        (**self).now_ms()
+    }
+}
+
+/// The system clock, as used by [`Ttid::new`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> Result<u64, TtidError> {
+        now_ms()
+    }
+}
+
+/// A clock that only moves when told to, for tests and simulations.
+///
+/// Shared by reference or [`Arc`], since `&ManualClock` and
+/// `Arc<ManualClock>` are clocks too.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_ms: AtomicU64,
+}
+
+impl ManualClock {
+    /// Clock standing at `now_ms`.
+    pub const fn new(now_ms: u64) -> Self {
+        Self {
+            now_ms: AtomicU64::new(now_ms),
+        }
+    }
+
+    /// Set the time to `now_ms`, also backwards.
+    pub fn set(&self, now_ms: u64) {
+        self.now_ms.store(now_ms, Ordering::Relaxed);
+    }
+
+    /// Move the time forward by `duration`, saturating.
+    pub fn advance(&self, duration: Duration) {
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        // `fetch_update` only fails if the closure returns `None`.
+        let _ = self
+            .now_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |now_ms| {
+                Some(now_ms.saturating_add(millis))
+            });
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> Result<u64, TtidError> {
+        Ok(self.now_ms.load(Ordering::Relaxed))
+    }
+}
+
+/// A clock advancing by a fixed step on every reading, for reproducible id
+/// sequences.
+#[derive(Debug)]
+pub struct SteppingClock {
+    next_ms: AtomicU64,
+    step_ms: u64,
+}
+
+impl SteppingClock {
+    /// Clock reading `start_ms` first, then advancing by `step` per reading.
+    pub fn new(start_ms: u64, step: Duration) -> Self {
+        Self {
+            next_ms: AtomicU64::new(start_ms),
+            step_ms: u64::try_from(step.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now_ms(&self) -> Result<u64, TtidError> {
+        // `fetch_update` only fails if the closure returns `None`.
+        let now_ms = self
+            .next_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |now_ms| {
+                Some(now_ms.saturating_add(self.step_ms))
+            })
+            .unwrap_or_else(|now_ms| now_ms);
+        Ok(now_ms)
+    }
+}
+
+/// Source of the randomness of generated ids.
+pub trait Randomness {
+    /// 58 random bits, in the low bits of the result.
+    fn random_bits(&self) -> u64;
+}
+
+impl<R: Randomness + ?Sized> Randomness for &R {
+    fn random_bits(&self) -> u64 {
+        (**self).random_bits()
+    }
+}
+
+impl<R: Randomness + ?Sized> Randomness for Arc<R> {
+    fn random_bits(&self) -> u64 {
+        (**self).random_bits()
+    }
+}
+
+/// The operating system's random source, as used by [`Ttid::new`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OsRandomness;
+
+impl Randomness for OsRandomness {
+    fn random_bits(&self) -> u64 {
+        random_bits()
+    }
+}
+
+/// Reproducible pseudo-random bits from a seed, for tests and golden files.
+///
+/// Uses splitmix64, whose output is fixed across platforms and crate
+/// versions. Not suitable for ids that must be unpredictable.
+#[derive(Debug)]
+pub struct SeededRandomness {
+    state: AtomicU64,
+}
+
+impl SeededRandomness {
+    /// Sequence determined by `seed`.
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed),
+        }
+    }
+}
+
+impl Randomness for SeededRandomness {
+    fn random_bits(&self) -> u64 {
+        let state = self
+            .state
+            .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+            .wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        (z ^ (z >> 31)) & RANDOM_MASK
+    }
+}
+
+/// Maximum number of randomness bits a [`NodeId`] may reserve, leaving at
+/// least 25 random bits per id and millisecond.
+pub const MAX_NODE_BITS: u32 = 32;
+
+/// Node or worker id stored in the `bits` randomness bits right below
+/// [`TOMBSTONE_FLAG`].
+///
+/// Generators with a node keep the flag clear, so their ids are live ids
+/// under the [tombstone](crate::tombstone) convention, and tombstones keep
+/// the node id. Revision ids of [`Ttid::new_revision_of`] store their
+/// lineage in the same bits; generators never create revisions, so no id
+/// carries both, and [`NodeId::read`] on a revision returns lineage bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    bits: u32,
+    id: u64,
+}
+
+impl NodeId {
+    /// Node `id` occupying `bits` randomness bits.
+    ///
+    /// `None` if `bits` is zero or exceeds [`MAX_NODE_BITS`], or `id` does
+    /// not fit into `bits` bits.
+    pub const fn new(bits: u32, id: u64) -> Option<Self> {
+        if bits == 0 || bits > MAX_NODE_BITS || id >> bits != 0 {
+            return None;
+        }
+        Some(Self { bits, id })
+    }
+
+    /// Read a node id of width `bits` from `id`.
+    ///
+    /// `None` if `bits` is zero or exceeds [`MAX_NODE_BITS`].
+    pub fn read<T: IdType>(id: &Ttid<T>, bits: u32) -> Option<Self> {
+        if bits == 0 || bits > MAX_NODE_BITS {
+            return None;
+        }
+        Self::new(
+            bits,
+            (id.randomness() >> Self::shift(bits)) & ((1 << bits) - 1),
+        )
+    }
+
+    /// Number of reserved randomness bits.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// The node id.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Randomness bits left to sequencing and fresh randomness.
+    fn sequence_mask(&self) -> u64 {
+        RANDOM_MASK >> (self.bits + 1)
+    }
+
+    /// Replace the node bits of `randomness` with the node id and clear
+    /// [`TOMBSTONE_FLAG`].
+    fn apply(&self, randomness: u64) -> u64 {
+        (self.id << Self::shift(self.bits)) | (randomness & self.sequence_mask())
+    }
+
+    /// Position of the lowest bit of a node id of width `bits`.
+    const fn shift(bits: u32) -> u32 {
+        TOMBSTONE_FLAG.trailing_zeros() - bits
+    }
+}
+
+/// Generator of ids with the time of a [`Clock`] and the bits of a
+/// [`Randomness`] source.
+///
+/// With the default [`SystemClock`], [`OsRandomness`] and no [`NodeId`] it
+/// creates ids like [`Ttid::new`], but is safe against clock regressions:
+/// it remembers the greatest timestamp issued and, while the clock is behind
+/// it, e.g. after an NTP correction or VM resume, reuses that timestamp and
+/// increments the randomness of the last id, so ids never go back in time.
+/// If the randomness overflows, the timestamp advances by one millisecond.
+#[derive(Debug, Default)]
+pub struct TtidGenerator<C: Clock = SystemClock, R: Randomness = OsRandomness> {
+    clock: C,
+    randomness: R,
+    reserved: Reserved,
+    /// Greatest timestamp issued, and the greatest randomness issued with it.
+    last: Mutex<Option<(u64, u64)>>,
+}
+
+impl<C: Clock + Clone, R: Randomness + Clone> Clone for TtidGenerator<C, R> {
+    /// Clone the generator, including the timestamp it will not go behind.
+    fn clone(&self) -> Self {
+        Self {
+            clock: self.clock.clone(),
+            randomness: self.randomness.clone(),
+            reserved: self.reserved,
+            last: Mutex::new(*self.last.lock().unwrap_or_else(PoisonError::into_inner)),
+        }
+    }
+}
+
+impl TtidGenerator {
+    const_fn! {
+        /// Generator reading the system clock.
+        pub fn new() -> Self {
+            Self::with_clock(SystemClock)
+        }
+    }
+}
+
+impl TtidGenerator<SteppingClock, SeededRandomness> {
+    /// Start of the clock of [`Self::seeded`] generators,
+    /// 2023-11-14T22:13:20Z.
+    pub const SEEDED_START_MS: u64 = 1_700_000_000_000;
+
+    /// Generator of a reproducible id sequence for snapshot tests and golden
+    /// files.
+    ///
+    /// The first id has timestamp [`Self::SEEDED_START_MS`], every further
+    /// id one millisecond more. The randomness comes from
+    /// [`SeededRandomness`], so the same `seed` and sequence of calls always
+    /// yield the same ids.
+    pub fn seeded(seed: u64) -> Self {
+        Self::with_clock_and_randomness(
+            SteppingClock::new(Self::SEEDED_START_MS, Duration::from_millis(1)),
+            SeededRandomness::new(seed),
+        )
+    }
+}
+
+impl<C: Clock> TtidGenerator<C> {
+    const_fn! {
+        /// Generator reading `clock`.
+        pub fn with_clock(clock: C) -> Self {
+            Self::with_clock_and_randomness(clock, OsRandomness)
+        }
+    }
+}
+
+impl<C: Clock, R: Randomness> TtidGenerator<C, R> {
+    const_fn! {
+        /// Generator reading `clock` and drawing bits from `randomness`.
+        pub fn with_clock_and_randomness(clock: C, randomness: R) -> Self {
+            Self {
+                clock,
+                randomness,
+                reserved: Reserved::NONE,
+                last: Mutex::new(None),
+            }
+        }
+    }
+
+    /// Reserve randomness bits of generated ids for `node`, see [`NodeId`].
+    ///
+    /// Ids of generators with different nodes of the same width never
+    /// collide; each id keeps `57 - node.bits()` random bits.
+    pub fn with_node(mut self, node: NodeId) -> Self {
+        self.reserved.node = Some(node);
+        self
+    }
+
+    /// Keep [`TOMBSTONE_FLAG`] clear in generated ids, for domains using the
+    /// [tombstone](crate::tombstone) convention. Implied by
+    /// [`Self::with_node`].
+    pub fn reserve_tombstone_flag(mut self) -> Self {
+        self.reserved.tombstone_flag = true;
+        self
+    }
+
+    /// The clock of this generator.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// The randomness source of this generator.
+    pub fn randomness(&self) -> &R {
+        &self.randomness
+    }
+
+    /// The node id stored in generated ids, if any.
+    pub fn node(&self) -> Option<NodeId> {
+        self.reserved.node
+    }
+
+    /// Generate an id of type `ty` at the current time of the clock, or at
+    /// the greatest timestamp issued if the clock is behind it.
+    ///
+    /// Fails with [`TtidError::TimestampOutOfRange`] if the clock fails or the
+    /// timestamp leaves the 48-bit range.
+    pub fn generate<T: IdType>(&self, ty: T) -> Result<Ttid<T>, TtidError> {
+        let now_ms = self.clock.now_ms()?;
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        let (timestamp_ms, randomness) = match *last {
+            Some((last_ms, last_randomness)) if now_ms < last_ms => {
+                sequence(last_ms, last_randomness, self.reserved.mask(), || {
+                    self.reserved.apply(self.randomness.random_bits())
+                })?
+            }
+            _ => (now_ms, self.reserved.apply(self.randomness.random_bits())),
+        };
+        let id = Ttid::from_parts(timestamp_ms, ty, randomness)?;
+        *last = match *last {
+            Some((last_ms, last_randomness)) if last_ms == timestamp_ms => {
+                Some((last_ms, last_randomness.max(randomness)))
+            }
+            _ => Some((timestamp_ms, randomness)),
+        };
+        Ok(id)
+    }
+}
+
+/// Generator of strictly increasing ids of one type.
+///
+/// The first id of a millisecond gets fresh randomness. Further ids in the
+/// same millisecond, or while the clock is behind the last id, reuse the last
+/// timestamp and increment the randomness, so they are strictly greater but
+/// predictable from their predecessor. If the randomness overflows, the
+/// timestamp advances by one millisecond.
+///
+/// The generator is `Sync` if the clock is; share one instance per type,
+/// e.g. in a `static` or an `Arc`, to order ids across threads.
+#[derive(Debug)]
+pub struct MonotonicGenerator<T: IdType, C: Clock = SystemClock> {
+    ty: T,
+    clock: C,
+    reserved: Reserved,
+    /// Timestamp and randomness of the last generated id.
+    last: Mutex<Option<(u64, u64)>>,
+}
+
+impl<T: IdType> MonotonicGenerator<T> {
+    const_fn! {
+        /// Generator of ids of type `ty`, reading the system clock.
+        pub fn new(ty: T) -> Self {
+            Self::with_clock(ty, SystemClock)
+        }
+    }
+}
+
+impl<T: IdType, C: Clock> MonotonicGenerator<T, C> {
+    const_fn! {
+        /// Generator of ids of type `ty`, reading `clock`.
+        pub fn with_clock(ty: T, clock: C) -> Self {
+            Self {
+                ty,
+                clock,
+                reserved: Reserved::NONE,
+                last: Mutex::new(None),
+            }
+        }
+    }
+
+    /// Reserve randomness bits of generated ids for `node`, see [`NodeId`].
+    ///
+    /// Increments within a millisecond leave the node bits untouched.
+    pub fn with_node(mut self, node: NodeId) -> Self {
+        self.reserved.node = Some(node);
+        self
+    }
+
+    /// Keep [`TOMBSTONE_FLAG`] clear in generated ids, for domains using the
+    /// [tombstone](crate::tombstone) convention. Implied by
+    /// [`Self::with_node`].
+    pub fn reserve_tombstone_flag(mut self) -> Self {
+        self.reserved.tombstone_flag = true;
+        self
+    }
+
+    /// The clock of this generator.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// The node id stored in generated ids, if any.
+    pub fn node(&self) -> Option<NodeId> {
+        self.reserved.node
+    }
+
+    /// Generate an id greater than all ids previously generated.
+    ///
+    /// Fails with [`TtidError::TimestampOutOfRange`] if the clock fails or
+    /// the timestamp leaves the 48-bit range.
+    pub fn generate(&self) -> Result<Ttid<T>, TtidError> {
+        self.generate_at(self.clock.now_ms()?)
+    }
+
+    /// [`Self::generate`] at `now_ms` instead of the current time.
+    pub fn generate_at(&self, now_ms: u64) -> Result<Ttid<T>, TtidError> {
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        self.next(&mut last, now_ms)
+    }
+
+    /// Append `n` ids to `out`, each greater than all ids previously
+    /// generated, reading the clock once.
+    ///
+    /// On error, the ids generated so far remain in `out`.
+    pub fn extend_batch(&self, n: usize, out: &mut Vec<Ttid<T>>) -> Result<(), TtidError> {
+        let now_ms = self.clock.now_ms()?;
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        out.reserve(n);
+        for _ in 0..n {
+            out.push(self.next(&mut last, now_ms)?);
+        }
+        Ok(())
+    }
+
+    /// The id following `last` at `now_ms`, recorded as the new `last`.
+    fn next(&self, last: &mut Option<(u64, u64)>, now_ms: u64) -> Result<Ttid<T>, TtidError> {
+        next_monotonic(last, now_ms, self.ty, self.reserved)
+    }
+}
+
+thread_local! {
+    /// Timestamp and randomness of the last id created by [`generate`] on
+    /// this thread.
+    static LAST_GENERATED: Cell<Option<(u64, u64)>> = const { Cell::new(None) };
+}
+
+/// Create an id of type `ty`, greater than all ids of the same type
+/// previously created by this function on the current thread.
+///
+/// Backed by thread-local state following the rules of
+/// [`MonotonicGenerator`], so no generator has to be passed around. The
+/// state is shared by all types: the id also has a larger
+/// `(timestamp, randomness)` than every id of any type created before on the
+/// thread. Ids compare by type id before randomness though, so an id created
+/// earlier in the same millisecond with a larger type id is the greater one.
+///
+/// Ids from different threads are not ordered among each other within a
+/// millisecond; use a shared [`MonotonicGenerator`] for that.
+///
+/// Fails with [`TtidError::TimestampOutOfRange`] if the system clock is
+/// before the Unix epoch or the timestamp leaves the 48-bit range.
+pub fn generate<T: IdType>(ty: T) -> Result<Ttid<T>, TtidError> {
+    let now_ms = now_ms()?;
+    LAST_GENERATED.with(|cell| {
+        let mut last = cell.get();
+        let id = next_monotonic(&mut last, now_ms, ty, Reserved::NONE)?;
+        cell.set(last);
+        Ok(id)
+    })
+}
+
+/// The id of type `ty` following `last` at `now_ms`, recorded as the new
+/// `last`.
+fn next_monotonic<T: IdType>(
+    last: &mut Option<(u64, u64)>,
+    now_ms: u64,
+    ty: T,
+    reserved: Reserved,
+) -> Result<Ttid<T>, TtidError> {
+    let (timestamp_ms, randomness) = match *last {
+        Some((last_ms, last_randomness)) if now_ms <= last_ms => {
+            sequence(last_ms, last_randomness, reserved.mask(), || {
+                reserved.apply(random_bits())
+            })?
+        }
+        _ => (now_ms, reserved.apply(random_bits())),
+    };
+    let id = Ttid::from_parts(timestamp_ms, ty, randomness)?;
+    *last = Some((timestamp_ms, randomness));
+    Ok(id)
+}
+
+/// Randomness bits a generator does not fill randomly.
+#[derive(Clone, Copy, Debug, Default)]
+struct Reserved {
+    node: Option<NodeId>,
+    /// Keep [`TOMBSTONE_FLAG`] clear, also without a node.
+    tombstone_flag: bool,
+}
+
+impl Reserved {
+    const NONE: Self = Self {
+        node: None,
+        tombstone_flag: false,
+    };
+
+    /// Randomness bits left to fresh randomness and sequencing.
+    fn mask(self) -> u64 {
+        match self.node {
+            Some(node) => node.sequence_mask(),
+            None if self.tombstone_flag => ENTITY_MASK,
+            None => RANDOM_MASK,
+        }
+    }
+
+    /// `randomness` with the reserved bits applied.
+    fn apply(self, randomness: u64) -> u64 {
+        match self.node {
+            Some(node) => node.apply(randomness),
+            None => randomness & self.mask(),
+        }
+    }
+}
+
+/// Timestamp and randomness of the id following the one at `last_ms` with
+/// `last_randomness`: the randomness incremented within `sequence_mask`, or,
+/// once those bits are exhausted, the next millisecond with `fresh`
+/// randomness.
+///
+/// Bits outside `sequence_mask`, e.g. a node id, are left untouched by the
+/// increment; `fresh` must set them itself.
+fn sequence(
+    last_ms: u64,
+    last_randomness: u64,
+    sequence_mask: u64,
+    fresh: impl FnOnce() -> u64,
+) -> Result<(u64, u64), TtidError> {
+    if last_randomness & sequence_mask < sequence_mask {
+        Ok((last_ms, last_randomness + 1))
+    } else if last_ms < TIMESTAMP_MAX {
+        Ok((last_ms + 1, fresh()))
+    } else {
+        Err(TtidError::TimestampOutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn ids_within_a_millisecond_increase() {
+        let generator = MonotonicGenerator::new(MyType::Session);
+        let ids: Vec<_> = (0..100)
+            .map(|_| generator.generate_at(1_000).unwrap())
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ids.iter().all(|id| id.timestamp_ms() == 1_000));
+        assert_eq!(ids[99].randomness(), ids[0].randomness() + 99);
+
+        let later = generator.generate_at(2_000).unwrap();
+        assert_eq!(later.timestamp_ms(), 2_000);
+
+        // A clock stepping back keeps the last timestamp.
+        let behind = generator.generate_at(1_500).unwrap();
+        assert_eq!(behind.timestamp_ms(), 2_000);
+        assert_eq!(behind.randomness(), later.randomness() + 1);
+    }
+
+    #[test]
+    fn generators_read_their_clock() {
+        let clock = Arc::new(ManualClock::new(5_000));
+        let ids = TtidGenerator::with_clock(Arc::clone(&clock));
+        let events = MonotonicGenerator::with_clock(MyType::Session, Arc::clone(&clock));
+
+        assert_eq!(ids.generate(MyType::Org).unwrap().timestamp_ms(), 5_000);
+        let first = events.generate().unwrap();
+        assert_eq!(first.timestamp_ms(), 5_000);
+
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(ids.generate(MyType::Org).unwrap().timestamp_ms(), 5_250);
+        assert_eq!(events.generate().unwrap().timestamp_ms(), 5_250);
+
+        clock.set(TIMESTAMP_MAX + 1);
+        assert_eq!(
+            ids.generate(MyType::Org),
+            Err(TtidError::TimestampOutOfRange)
+        );
+
+        let system = TtidGenerator::new().generate(MyType::User).unwrap();
+        assert!(system.timestamp_ms() > 1_700_000_000_000);
+    }
+
+    #[test]
+    fn batches_share_one_clock_reading() {
+        let ids = Ttid::new_batch(MyType::Org, 100).unwrap();
+        assert_eq!(ids.len(), 100);
+        assert!(
+            ids.iter()
+                .all(|id| id.timestamp_ms() == ids[0].timestamp_ms())
+        );
+        assert!(ids.iter().all(|id| id.id_type() == MyType::Org));
+        assert!(ids.windows(2).any(|pair| pair[0] != pair[1]));
+
+        let mut out = vec![ids[0]];
+        Ttid::extend_batch(MyType::Org, 3, &mut out).unwrap();
+        assert_eq!(out.len(), 4);
+        assert_eq!(out[0], ids[0]);
+
+        let clock = ManualClock::new(1_000);
+        let events = MonotonicGenerator::with_clock(MyType::Session, &clock);
+        let first = events.generate().unwrap();
+        let mut batch = Vec::new();
+        events.extend_batch(50, &mut batch).unwrap();
+        clock.advance(Duration::from_millis(1));
+        events.extend_batch(50, &mut batch).unwrap();
+        assert!(batch[0] > first);
+        assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(batch[49].timestamp_ms(), 1_000);
+        assert_eq!(batch[50].timestamp_ms(), 1_001);
+    }
+
+    #[test]
+    fn clock_regressions_do_not_reorder_ids() {
+        let clock = ManualClock::new(10_000);
+        let node = NodeId::new(8, 0xab).unwrap();
+        let ids = TtidGenerator::with_clock(&clock).with_node(node);
+        let first = ids.generate(MyType::User).unwrap();
+
+        clock.set(9_000);
+        let mut previous = first;
+        for _ in 0..10 {
+            let id = ids.generate(MyType::User).unwrap();
+            assert_eq!(id.timestamp_ms(), 10_000);
+            assert!(id > previous);
+            assert_eq!(NodeId::read(&id, 8), Some(node));
+            previous = id;
+        }
+
+        clock.set(10_001);
+        assert_eq!(ids.generate(MyType::Org).unwrap().timestamp_ms(), 10_001);
+        assert_eq!(
+            ids.clone().generate(MyType::Org).unwrap().timestamp_ms(),
+            10_001
+        );
+
+        let full = NodeId::new(8, 0).unwrap();
+        let ids = TtidGenerator::with_clock_and_randomness(&clock, SeededRandomness::new(1))
+            .with_node(full);
+        *ids.last.lock().unwrap() = Some((20_000, RANDOM_MASK >> 9));
+        assert_eq!(ids.generate(MyType::User).unwrap().timestamp_ms(), 20_001);
+    }
+
+    #[test]
+    fn seeded_generators_are_reproducible() {
+        let generate = |seed| {
+            let ids = TtidGenerator::seeded(seed);
+            (0..3)
+                .map(|_| ids.generate(MyType::User).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let ids = generate(7);
+        assert_eq!(ids, generate(7));
+        assert_ne!(ids, generate(8));
+        assert_eq!(
+            ids.iter().map(|id| id.timestamp_ms()).collect::<Vec<_>>(),
+            [1_700_000_000_000, 1_700_000_000_001, 1_700_000_000_002]
+        );
+        assert_eq!(ids[0].randomness(), 0x03cb_e1e4_5932_0dd7);
+        assert!(ids.iter().all(|id| id.randomness() <= RANDOM_MASK));
+    }
+
+    #[test]
+    fn node_ids_sit_below_the_tombstone_flag() {
+        assert_eq!(NodeId::new(0, 0), None);
+        assert_eq!(NodeId::new(MAX_NODE_BITS + 1, 0), None);
+        assert_eq!(NodeId::new(4, 16), None);
+
+        let node = NodeId::new(4, 0b1011).unwrap();
+        let ids = TtidGenerator::new().with_node(node);
+        assert_eq!(ids.node(), Some(node));
+        for _ in 0..100 {
+            let id = ids.generate(MyType::User).unwrap();
+            assert_eq!(id.randomness() >> 53, 0b1011);
+            assert!(!id.is_tombstone());
+            assert_eq!(NodeId::read(&id, 4), Some(node));
+            assert_eq!(NodeId::read(&id.tombstone().unwrap(), 4), Some(node));
+        }
+
+        let live = TtidGenerator::new().reserve_tombstone_flag();
+        let live_events = MonotonicGenerator::new(MyType::Session).reserve_tombstone_flag();
+        *live_events.last.lock().unwrap() = Some((1_000, ENTITY_MASK));
+        for _ in 0..64 {
+            assert!(!live.generate(MyType::User).unwrap().is_tombstone());
+            assert!(!live_events.generate_at(1_000).unwrap().is_tombstone());
+        }
+
+        let events = MonotonicGenerator::new(MyType::Session).with_node(node);
+        assert_eq!(events.node(), Some(node));
+        let mut previous = events.generate_at(1_000).unwrap();
+        *events.last.lock().unwrap() = Some((1_000, node.apply(node.sequence_mask() - 1)));
+        for _ in 0..3 {
+            let id = events.generate_at(1_000).unwrap();
+            assert!(id > previous);
+            assert_eq!(NodeId::read(&id, 4), Some(node));
+            previous = id;
+        }
+        assert_eq!(previous.timestamp_ms(), 1_001);
+
+        let max = NodeId::new(MAX_NODE_BITS, u64::from(u32::MAX)).unwrap();
+        let id = TtidGenerator::new()
+            .with_node(max)
+            .generate(MyType::User)
+            .unwrap();
+        assert_eq!(NodeId::read(&id, MAX_NODE_BITS), Some(max));
+        assert_eq!(NodeId::read(&id, 0), None);
+    }
+
+    #[test]
+    fn thread_local_generation_increases_across_types() {
+        let ids: Vec<_> = (0..200)
+            .map(|n| {
+                let ty = if n % 2 == 0 {
+                    MyType::User
+                } else {
+                    MyType::Org
+                };
+                generate(ty).unwrap()
+            })
+            .collect();
+        for ty in [MyType::User, MyType::Org] {
+            let of_type: Vec<_> = ids.iter().filter(|id| id.id_type() == ty).collect();
+            assert!(of_type.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        let parts: Vec<_> = ids
+            .iter()
+            .map(|id| (id.timestamp_ms(), id.randomness()))
+            .collect();
+        assert!(parts.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn randomness_overflow_advances_timestamp() {
+        let generator = MonotonicGenerator::new(MyType::User);
+        *generator.last.lock().unwrap() = Some((1_000, RANDOM_MASK));
+        let id = generator.generate_at(1_000).unwrap();
+        assert_eq!(id.timestamp_ms(), 1_001);
+
+        *generator.last.lock().unwrap() = Some((TIMESTAMP_MAX, RANDOM_MASK));
+        assert_eq!(
+            generator.generate_at(TIMESTAMP_MAX),
+            Err(TtidError::TimestampOutOfRange)
+        );
+    }
+}
+
+#[cfg(all(test, ttid_loom))]
+mod loom_tests {
+    use loom::sync::Arc;
+    use loom::sync::atomic::AtomicU64;
+    use loom::thread;
+
+    use super::*;
+    use crate::tests::MyType;
+
+    /// A clock going back one second on every reading.
+    struct RegressingClock(AtomicU64);
+
+    impl Clock for RegressingClock {
+        fn now_ms(&self) -> Result<u64, TtidError> {
+            Ok(self.0.fetch_sub(1_000, Ordering::Relaxed))
+        }
+    }
+
+    #[test]
+    fn monotonic_ids_follow_the_observed_order() {
+        loom::model(|| {
+            let events = Arc::new(MonotonicGenerator::with_clock(
+                MyType::Session,
+                ManualClock::new(1_000),
+            ));
+            let published = Arc::new(Mutex::new(None));
+
+            let writer = {
+                let (events, published) = (Arc::clone(&events), Arc::clone(&published));
+                thread::spawn(move || {
+                    let first = events.generate().unwrap();
+                    *published.lock().unwrap() = Some(first);
+                    let second = events.generate().unwrap();
+                    assert!(first < second);
+                    [first, second]
+                })
+            };
+            let reader = {
+                let (events, published) = (Arc::clone(&events), Arc::clone(&published));
+                thread::spawn(move || {
+                    let observed = *published.lock().unwrap();
+                    let id = events.generate().unwrap();
+                    if let Some(observed) = observed {
+                        assert!(observed < id);
+                    }
+                    id
+                })
+            };
+
+            let mut ids = writer.join().unwrap().to_vec();
+            ids.push(reader.join().unwrap());
+            let last = events.generate().unwrap();
+            assert!(ids.iter().all(|id| *id < last));
+            ids.sort();
+            ids.dedup();
+            assert_eq!(ids.len(), 3);
+        });
+    }
+
+    #[test]
+    fn shared_generators_never_go_back_in_time() {
+        loom::model(|| {
+            let ids = Arc::new(TtidGenerator::with_clock(RegressingClock(AtomicU64::new(
+                1_000_000,
+            ))));
+            let published = Arc::new(Mutex::new(None));
+
+            let writer = {
+                let (ids, published) = (Arc::clone(&ids), Arc::clone(&published));
+                thread::spawn(move || {
+                    let first = ids.generate(MyType::User).unwrap();
+                    *published.lock().unwrap() = Some(first);
+                    let second = ids.generate(MyType::User).unwrap();
+                    assert!(first.timestamp_ms() <= second.timestamp_ms());
+                    second
+                })
+            };
+            let reader = {
+                let (ids, published) = (Arc::clone(&ids), Arc::clone(&published));
+                thread::spawn(move || {
+                    let observed = *published.lock().unwrap();
+                    let id = ids.generate(MyType::Org).unwrap();
+                    if let Some(observed) = observed {
+                        assert!(observed.timestamp_ms() <= id.timestamp_ms());
+                    }
+                    id
+                })
+            };
+
+            let newest = [writer.join().unwrap(), reader.join().unwrap()];
+            let last = ids.generate(MyType::User).unwrap();
+            assert!(
+                newest
+                    .iter()
+                    .all(|id| id.timestamp_ms() <= last.timestamp_ms())
+            );
+            assert_eq!(last.timestamp_ms(), 1_000_000);
+        });
+    }
+}
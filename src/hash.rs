@@ -0,0 +1,73 @@
+//! Stable, non-cryptographic hashing for deterministic id derivation.
+//!
+//! Unlike `std::hash`, the output is fixed across Rust versions, platforms and
+//! processes, so values derived from it can be persisted.
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hasher with a final avalanche step.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> &mut Self {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+        self
+    }
+
+    /// Hash a length-prefixed byte string, so adjacent fields cannot collide
+    /// by shifting bytes between them.
+    pub(crate) fn write_str(&mut self, value: &str) -> &mut Self {
+        self.write(&(value.len() as u64).to_le_bytes())
+            .write(value.as_bytes())
+    }
+
+    /// Finish with a splitmix64 finalizer, spreading FNV's weak high bits.
+    pub(crate) fn finish(&self) -> u64 {
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_is_stable() {
+        // Pinned values: changing them breaks persisted derived ids.
+        assert_eq!(StableHasher::new().finish(), 0xf52a_15e9_a9b5_e89b);
+        assert_eq!(
+            StableHasher::new().write(b"ttid").finish(),
+            0xc8f0_4faa_8163_ff59
+        );
+        assert_eq!(
+            StableHasher::new()
+                .write(&5u64.to_le_bytes())
+                .write(b"thumb")
+                .finish(),
+            0x17e8_b93a_0dc2_5337
+        );
+    }
+
+    #[test]
+    fn write_str_is_length_prefixed() {
+        let ab_c = StableHasher::new().write_str("ab").write_str("c").finish();
+        let a_bc = StableHasher::new().write_str("a").write_str("bc").finish();
+        assert_ne!(ab_c, a_bc);
+    }
+}
@@ -0,0 +1,184 @@
+//! Declarative [`IdType`](crate::IdType) domains.
+
+/// Declare a type-domain enum and implement [`IdType`](crate::IdType) for it
+/// from one table of variants, type ids and type names.
+///
+/// Each variant is written `Variant = <type id> => "<type name>"`. The macro
+/// generates:
+/// - the enum with `#[repr(u16)]`, the type ids as discriminants and derived
+///   `Clone` and `Copy`, plus the given attributes,
+/// - `to_type_id` as a discriminant cast and `from_type_id`,
+///   `as_type_name` and `from_type_name` as single `match` expressions, which
+///   the compiler lowers to jump tables and length-first string comparisons,
+/// - exact [`TYPE_NAMES`](crate::IdType::TYPE_NAMES),
+///   [`TYPE_IDS`](crate::IdType::TYPE_IDS) and
+///   [`MAX_TYPE_NAME_LEN`](crate::IdType::MAX_TYPE_NAME_LEN).
+///
+/// Invalid type names, the reserved [`UNTYPED_TYPE_ID`](crate::UNTYPED_TYPE_ID)
+/// and duplicate type ids or names fail to compile.
+///
+/// ```
+/// use ttid::Ttid;
+///
+/// ttid::id_type! {
+///     #[derive(Debug, PartialEq, Eq)]
+///     pub enum MyType {
+///         User = 1 => "user",
+///         Session = 2 => "session",
+///     }
+/// }
+///
+/// let id = Ttid::new(MyType::Session).unwrap();
+/// assert!(id.to_string().starts_with("session_"));
+/// assert_eq!(id.to_string().parse::<Ttid<MyType>>().unwrap(), id);
+/// ```
+#[macro_export]
+macro_rules! id_type {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $id:literal => $type_name:literal
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy)]
+        #[repr(u16)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant = $id,
+            )+
+        }
+
+        const _: () = assert!(
+            $crate::__private::valid_domain(&[$($id),+], &[$($type_name),+]),
+            "invalid id_type! domain: reserved or duplicate type id, or invalid or duplicate type name",
+        );
+
+        impl $crate::IdType for $name {
+            const MAX_TYPE_NAME_LEN: usize =
+                $crate::__private::max_len(&[$($type_name),+]);
+
+            const TYPE_NAMES: &'static [&'static str] = &[$($type_name),+];
+
+            const TYPE_IDS: &'static [u16] = &[$($id),+];
+
+            fn to_type_id(self) -> u16 {
+                self as u16
+            }
+
+            fn from_type_id(id: u16) -> ::core::option::Option<Self> {
+                match id {
+                    $($id => ::core::option::Option::Some(Self::$variant),)+
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn as_type_name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $type_name,)+
+                }
+            }
+
+            fn from_type_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    $($type_name => ::core::option::Option::Some(Self::$variant),)+
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    };
+}
+
+/// Whether the type ids and names form a valid domain: no reserved or
+/// duplicate ids, and valid, distinct names.
+pub const fn valid_domain(ids: &[u16], names: &[&str]) -> bool {
+    let mut i = 0;
+    while i < ids.len() {
+        if ids[i] == crate::UNTYPED_TYPE_ID || crate::validate_type_name(names[i]).is_err() {
+            return false;
+        }
+        let mut j = i + 1;
+        while j < ids.len() {
+            if ids[i] == ids[j] || str_eq(names[i], names[j]) {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Length of the longest name.
+pub const fn max_len(names: &[&str]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < names.len() {
+        if names[i].len() > max {
+            max = names[i].len();
+        }
+        i += 1;
+    }
+    max
+}
+
+pub(crate) const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IdType, Ttid};
+
+    crate::id_type! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Declared {
+            User = 1 => "user",
+            /// Doc comments on variants are kept.
+            Organization = 2 => "organization",
+            Max = 65535 => "max",
+        }
+    }
+
+    #[test]
+    fn declared_domains_map_both_ways() {
+        assert_eq!(Declared::TYPE_NAMES, ["user", "organization", "max"]);
+        assert_eq!(Declared::TYPE_IDS, [1, 2, 65535]);
+        assert_eq!(Declared::MAX_TYPE_NAME_LEN, 12);
+        for ty in [Declared::User, Declared::Organization, Declared::Max] {
+            assert_eq!(Declared::from_type_id(ty.to_type_id()), Some(ty));
+            assert_eq!(Declared::from_type_name(ty.as_type_name()), Some(ty));
+        }
+        assert_eq!(Declared::Max.to_type_id(), 65535);
+        assert_eq!(Declared::from_type_id(3), None);
+        assert_eq!(Declared::from_type_name("users"), None);
+
+        let id = Ttid::from_parts(1_000, Declared::Organization, 7).unwrap();
+        assert_eq!(id.to_string().parse::<Ttid<Declared>>().unwrap(), id);
+    }
+
+    #[test]
+    fn rejects_invalid_domains() {
+        assert!(valid_domain(&[1, 2], &["user", "org"]));
+        assert!(!valid_domain(&[0, 2], &["user", "org"]));
+        assert!(!valid_domain(&[1, 1], &["user", "org"]));
+        assert!(!valid_domain(&[1, 2], &["user", "user"]));
+        assert!(!valid_domain(&[1, 2], &["user", "org_unit"]));
+    }
+}
@@ -0,0 +1,93 @@
+//! Trait implementations for third-party crates, each behind its own feature.
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "bson")]
+mod bson;
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "digest")]
+mod digest;
+#[cfg(feature = "mysql")]
+mod mysql;
+#[cfg(feature = "oracle")]
+mod oracle;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "rand")]
+mod rand;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+#[cfg(feature = "rocket")]
+mod rocket;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "sea-orm")]
+mod sea_orm;
+#[cfg(feature = "speedy")]
+mod speedy;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(feature = "tiberius")]
+mod tiberius;
+#[cfg(feature = "utoipa")]
+mod utoipa;
+#[cfg(feature = "valuable")]
+mod valuable;
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisKey;
+#[cfg(feature = "rkyv")]
+pub use self::rkyv::ArchivedTtid;
+#[cfg(feature = "rusqlite")]
+pub use self::rusqlite::SqliteText;
+
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+use crate::{IdType, SHORT_UUID_MAX_LEN};
+
+/// Character class of the base58 alphabet used for the shortuuid part.
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+const SHORT_UUID_CLASS: &str = "[1-9a-km-zA-HJ-NP-Z]";
+
+/// Anchored regex for `<type-name>_<shortuuid>` strings of domain `T`, for
+/// schema generators.
+///
+/// Restricted to the names in [`IdType::TYPE_NAMES`] if the domain lists them.
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+fn string_pattern<T: IdType>() -> String {
+    let prefix = if T::TYPE_NAMES.is_empty() {
+        format!("[A-Za-z0-9-]{{1,{}}}", T::MAX_TYPE_NAME_LEN)
+    } else {
+        format!("(?:{})", T::TYPE_NAMES.join("|"))
+    };
+    format!("^{prefix}_{SHORT_UUID_CLASS}{{1,{SHORT_UUID_MAX_LEN}}}$")
+}
+
+/// Description of valid ids of domain `T` for rejection messages, e.g.
+/// ``expected prefix `user_` ``, if the domain lists its
+/// [`IdType::TYPE_NAMES`](crate::IdType::TYPE_NAMES).
+#[cfg(any(
+    feature = "actix-web",
+    feature = "axum",
+    feature = "garde",
+    feature = "validator"
+))]
+pub(crate) fn expected_prefixes<T: crate::IdType>() -> Option<String> {
+    match T::TYPE_NAMES {
+        [] => None,
+        [name] => Some(format!("expected prefix `{name}_`")),
+        names => {
+            let prefixes: Vec<_> = names.iter().map(|name| format!("`{name}_`")).collect();
+            Some(format!(
+                "expected one of the prefixes {}",
+                prefixes.join(", ")
+            ))
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! `arbitrary` support for fuzzing.
+//!
+//! Generated ids are always valid: the timestamp is within the 48-bit range
+//! and the type is picked from [`IdType::TYPE_NAMES`]. Domains without listed
+//! names fall back to an arbitrary type id, rejecting inputs the domain does
+//! not know.
+
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+use crate::deser::TIMESTAMP_MAX;
+use crate::{IdType, Ttid, UNTYPED_TYPE_ID};
+
+impl<'a, T: IdType> Arbitrary<'a> for Ttid<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let ty = if T::TYPE_NAMES.is_empty() {
+            let type_id = u16::arbitrary(u)?;
+            T::from_type_id(type_id).filter(|_| type_id != UNTYPED_TYPE_ID)
+        } else {
+            T::from_type_name(u.choose(T::TYPE_NAMES)?)
+        };
+        let ty = ty.ok_or(Error::IncorrectFormat)?;
+
+        let timestamp_ms = u.int_in_range(0..=TIMESTAMP_MAX)?;
+        let randomness = u64::arbitrary(u)?;
+        Ttid::from_parts(timestamp_ms, ty, randomness).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn generates_valid_ids_of_every_listed_type() {
+        let data: Vec<u8> = (0..4096u32)
+            .map(|n| (n.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let mut u = Unstructured::new(&data);
+
+        let mut seen = Vec::new();
+        while !u.is_empty() {
+            let id = Ttid::<MyType>::arbitrary(&mut u).unwrap();
+            assert_eq!(Ttid::<MyType>::from_uuid(id.as_uuid()), Ok(id));
+            if !seen.contains(&id.id_type()) {
+                seen.push(id.id_type());
+            }
+        }
+        assert_eq!(seen.len(), MyType::TYPE_NAMES.len());
+    }
+
+    #[test]
+    fn unlisted_domains_use_known_type_ids_only() {
+        let known = 1u16.to_le_bytes();
+        let id = Ttid::<NarrowType>::arbitrary(&mut Unstructured::new(&known)).unwrap();
+        assert_eq!(id.id_type(), NarrowType::User);
+
+        let unknown = 2u16.to_le_bytes();
+        assert!(Ttid::<NarrowType>::arbitrary(&mut Unstructured::new(&unknown)).is_err());
+    }
+}
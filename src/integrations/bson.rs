@@ -0,0 +1,100 @@
+//! `bson` support for MongoDB.
+//!
+//! TTIDs are stored as BSON binary values with subtype 4 (UUID), the same
+//! representation drivers use for native UUIDs. MongoDB compares binary
+//! values of equal length byte by byte, so indexes on TTID fields follow time
+//! ordering. Reading validates the subtype, the TTID layout and the type id.
+
+use bson::spec::BinarySubtype;
+use bson::{Binary, Bson};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid, TtidError};
+
+impl<T: IdType> From<Ttid<T>> for Binary {
+    fn from(value: Ttid<T>) -> Self {
+        Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: value.as_uuid().as_bytes().to_vec(),
+        }
+    }
+}
+
+impl<T: IdType> From<Ttid<T>> for Bson {
+    fn from(value: Ttid<T>) -> Self {
+        Bson::Binary(value.into())
+    }
+}
+
+impl<T: IdType> TryFrom<Binary> for Ttid<T> {
+    type Error = TtidError;
+
+    fn try_from(value: Binary) -> Result<Self, Self::Error> {
+        if value.subtype != BinarySubtype::Uuid {
+            return Err(TtidError::InvalidUuid);
+        }
+        let bytes =
+            <[u8; 16]>::try_from(value.bytes.as_slice()).map_err(|_| TtidError::InvalidUuid)?;
+        Ttid::from_uuid(Uuid::from_bytes(bytes))
+    }
+}
+
+impl<T: IdType> TryFrom<Bson> for Ttid<T> {
+    type Error = TtidError;
+
+    fn try_from(value: Bson) -> Result<Self, Self::Error> {
+        match value {
+            Bson::Binary(binary) => binary.try_into(),
+            _ => Err(TtidError::InvalidUuid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn binary_subtype_4_roundtrip() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let value = Bson::from(ttid);
+        let Bson::Binary(binary) = &value else {
+            panic!("expected binary, got {value:?}");
+        };
+        assert_eq!(binary.subtype, BinarySubtype::Uuid);
+        assert_eq!(binary.bytes, ttid.as_uuid().as_bytes());
+
+        assert_eq!(Ttid::<MyType>::try_from(value).unwrap(), ttid);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let session = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 1).unwrap();
+        assert_eq!(
+            Ttid::<NarrowType>::try_from(Bson::from(session)),
+            Err(TtidError::UnknownTypeId(777))
+        );
+
+        let generic = Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: session.as_uuid().as_bytes().to_vec(),
+        };
+        assert_eq!(
+            Ttid::<MyType>::try_from(generic),
+            Err(TtidError::InvalidUuid)
+        );
+
+        let short = Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: vec![1, 2, 3],
+        };
+        assert_eq!(Ttid::<MyType>::try_from(short), Err(TtidError::InvalidUuid));
+
+        assert_eq!(
+            Ttid::<MyType>::try_from(Bson::String(session.to_string())),
+            Err(TtidError::InvalidUuid)
+        );
+    }
+}
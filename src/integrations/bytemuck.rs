@@ -0,0 +1,47 @@
+use bytemuck::{CheckedBitPattern, NoUninit};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+// SAFETY: `Ttid` is `repr(transparent)` over `Uuid`, which is
+// `repr(transparent)` over `[u8; 16]`, so it has no padding or uninitialized
+// bytes.
+unsafe impl<T: IdType + 'static> NoUninit for Ttid<T> {}
+
+// SAFETY: same layout as above, `Bits` has the size and alignment of `Ttid`.
+// Only byte patterns accepted by `Ttid::from_uuid` are valid.
+unsafe impl<T: IdType + 'static> CheckedBitPattern for Ttid<T> {
+    type Bits = [u8; 16];
+
+    fn is_valid_bit_pattern(bits: &Self::Bits) -> bool {
+        Ttid::<T>::from_uuid(Uuid::from_bytes(*bits)).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::checked::{CheckedCastError, try_cast_slice};
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn slices_cast_to_bytes_and_back_with_validation() {
+        let ids: Vec<_> = (1..=3)
+            .map(|n| Ttid::<MyType>::from_parts(n * 1_000, MyType::User, n).unwrap())
+            .collect();
+
+        let raw: &[[u8; 16]] = bytemuck::cast_slice(&ids);
+        assert_eq!(raw[1], *ids[1].as_uuid().as_bytes());
+        assert_eq!(bytemuck::cast_slice::<_, u8>(&ids).len(), 48);
+
+        assert_eq!(try_cast_slice::<_, Ttid<MyType>>(raw).unwrap(), &ids[..]);
+
+        let session = Ttid::<MyType>::from_parts(1, MyType::Session, 1).unwrap();
+        let foreign = [*session.as_uuid().as_bytes()];
+        assert_eq!(
+            try_cast_slice::<_, Ttid<NarrowType>>(&foreign[..]),
+            Err(CheckedCastError::InvalidBitPattern)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! `diesel` support.
+//!
+//! On Postgres, TTIDs map to the `Uuid` SQL type. With `diesel-sqlite`, they
+//! map to `Binary` (16 bytes) or, as a fallback for existing text columns, to
+//! `Text` in the `<type-name>_<shortuuid>` form. Reading `Text` also accepts
+//! hyphenated UUIDs. Decoding validates the TTID layout and the type id.
+
+use std::error::Error;
+use std::fmt::Debug;
+
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types;
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+impl<T: IdType + Debug> ToSql<sql_types::Uuid, Pg> for Ttid<T> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <Uuid as ToSql<sql_types::Uuid, Pg>>::to_sql(&self.uuid, out)
+    }
+}
+
+impl<T: IdType> FromSql<sql_types::Uuid, Pg> for Ttid<T> {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let uuid = <Uuid as FromSql<sql_types::Uuid, Pg>>::from_sql(value)?;
+        Ttid::from_uuid(uuid).map_err(invalid)
+    }
+}
+
+/// Describe a decode failure, diesel reports it as a deserialization error.
+fn invalid(err: impl Error) -> Box<dyn Error + Send + Sync> {
+    format!("invalid TTID column value: {err}").into()
+}
+
+#[cfg(feature = "diesel-sqlite")]
+mod sqlite {
+    use diesel::sqlite::{Sqlite, SqliteValue};
+
+    use super::*;
+
+    impl<T: IdType + Debug> ToSql<sql_types::Binary, Sqlite> for Ttid<T> {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(&self.uuid.as_bytes()[..]);
+            Ok(serialize::IsNull::No)
+        }
+    }
+
+    impl<T: IdType + Debug> ToSql<sql_types::Text, Sqlite> for Ttid<T> {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.to_string());
+            Ok(serialize::IsNull::No)
+        }
+    }
+
+    impl<T: IdType> FromSql<sql_types::Binary, Sqlite> for Ttid<T> {
+        fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+            let bytes = <Vec<u8> as FromSql<sql_types::Binary, Sqlite>>::from_sql(value)?;
+            let bytes = <[u8; 16]>::try_from(bytes.as_slice()).map_err(|_| {
+                format!(
+                    "invalid TTID column value: expected 16 bytes, got {}",
+                    bytes.len()
+                )
+            })?;
+            Ttid::from_uuid(Uuid::from_bytes(bytes)).map_err(invalid)
+        }
+    }
+
+    impl<T: IdType> FromSql<sql_types::Text, Sqlite> for Ttid<T> {
+        fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+            let text = <String as FromSql<sql_types::Text, Sqlite>>::from_sql(value)?;
+            if text.contains('_') {
+                text.parse().map_err(invalid)
+            } else {
+                let uuid = Uuid::try_parse(&text).map_err(invalid)?;
+                Ttid::from_uuid(uuid).map_err(invalid)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use diesel::prelude::*;
+        use diesel::sql_types::{Binary, Text};
+
+        use crate::Ttid;
+        use crate::tests::{MyType, NarrowType};
+
+        #[derive(QueryableByName, Debug, PartialEq)]
+        struct Row {
+            #[diesel(sql_type = Binary)]
+            id: Ttid<MyType>,
+            #[diesel(sql_type = Text)]
+            label: Ttid<MyType>,
+        }
+
+        #[derive(QueryableByName, Debug)]
+        struct NarrowRow {
+            #[diesel(sql_type = Binary)]
+            #[allow(dead_code)]
+            id: Ttid<NarrowType>,
+        }
+
+        #[test]
+        fn binary_and_text_columns_roundtrip() {
+            let mut conn = SqliteConnection::establish(":memory:").unwrap();
+            diesel::sql_query("CREATE TABLE ids (id BLOB NOT NULL, label TEXT NOT NULL)")
+                .execute(&mut conn)
+                .unwrap();
+
+            let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+            diesel::sql_query("INSERT INTO ids (id, label) VALUES (?, ?)")
+                .bind::<Binary, _>(id)
+                .bind::<Text, _>(id)
+                .execute(&mut conn)
+                .unwrap();
+
+            let row: Row = diesel::sql_query("SELECT id, label FROM ids")
+                .get_result(&mut conn)
+                .unwrap();
+            assert_eq!(row, Row { id, label: id });
+
+            let err = diesel::sql_query("SELECT id FROM ids")
+                .get_result::<NarrowRow>(&mut conn)
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("invalid TTID column value"),
+                "{err}"
+            );
+
+            let row: Row = diesel::sql_query("SELECT id, ? AS label FROM ids")
+                .bind::<Text, _>(id.as_uuid().to_string())
+                .get_result(&mut conn)
+                .unwrap();
+            assert_eq!(row.label, id);
+        }
+    }
+}
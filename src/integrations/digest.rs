@@ -0,0 +1,36 @@
+use digest::Update;
+
+use crate::{IdType, Ttid};
+
+impl<T: IdType> Ttid<T> {
+    /// Feed the id into a hasher, e.g. `sha2::Sha256`, for content hashes
+    /// that include ids.
+    ///
+    /// Writes the 16 big-endian UUID bytes, the same bytes on every platform
+    /// and for every string form of the id.
+    pub fn update_hash(&self, hasher: &mut impl Update) {
+        hasher.update(self.as_uuid().as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use crate::Ttid;
+    use crate::tests::MyType;
+
+    #[test]
+    fn hashes_the_uuid_bytes() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let mut hasher = Sha256::new();
+        id.update_hash(&mut hasher);
+        Digest::update(&mut hasher, b"body");
+
+        let mut expected = Sha256::new();
+        Digest::update(&mut expected, id.as_uuid().as_bytes());
+        Digest::update(&mut expected, b"body");
+        assert_eq!(hasher.finalize(), expected.finalize());
+    }
+}
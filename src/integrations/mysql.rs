@@ -0,0 +1,91 @@
+//! `mysql_common` support, shared by the `mysql` and `mysql_async` drivers.
+//!
+//! TTIDs are written as `BINARY(16)`. Reading accepts `BINARY(16)` as well as
+//! string columns holding either the TTID string form or a hyphenated UUID.
+
+use mysql_common::value::Value;
+use mysql_common::value::convert::{FromValue, FromValueError};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+impl<T: IdType> From<Ttid<T>> for Value {
+    fn from(value: Ttid<T>) -> Self {
+        Value::Bytes(value.as_uuid().as_bytes().to_vec())
+    }
+}
+
+/// Intermediate representation required by [`FromValue`].
+#[doc(hidden)]
+pub struct TtidIr<T: IdType>(Ttid<T>);
+
+impl<T: IdType> TryFrom<Value> for TtidIr<T> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Bytes(bytes) = &value else {
+            return Err(FromValueError(value));
+        };
+
+        let parsed = match <[u8; 16]>::try_from(bytes.as_slice()) {
+            Ok(raw) => Ttid::from_uuid(Uuid::from_bytes(raw)).ok(),
+            Err(_) => std::str::from_utf8(bytes).ok().and_then(|text| {
+                text.parse::<Ttid<T>>().ok().or_else(|| {
+                    Uuid::parse_str(text)
+                        .ok()
+                        .and_then(|uuid| Ttid::from_uuid(uuid).ok())
+                })
+            }),
+        };
+
+        parsed.map(TtidIr).ok_or(FromValueError(value))
+    }
+}
+
+impl<T: IdType> From<TtidIr<T>> for Ttid<T> {
+    fn from(value: TtidIr<T>) -> Self {
+        value.0
+    }
+}
+
+impl<T: IdType> FromValue for Ttid<T> {
+    type Intermediate = TtidIr<T>;
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_common::value::convert::from_value_opt;
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn binary_roundtrip() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let value = Value::from(ttid);
+        assert_eq!(value, Value::Bytes(ttid.as_uuid().as_bytes().to_vec()));
+        assert_eq!(from_value_opt::<Ttid<MyType>>(value).unwrap(), ttid);
+    }
+
+    #[test]
+    fn accepts_string_columns() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let text = Value::Bytes(ttid.to_string().into_bytes());
+        assert_eq!(from_value_opt::<Ttid<MyType>>(text).unwrap(), ttid);
+
+        let hyphenated = Value::Bytes(ttid.as_uuid().to_string().into_bytes());
+        assert_eq!(from_value_opt::<Ttid<MyType>>(hyphenated).unwrap(), ttid);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let session = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 1).unwrap();
+
+        assert!(from_value_opt::<Ttid<NarrowType>>(Value::from(session)).is_err());
+        assert!(from_value_opt::<Ttid<MyType>>(Value::Bytes(vec![1, 2, 3])).is_err());
+        assert!(from_value_opt::<Ttid<MyType>>(Value::Int(1)).is_err());
+        assert!(from_value_opt::<Ttid<MyType>>(Value::NULL).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! `oracle` support for `RAW(16)` columns.
+//!
+//! TTIDs are stored as their 16 UUID bytes. Oracle compares `RAW` values
+//! byte by byte, so ordering by a `RAW(16)` column matches time ordering.
+//! Reading also accepts character columns holding either the TTID string
+//! form or a hyphenated UUID, and validates the TTID layout and the type id.
+
+use oracle::sql_type::{FromSql, OracleType, ToSql, ToSqlNull};
+use oracle::{Connection, SqlValue};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+impl<T: IdType> ToSqlNull for Ttid<T> {
+    fn oratype_for_null(_conn: &Connection) -> oracle::Result<OracleType> {
+        Ok(OracleType::Raw(16))
+    }
+}
+
+impl<T: IdType> ToSql for Ttid<T> {
+    fn oratype(&self, _conn: &Connection) -> oracle::Result<OracleType> {
+        Ok(OracleType::Raw(16))
+    }
+
+    fn to_sql(&self, val: &mut SqlValue) -> oracle::Result<()> {
+        val.set(&raw(self))
+    }
+}
+
+impl<T: IdType> FromSql for Ttid<T> {
+    fn from_sql(val: &SqlValue) -> oracle::Result<Self> {
+        match val.oracle_type()? {
+            OracleType::Varchar2(_)
+            | OracleType::NVarchar2(_)
+            | OracleType::Char(_)
+            | OracleType::NChar(_)
+            | OracleType::Long
+            | OracleType::CLOB
+            | OracleType::NCLOB => decode_text(&String::from_sql(val)?),
+            _ => decode_raw(&Vec::<u8>::from_sql(val)?),
+        }
+    }
+}
+
+/// The `RAW(16)` value of `ttid`.
+fn raw<T: IdType>(ttid: &Ttid<T>) -> Vec<u8> {
+    ttid.as_uuid().as_bytes().to_vec()
+}
+
+/// Decode the 16 UUID bytes of a `RAW(16)` value.
+fn decode_raw<T: IdType>(bytes: &[u8]) -> oracle::Result<Ttid<T>> {
+    let bytes = <[u8; 16]>::try_from(bytes).map_err(|_| {
+        oracle::Error::ParseError(format!("expected 16 bytes, got {}", bytes.len()).into())
+    })?;
+    Ttid::from_uuid(Uuid::from_bytes(bytes)).map_err(|err| oracle::Error::ParseError(err.into()))
+}
+
+/// Decode the TTID string form or a hyphenated UUID.
+fn decode_text<T: IdType>(text: &str) -> oracle::Result<Ttid<T>> {
+    if text.contains('_') {
+        text.parse()
+            .map_err(|err| oracle::Error::ParseError(Box::new(err)))
+    } else {
+        let uuid = Uuid::try_parse(text).map_err(|err| oracle::Error::ParseError(Box::new(err)))?;
+        Ttid::from_uuid(uuid).map_err(|err| oracle::Error::ParseError(err.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn raw_and_string_roundtrip() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let bytes = raw(&ttid);
+        assert_eq!(bytes, ttid.as_uuid().as_bytes());
+        assert_eq!(decode_raw::<MyType>(&bytes).unwrap(), ttid);
+        assert_eq!(decode_text::<MyType>(&ttid.to_string()).unwrap(), ttid);
+        assert_eq!(
+            decode_text::<MyType>(&ttid.as_uuid().to_string()).unwrap(),
+            ttid
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let session = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 1).unwrap();
+
+        let err = decode_raw::<MyType>(&[1, 2, 3]).unwrap_err();
+        assert!(
+            err.to_string().contains("expected 16 bytes, got 3"),
+            "{err}"
+        );
+        assert!(decode_raw::<MyType>(Uuid::new_v4().as_bytes()).is_err());
+        assert!(decode_raw::<NarrowType>(&raw(&session)).is_err());
+
+        assert!(decode_text::<MyType>("user_").is_err());
+        assert!(decode_text::<MyType>(&Uuid::new_v4().to_string()).is_err());
+        assert!(decode_text::<NarrowType>(&session.to_string()).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! `postgres-types` support, for `tokio-postgres` and `postgres`.
+//!
+//! TTIDs map to the `uuid` Postgres type. Decoding validates the TTID layout
+//! and the type id.
+
+use std::error::Error;
+use std::fmt::Debug;
+
+use bytes::BytesMut;
+use postgres_types::{FromSql, IsNull, ToSql, Type, accepts, to_sql_checked};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+impl<T: IdType + Debug> ToSql for Ttid<T> {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.uuid.to_sql(ty, out)
+    }
+
+    accepts!(UUID);
+
+    to_sql_checked!();
+}
+
+impl<'a, T: IdType> FromSql<'a> for Ttid<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let uuid = Uuid::from_sql(ty, raw)?;
+        Ok(Ttid::from_uuid(uuid)?)
+    }
+
+    accepts!(UUID);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TtidError;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn uuid_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        let mut buf = BytesMut::new();
+        assert!(matches!(
+            id.to_sql_checked(&Type::UUID, &mut buf),
+            Ok(IsNull::No)
+        ));
+        assert_eq!(&buf[..], id.as_uuid().as_bytes());
+        assert_eq!(Ttid::<MyType>::from_sql(&Type::UUID, &buf).unwrap(), id);
+
+        assert!(
+            id.to_sql_checked(&Type::TEXT, &mut BytesMut::new())
+                .is_err()
+        );
+        assert!(!<Ttid<MyType> as FromSql>::accepts(&Type::TEXT));
+    }
+
+    #[test]
+    fn decoding_validates() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+        let err = Ttid::<NarrowType>::from_sql(&Type::UUID, id.as_uuid().as_bytes()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<TtidError>(),
+            Some(&TtidError::UnknownTypeId(777))
+        );
+
+        assert!(Ttid::<MyType>::from_sql(&Type::UUID, Uuid::nil().as_bytes()).is_err());
+        assert!(Ttid::<MyType>::from_sql(&Type::UUID, &[0; 4]).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use rand::Rng;
+
+use crate::{IdType, RANDOM_MASK, Ttid, TtidError, now_ms};
+
+impl<T: IdType> Ttid<T> {
+    /// Create a new TTID like [`Ttid::new`], with the randomness drawn from
+    /// `rng`, e.g. a seeded RNG for reproducible tests or a hardware RNG.
+    ///
+    /// Takes the low 58 bits of one `u64` from `rng`.
+    pub fn new_with_rng<R: Rng + ?Sized>(ty: T, rng: &mut R) -> Result<Self, TtidError> {
+        Self::from_parts(now_ms()?, ty, rng.next_u64() & RANDOM_MASK)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn seeded_rngs_give_reproducible_randomness() {
+        let a = Ttid::new_with_rng(MyType::User, &mut StdRng::seed_from_u64(7)).unwrap();
+        let b = Ttid::new_with_rng(MyType::User, &mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(a.randomness(), b.randomness());
+        assert_eq!(
+            a.randomness(),
+            StdRng::seed_from_u64(7).next_u64() & RANDOM_MASK
+        );
+        assert_eq!(a.id_type(), MyType::User);
+    }
+}
@@ -0,0 +1,119 @@
+//! `redis` support.
+//!
+//! As command arguments, TTIDs are written in the compact 16-byte form, for
+//! values and hash fields. Wrap ids used in key names in [`RedisKey`] to
+//! write the readable `<type-name>_<shortuuid>` form instead.
+//!
+//! Reading accepts both forms, as well as hyphenated UUID strings, and
+//! validates the TTID layout and the type id.
+
+use redis::{ErrorKind, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs, Value};
+use uuid::Uuid;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// A [`Ttid`] written as `<type-name>_<shortuuid>` by `redis`, for key
+/// names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RedisKey<T: IdType>(pub Ttid<T>);
+
+impl<T: IdType> ToRedisArgs for Ttid<T> {
+    fn write_redis_args<W: ?Sized + RedisWrite>(&self, out: &mut W) {
+        out.write_arg(self.uuid.as_bytes());
+    }
+}
+
+impl<T: IdType> FromRedisValue for Ttid<T> {
+    fn from_redis_value(value: &Value) -> RedisResult<Self> {
+        let parsed = match value {
+            Value::BulkString(bytes) => match <[u8; 16]>::try_from(bytes.as_slice()) {
+                Ok(raw) => Ttid::from_uuid(Uuid::from_bytes(raw)).map_err(ParseTtidError::from),
+                Err(_) => std::str::from_utf8(bytes)
+                    .map_err(|_| ParseTtidError::InvalidFormat)
+                    .and_then(parse_text),
+            },
+            Value::SimpleString(text) => parse_text(text),
+            _ => {
+                return Err((
+                    ErrorKind::TypeError,
+                    "Response was of incompatible type",
+                    format!("expected a TTID, got {value:?}"),
+                )
+                    .into());
+            }
+        };
+        parsed.map_err(|err| {
+            (
+                ErrorKind::TypeError,
+                "Response was of incompatible type",
+                format!("invalid TTID: {err}"),
+            )
+                .into()
+        })
+    }
+}
+
+/// Parse the TTID string form or a hyphenated UUID.
+fn parse_text<T: IdType>(text: &str) -> Result<Ttid<T>, ParseTtidError> {
+    match Uuid::try_parse(text) {
+        Ok(uuid) => Ok(Ttid::from_uuid(uuid)?),
+        Err(_) => text.parse(),
+    }
+}
+
+impl<T: IdType> ToRedisArgs for RedisKey<T> {
+    fn write_redis_args<W: ?Sized + RedisWrite>(&self, out: &mut W) {
+        out.write_arg_fmt(self.0);
+    }
+}
+
+impl<T: IdType> FromRedisValue for RedisKey<T> {
+    fn from_redis_value(value: &Value) -> RedisResult<Self> {
+        Ttid::from_redis_value(value).map(Self)
+    }
+}
+
+impl<T: IdType> From<Ttid<T>> for RedisKey<T> {
+    fn from(value: Ttid<T>) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn writes_bytes_for_values_and_strings_for_keys() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        assert_eq!(id.to_redis_args(), vec![id.as_uuid().as_bytes().to_vec()]);
+        assert_eq!(
+            RedisKey(id).to_redis_args(),
+            vec![id.to_string().into_bytes()]
+        );
+    }
+
+    #[test]
+    fn reads_all_forms_and_validates() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        let values = [
+            Value::BulkString(id.as_uuid().as_bytes().to_vec()),
+            Value::BulkString(id.to_string().into_bytes()),
+            Value::SimpleString(id.as_uuid().to_string()),
+        ];
+        for value in &values {
+            assert_eq!(Ttid::<MyType>::from_redis_value(value).unwrap(), id);
+            assert_eq!(
+                RedisKey::<MyType>::from_redis_value(value).unwrap(),
+                RedisKey(id)
+            );
+            assert!(Ttid::<NarrowType>::from_redis_value(value).is_err());
+        }
+
+        assert!(Ttid::<MyType>::from_redis_value(&Value::Nil).is_err());
+        assert!(Ttid::<MyType>::from_redis_value(&Value::BulkString(vec![0; 4])).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use std::marker::PhantomData;
+
+use rkyv::bytecheck::CheckBytes;
+use rkyv::munge::munge;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+/// Archived form of a [`Ttid`]: the 16 UUID bytes.
+///
+/// Validation (`rkyv::access`) checks the TTID layout and that the type id is
+/// known by `T`, so archived ids can be read without further checks.
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ArchivedTtid<T: IdType> {
+    bytes: [u8; 16],
+    marker: PhantomData<T>,
+}
+
+impl<T: IdType> ArchivedTtid<T> {
+    /// Borrow the raw UUID bytes.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.bytes
+    }
+
+    /// Return the archived id.
+    ///
+    /// Does not re-validate: the bytes were checked when the archive was
+    /// accessed. Reading archives with `rkyv::access_unchecked` that were not
+    /// produced by this crate may yield ids that panic on decoding.
+    pub fn get(&self) -> Ttid<T> {
+        Ttid {
+            uuid: Uuid::from_bytes(self.bytes),
+            marker: PhantomData,
+        }
+    }
+}
+
+// SAFETY: `ArchivedTtid` is a transparent wrapper around a byte array, which
+// has no alignment or endianness requirements.
+unsafe impl<T: IdType> Portable for ArchivedTtid<T> {}
+
+// SAFETY: `check_bytes` only reads the 16 bytes of the value, which are always
+// initialized, and accepts exactly the byte patterns `ArchivedTtid::get`
+// relies on.
+unsafe impl<T, C> CheckBytes<C> for ArchivedTtid<T>
+where
+    T: IdType,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, _context: &mut C) -> Result<(), C::Error> {
+        // SAFETY: the caller guarantees `value` points to a readable
+        // `ArchivedTtid`, which is layout-compatible with `[u8; 16]`.
+        let bytes = unsafe { *value.cast::<[u8; 16]>() };
+        Ttid::<T>::from_uuid(Uuid::from_bytes(bytes))
+            .map(drop)
+            .map_err(C::Error::new)
+    }
+}
+
+impl<T: IdType> Archive for Ttid<T> {
+    type Archived = ArchivedTtid<T>;
+    type Resolver = ();
+
+    fn resolve(&self, _resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedTtid { bytes, marker: _ } = out);
+        bytes.write(*self.uuid.as_bytes());
+    }
+}
+
+impl<T: IdType, S: Fallible + ?Sized> Serialize<S> for Ttid<T> {
+    fn serialize(&self, _serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<T: IdType, D: Fallible + ?Sized> Deserialize<Ttid<T>, D> for ArchivedTtid<T> {
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Ttid<T>, D::Error> {
+        Ok(self.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::rancor::Error;
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn archive_roundtrips_without_copying() {
+        let ids: Vec<_> = (0..100)
+            .map(|n| Ttid::<MyType>::from_parts(1_700_000_000_000 + n, MyType::Org, n).unwrap())
+            .collect();
+
+        let bytes = rkyv::to_bytes::<Error>(&ids).unwrap();
+        let archived = rkyv::access::<rkyv::Archived<Vec<Ttid<MyType>>>, Error>(&bytes).unwrap();
+
+        assert_eq!(archived.len(), ids.len());
+        for (archived, id) in archived.iter().zip(&ids) {
+            assert_eq!(archived.get(), *id);
+            assert_eq!(archived.as_bytes(), id.as_uuid().as_bytes());
+        }
+        assert_eq!(
+            rkyv::deserialize::<Vec<Ttid<MyType>>, Error>(archived).unwrap(),
+            ids
+        );
+    }
+
+    #[test]
+    fn validation_rejects_foreign_ids() {
+        let session = Ttid::<MyType>::from_parts(1, MyType::Session, 1).unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&session).unwrap();
+
+        assert!(rkyv::access::<ArchivedTtid<MyType>, Error>(&bytes).is_ok());
+        assert!(rkyv::access::<ArchivedTtid<NarrowType>, Error>(&bytes).is_err());
+
+        let mut corrupted = bytes.clone();
+        corrupted[6] = 0;
+        assert!(rkyv::access::<ArchivedTtid<MyType>, Error>(&corrupted).is_err());
+    }
+}
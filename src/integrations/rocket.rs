@@ -0,0 +1,79 @@
+//! `rocket` support for route parameters and form fields.
+//!
+//! Invalid ids are rejected with `422 Unprocessable Entity`: failed
+//! parameters forward with that status, failed form fields are validation
+//! errors.
+
+use rocket::form::{self, FromFormField, ValueField};
+use rocket::request::FromParam;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+impl<'a, T: IdType> FromParam<'a> for Ttid<T> {
+    type Error = ParseTtidError;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        param.parse()
+    }
+}
+
+impl<'v, T: IdType + Send> FromFormField<'v> for Ttid<T> {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        field
+            .value
+            .parse()
+            .map_err(|err: ParseTtidError| form::Error::validation(err.to_string()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::form::{Form, FromForm};
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, post, routes};
+
+    use crate::Ttid;
+    use crate::tests::MyType;
+
+    #[get("/users/<id>")]
+    fn user(id: Ttid<MyType>) -> String {
+        id.to_string()
+    }
+
+    #[derive(FromForm)]
+    struct Invite {
+        org: Ttid<MyType>,
+    }
+
+    #[post("/invites", data = "<invite>")]
+    fn invite(invite: Form<Invite>) -> String {
+        invite.org.to_string()
+    }
+
+    #[test]
+    fn params_and_form_fields() {
+        let client = Client::tracked(rocket::build().mount("/", routes![user, invite])).unwrap();
+        let org = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+
+        let response = client.get(format!("/users/{org}")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), org.to_string());
+        let response = client.get("/users/nope").dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .post("/invites")
+            .header(ContentType::Form)
+            .body(format!("org={org}"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), org.to_string());
+        let response = client
+            .post("/invites")
+            .header(ContentType::Form)
+            .body("org=team_1")
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+}
@@ -0,0 +1,138 @@
+//! `rusqlite` support.
+//!
+//! TTIDs are stored as 16-byte blobs, which keep them compact and sort by
+//! time under SQLite's `memcmp` blob ordering. Wrap ids in [`SqliteText`] to
+//! store the `<type-name>_<shortuuid>` form in text columns instead.
+//!
+//! Reading accepts both storage forms, as well as hyphenated UUID strings,
+//! and validates the TTID layout and the type id.
+
+use rusqlite::ToSql;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use uuid::Uuid;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// A [`Ttid`] stored as `<type-name>_<shortuuid>` text by `rusqlite`.
+///
+/// Reads the same forms as [`Ttid`] itself, so columns can be migrated
+/// between blob and text storage without breaking readers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SqliteText<T: IdType>(pub Ttid<T>);
+
+impl<T: IdType> ToSql for Ttid<T> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Blob(self.uuid.as_bytes())))
+    }
+}
+
+impl<T: IdType> FromSql for Ttid<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Blob(bytes) => {
+                let raw =
+                    <[u8; 16]>::try_from(bytes).map_err(|_| FromSqlError::InvalidBlobSize {
+                        expected_size: 16,
+                        blob_size: bytes.len(),
+                    })?;
+                Ttid::from_uuid(Uuid::from_bytes(raw))
+                    .map_err(|err| FromSqlError::Other(err.into()))
+            }
+            ValueRef::Text(text) => {
+                let text =
+                    std::str::from_utf8(text).map_err(|err| FromSqlError::Other(err.into()))?;
+                parse_text(text).map_err(|err| FromSqlError::Other(err.into()))
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Parse the TTID string form or a hyphenated UUID.
+fn parse_text<T: IdType>(text: &str) -> Result<Ttid<T>, ParseTtidError> {
+    match Uuid::try_parse(text) {
+        Ok(uuid) => Ok(Ttid::from_uuid(uuid)?),
+        Err(_) => text.parse(),
+    }
+}
+
+impl<T: IdType> ToSql for SqliteText<T> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0.to_string()))
+    }
+}
+
+impl<T: IdType> FromSql for SqliteText<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ttid::column_result(value).map(Self)
+    }
+}
+
+impl<T: IdType> From<Ttid<T>> for SqliteText<T> {
+    fn from(value: Ttid<T>) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn blob_and_text_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE ids (blob BLOB, text TEXT)", [])
+            .unwrap();
+
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+        conn.execute("INSERT INTO ids VALUES (?1, ?2)", (id, SqliteText(id)))
+            .unwrap();
+
+        let (blob, text): (Vec<u8>, String) = conn
+            .query_row("SELECT blob, text FROM ids", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(blob, id.as_uuid().as_bytes());
+        assert_eq!(text, id.to_string());
+
+        let (from_blob, from_text): (Ttid<MyType>, SqliteText<MyType>) = conn
+            .query_row("SELECT blob, text FROM ids", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(from_blob, id);
+        assert_eq!(from_text, SqliteText(id));
+
+        let err = conn
+            .query_row("SELECT blob FROM ids", [], |row| {
+                row.get::<_, Ttid<NarrowType>>(0)
+            })
+            .unwrap_err();
+        assert!(matches!(err, rusqlite::Error::FromSqlConversionFailure(..)));
+    }
+
+    #[test]
+    fn column_result_validates() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 7).unwrap();
+        let hyphenated = id.as_uuid().to_string();
+        assert_eq!(
+            Ttid::<MyType>::column_result(ValueRef::Text(hyphenated.as_bytes())).unwrap(),
+            id
+        );
+
+        assert!(matches!(
+            Ttid::<MyType>::column_result(ValueRef::Blob(&[0; 4])),
+            Err(FromSqlError::InvalidBlobSize { blob_size: 4, .. })
+        ));
+        assert!(Ttid::<MyType>::column_result(ValueRef::Blob(Uuid::nil().as_bytes())).is_err());
+        assert!(Ttid::<MyType>::column_result(ValueRef::Text(b"user_garbage")).is_err());
+        assert!(matches!(
+            Ttid::<MyType>::column_result(ValueRef::Integer(1)),
+            Err(FromSqlError::InvalidType)
+        ));
+    }
+}
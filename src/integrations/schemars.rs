@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+use super::string_pattern;
+use crate::{IdType, Ttid};
+
+impl<T: IdType> JsonSchema for Ttid<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("Ttid")
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Cow::Owned(format!("ttid::Ttid<{}>", std::any::type_name::<T>()))
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": string_pattern::<T>(),
+            "maxLength": T::MAX_STRING_LEN,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn schema_pattern_uses_known_type_names() {
+        let schema = schemars::schema_for!(Ttid<MyType>);
+
+        assert_eq!(schema.get("type").unwrap(), "string");
+        assert_eq!(
+            schema.get("pattern").unwrap(),
+            "^(?:user|org|session|max)_[1-9a-km-zA-HJ-NP-Z]{1,22}$"
+        );
+        assert_eq!(schema.get("maxLength").unwrap(), 30);
+
+        assert_eq!(
+            string_pattern::<NarrowType>(),
+            "^[A-Za-z0-9-]{1,16}_[1-9a-km-zA-HJ-NP-Z]{1,22}$"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! `sea-orm` support.
+//!
+//! TTIDs are values of the `Uuid` column type, so entity models can use
+//! `Ttid<T>` fields, primary keys included, directly. Reading validates the
+//! TTID layout and the type id.
+
+use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, ValueType, ValueTypeErr};
+use sea_orm::{
+    ActiveValue, ColIdx, DbErr, IntoActiveValue, QueryResult, TryFromU64, TryGetError, TryGetable,
+    Value,
+};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+impl<T: IdType> From<Ttid<T>> for Value {
+    fn from(value: Ttid<T>) -> Self {
+        Value::Uuid(Some(Box::new(value.as_uuid())))
+    }
+}
+
+impl<T: IdType> ValueType for Ttid<T> {
+    fn try_from(value: Value) -> Result<Self, ValueTypeErr> {
+        match value {
+            Value::Uuid(Some(uuid)) => Ttid::from_uuid(*uuid).map_err(|_| ValueTypeErr),
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        "Ttid".to_owned()
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::Uuid
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::Uuid
+    }
+}
+
+impl<T: IdType> Nullable for Ttid<T> {
+    fn null() -> Value {
+        Value::Uuid(None)
+    }
+}
+
+impl<T: IdType> TryGetable for Ttid<T> {
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let uuid = Uuid::try_get_by(res, index)?;
+        Ttid::from_uuid(uuid).map_err(|err| {
+            TryGetError::DbErr(DbErr::Type(format!(
+                "invalid TTID in column {index:?}: {err}"
+            )))
+        })
+    }
+}
+
+/// TTIDs are never auto-increment keys.
+impl<T: IdType> TryFromU64 for Ttid<T> {
+    fn try_from_u64(_: u64) -> Result<Self, DbErr> {
+        Err(DbErr::ConvertFromU64("Ttid"))
+    }
+}
+
+impl<T: IdType> IntoActiveValue<Ttid<T>> for Ttid<T> {
+    fn into_active_value(self) -> ActiveValue<Self> {
+        ActiveValue::Set(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn value_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        let value = Value::from(id);
+        assert_eq!(value, Value::Uuid(Some(Box::new(id.as_uuid()))));
+        assert_eq!(
+            <Ttid<MyType> as ValueType>::try_from(value.clone()).unwrap(),
+            id
+        );
+        assert_eq!(Ttid::<MyType>::column_type(), ColumnType::Uuid);
+        assert_eq!(Ttid::<MyType>::null(), Value::Uuid(None));
+        assert!(matches!(id.into_active_value(), ActiveValue::Set(set) if set == id));
+
+        assert!(<Ttid<NarrowType> as ValueType>::try_from(value).is_err());
+        assert!(<Ttid<MyType> as ValueType>::try_from(Value::Uuid(None)).is_err());
+        assert!(<Ttid<MyType> as ValueType>::try_from(Value::Int(Some(1))).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+//! `speedy` support.
+//!
+//! TTIDs are written as their 16 big-endian UUID bytes, independent of the
+//! context's endianness. Reading validates the TTID layout and the type id.
+
+use speedy::{Context, Readable, Reader, Writable, Writer};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+impl<'a, C: Context, T: IdType> Readable<'a, C> for Ttid<T> {
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let mut bytes = [0u8; 16];
+        reader.read_bytes(&mut bytes)?;
+        Ttid::from_uuid(Uuid::from_bytes(bytes))
+            .map_err(|err| speedy::Error::custom(format!("invalid TTID: {err}")).into())
+    }
+
+    fn minimum_bytes_needed() -> usize {
+        16
+    }
+}
+
+impl<C: Context, T: IdType> Writable<C> for Ttid<T> {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        writer.write_bytes(self.uuid.as_bytes())
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Ok(16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use speedy::{BigEndian, LittleEndian};
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn roundtrip_as_uuid_bytes() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+
+        let little = id.write_to_vec_with_ctx(LittleEndian::default()).unwrap();
+        let big = id.write_to_vec_with_ctx(BigEndian::default()).unwrap();
+        assert_eq!(little, id.as_uuid().as_bytes());
+        assert_eq!(big, little);
+
+        let read = Ttid::<MyType>::read_from_buffer_with_ctx(LittleEndian::default(), &little);
+        assert_eq!(read.unwrap(), id);
+    }
+
+    #[test]
+    fn read_validates() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+        let bytes = id.as_uuid().into_bytes();
+
+        let ctx = LittleEndian::default;
+        assert!(Ttid::<NarrowType>::read_from_buffer_with_ctx(ctx(), &bytes).is_err());
+        assert!(Ttid::<MyType>::read_from_buffer_with_ctx(ctx(), Uuid::nil().as_bytes()).is_err());
+        assert!(Ttid::<MyType>::read_from_buffer_with_ctx(ctx(), &bytes[..8]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! `sqlx` support for every database with `uuid` support in sqlx.
+//!
+//! TTIDs are written like `uuid::Uuid`: as `UUID` on Postgres and as 16 bytes
+//! (`BINARY(16)`, `BLOB`) on MySQL and SQLite. Reading also accepts string
+//! columns holding either the TTID string form or a hyphenated UUID, and
+//! validates the TTID layout and the type id. Which form to expect is decided
+//! by the column type, so text-format results, e.g. from Postgres simple
+//! queries, decode like binary ones.
+
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type, ValueRef};
+use uuid::Uuid;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+impl<T: IdType, DB: Database> Type<DB> for Ttid<T>
+where
+    Uuid: Type<DB>,
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <Uuid as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <Uuid as Type<DB>>::compatible(ty) || <str as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, T: IdType, DB: Database> Encode<'q, DB> for Ttid<T>
+where
+    Uuid: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        <Uuid as Encode<'q, DB>>::encode_by_ref(&self.as_uuid(), buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        <Uuid as Encode<'q, DB>>::size_hint(&self.as_uuid())
+    }
+}
+
+impl<'r, T: IdType, DB: Database> Decode<'r, DB> for Ttid<T>
+where
+    Uuid: Decode<'r, DB>,
+    &'r str: Decode<'r, DB>,
+    str: Type<DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        if <str as Type<DB>>::compatible(&value.type_info()) {
+            let text = <&str as Decode<'r, DB>>::decode(value)?;
+            Ok(decode_text(text)?)
+        } else {
+            let uuid = <Uuid as Decode<'r, DB>>::decode(value)?;
+            Ok(Ttid::from_uuid(uuid)?)
+        }
+    }
+}
+
+/// Decode the TTID string form or a hyphenated UUID.
+fn decode_text<T: IdType>(text: &str) -> Result<Ttid<T>, ParseTtidError> {
+    if text.contains('_') {
+        text.parse()
+    } else {
+        let uuid = Uuid::try_parse(text).map_err(|_| ParseTtidError::InvalidFormat)?;
+        Ok(Ttid::from_uuid(uuid)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Connection, Row, SqliteConnection};
+
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[tokio::test]
+    async fn sqlite_blob_and_text_columns() {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE ids (id BLOB NOT NULL, label TEXT NOT NULL)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+        sqlx::query("INSERT INTO ids (id, label) VALUES (?, ?)")
+            .bind(id)
+            .bind(id.to_string())
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let row = sqlx::query("SELECT id, label FROM ids")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<Ttid<MyType>, _>("id"), id);
+        assert_eq!(row.get::<Ttid<MyType>, _>("label"), id);
+        assert!(row.try_get::<Ttid<NarrowType>, _>("id").is_err());
+    }
+
+    #[tokio::test]
+    async fn sqlite_column_type_decides_the_form() {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let row = sqlx::query("SELECT ? AS uuid, CAST(? AS BLOB) AS text_bytes")
+            .bind(id.as_uuid().to_string())
+            .bind(id.to_string())
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<Ttid<MyType>, _>("uuid"), id);
+        // Blobs are raw UUID bytes, never text.
+        assert!(row.try_get::<Ttid<MyType>, _>("text_bytes").is_err());
+    }
+
+    #[test]
+    fn decode_text_accepts_ttid_and_uuid_strings() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        assert_eq!(decode_text(&id.to_string()), Ok(id));
+        assert_eq!(decode_text(&id.as_uuid().to_string()), Ok(id));
+        // 16 characters are text, not raw UUID bytes.
+        assert_eq!(
+            decode_text::<MyType>("0123456789abcdef"),
+            Err(ParseTtidError::InvalidFormat)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! `tiberius` (SQL Server) support for `uniqueidentifier` columns.
+//!
+//! Values are exchanged as [`ColumnData::Guid`], so tiberius handles the
+//! mixed-endian wire format and round-trips are exact.
+//!
+//! SQL Server does not compare `uniqueidentifier` values byte by byte: like
+//! .NET's `SqlGuid`, it compares the mixed-endian GUID bytes
+//! ([`Uuid::to_bytes_le`]) `10..16` first, then `8..10`, `6..8`, `4..6` and
+//! finally `0..4`. Plain TTIDs therefore do not sort by time there. For a
+//! time-ordered clustered index, store [`Ttid::to_mssql_sortable_uuid`]
+//! instead and read back with [`Ttid::from_mssql_sortable_uuid`].
+
+use tiberius::error::Error;
+use tiberius::{ColumnData, FromSql, FromSqlOwned, IntoSql, ToSql};
+use uuid::Uuid;
+
+use crate::{IdType, Ttid, TtidError};
+
+/// Positions in the mixed-endian GUID bytes in SQL Server comparison order,
+/// most significant first.
+const MSSQL_SORT_ORDER: [usize; 16] = [10, 11, 12, 13, 14, 15, 8, 9, 6, 7, 4, 5, 0, 1, 2, 3];
+
+impl<T: IdType> Ttid<T> {
+    /// Rearrange the UUID bytes so SQL Server's `uniqueidentifier` ordering
+    /// matches time ordering.
+    ///
+    /// The result is not a valid TTID itself, convert back with
+    /// [`Self::from_mssql_sortable_uuid`].
+    pub fn to_mssql_sortable_uuid(&self) -> Uuid {
+        let bytes = self.as_uuid().into_bytes();
+        let mut sortable = [0u8; 16];
+        for (byte, pos) in bytes.iter().zip(MSSQL_SORT_ORDER) {
+            sortable[pos] = *byte;
+        }
+        Uuid::from_bytes_le(sortable)
+    }
+
+    /// Validate and restore a TTID from [`Self::to_mssql_sortable_uuid`].
+    pub fn from_mssql_sortable_uuid(uuid: Uuid) -> Result<Self, TtidError> {
+        let sortable = uuid.to_bytes_le();
+        let mut bytes = [0u8; 16];
+        for (byte, pos) in bytes.iter_mut().zip(MSSQL_SORT_ORDER) {
+            *byte = sortable[pos];
+        }
+        Self::from_uuid(Uuid::from_bytes(bytes))
+    }
+}
+
+impl<T: IdType + Send + Sync> ToSql for Ttid<T> {
+    fn to_sql(&self) -> ColumnData<'_> {
+        ColumnData::Guid(Some(self.as_uuid()))
+    }
+}
+
+impl<'a, T: IdType + Send + Sync> IntoSql<'a> for Ttid<T> {
+    fn into_sql(self) -> ColumnData<'a> {
+        ColumnData::Guid(Some(self.as_uuid()))
+    }
+}
+
+impl<'a, T: IdType + 'a> FromSql<'a> for Ttid<T> {
+    fn from_sql(value: &'a ColumnData<'static>) -> tiberius::Result<Option<Self>> {
+        decode(value)
+    }
+}
+
+impl<T: IdType + 'static> FromSqlOwned for Ttid<T> {
+    fn from_sql_owned(value: ColumnData<'static>) -> tiberius::Result<Option<Self>> {
+        decode(&value)
+    }
+}
+
+fn decode<T: IdType>(value: &ColumnData<'_>) -> tiberius::Result<Option<Ttid<T>>> {
+    match value {
+        ColumnData::Guid(uuid) => uuid
+            .map(|uuid| {
+                Ttid::from_uuid(uuid)
+                    .map_err(|err| Error::Conversion(format!("invalid TTID: {err}").into()))
+            })
+            .transpose(),
+        other => Err(Error::Conversion(
+            format!("cannot interpret {other:?} as a TTID value").into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    /// Sort key emulating SQL Server's `uniqueidentifier` comparison, as
+    /// implemented by `SqlGuid.CompareTo` on the mixed-endian GUID bytes.
+    fn mssql_key(uuid: Uuid) -> [u8; 16] {
+        let bytes = uuid.to_bytes_le();
+        [10, 11, 12, 13, 14, 15, 8, 9, 6, 7, 4, 5, 0, 1, 2, 3].map(|pos| bytes[pos])
+    }
+
+    #[test]
+    fn column_data_roundtrip() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let data = ttid.to_sql();
+        assert!(matches!(data, ColumnData::Guid(Some(uuid)) if uuid == ttid.as_uuid()));
+        assert_eq!(
+            Ttid::<MyType>::from_sql_owned(ttid.into_sql()).unwrap(),
+            Some(ttid)
+        );
+        assert_eq!(
+            Ttid::<MyType>::from_sql_owned(ColumnData::Guid(None)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn decode_rejects_invalid_values() {
+        let session = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 1).unwrap();
+
+        assert!(Ttid::<NarrowType>::from_sql_owned(session.into_sql()).is_err());
+        assert!(Ttid::<MyType>::from_sql_owned(ColumnData::I32(Some(1))).is_err());
+    }
+
+    #[test]
+    fn sortable_uuid_follows_time_order_in_sql_server() {
+        let ids = [
+            Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, u64::MAX).unwrap(),
+            Ttid::<MyType>::from_parts(1_700_000_000_001, MyType::User, 0).unwrap(),
+            Ttid::<MyType>::from_parts(1_700_000_000_001, MyType::User, 1).unwrap(),
+            Ttid::<MyType>::from_parts(1_800_000_000_000, MyType::User, 0).unwrap(),
+        ];
+
+        for pair in ids.windows(2) {
+            assert!(
+                mssql_key(pair[0].to_mssql_sortable_uuid())
+                    < mssql_key(pair[1].to_mssql_sortable_uuid())
+            );
+        }
+        for id in ids {
+            let restored = Ttid::from_mssql_sortable_uuid(id.to_mssql_sortable_uuid()).unwrap();
+            assert_eq!(restored, id);
+        }
+
+        // The least significant byte for SQL Server is the first one of the
+        // text form, which is byte 3 of the mixed-endian layout.
+        let low = Uuid::parse_str("01000000-0000-0000-0000-000000000000").unwrap();
+        let high = Uuid::parse_str("00000001-0000-0000-0000-000000000000").unwrap();
+        assert!(mssql_key(low) < mssql_key(high));
+
+        let mut generated: Vec<_> = (0..64)
+            .map(|n| {
+                let id = Ttid::new(MyType::User).unwrap();
+                id.with_timestamp_ms(id.timestamp_ms() + n % 5).unwrap()
+            })
+            .collect();
+        generated.sort_by_key(|id| id.as_uuid());
+        let mut by_sql_server = generated.clone();
+        by_sql_server.sort_by_key(|id| mssql_key(id.to_mssql_sortable_uuid()));
+        assert_eq!(by_sql_server, generated);
+    }
+}
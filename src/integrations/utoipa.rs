@@ -0,0 +1,93 @@
+use std::borrow::Cow;
+
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::{RefOr, Required};
+use utoipa::{IntoParams, PartialSchema, ToSchema};
+
+use super::string_pattern;
+use crate::{IdType, Ttid};
+
+/// Fixed components of the generated example id.
+const EXAMPLE_TIMESTAMP_MS: u64 = 1_700_000_000_000;
+const EXAMPLE_RANDOMNESS: u64 = 0x2a5f_0c3e_9b71_d4e8;
+
+impl<T: IdType> PartialSchema for Ttid<T> {
+    fn schema() -> RefOr<Schema> {
+        let mut schema = ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some("Typed id in `<type-name>_<shortuuid>` format."))
+            .pattern(Some(string_pattern::<T>()))
+            .max_length(Some(T::MAX_STRING_LEN));
+        if let Some(example) = example::<T>() {
+            schema = schema.examples([example]);
+        }
+        schema.into()
+    }
+}
+
+impl<T: IdType> ToSchema for Ttid<T> {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Ttid")
+    }
+}
+
+impl<T: IdType> IntoParams for Ttid<T> {
+    /// A single required parameter named `id`, in the path unless the
+    /// `#[utoipa::path]` attribute says otherwise.
+    fn into_params(parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![
+            ParameterBuilder::new()
+                .name("id")
+                .parameter_in(parameter_in_provider().unwrap_or_default())
+                .required(Required::True)
+                .schema(Some(Self::schema()))
+                .build(),
+        ]
+    }
+}
+
+/// Example id of the first type in [`IdType::TYPE_NAMES`], e.g.
+/// `user_5wbwf6yUxVBcr48AMbz9cb`.
+fn example<T: IdType>() -> Option<String> {
+    let ty = T::from_type_name(T::TYPE_NAMES.first()?)?;
+    let id = Ttid::from_parts(EXAMPLE_TIMESTAMP_MS, ty, EXAMPLE_RANDOMNESS).ok()?;
+    Some(id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn schema_describes_string_form_with_example() {
+        let RefOr::T(Schema::Object(schema)) = Ttid::<MyType>::schema() else {
+            panic!("expected inline object schema");
+        };
+
+        assert_eq!(
+            schema.pattern.as_deref(),
+            Some("^(?:user|org|session|max)_[1-9a-km-zA-HJ-NP-Z]{1,22}$")
+        );
+        assert_eq!(schema.max_length, Some(30));
+
+        let user = example::<MyType>().unwrap();
+        assert!(user.starts_with("user_"));
+        assert!(user.parse::<Ttid<MyType>>().is_ok());
+        assert_eq!(schema.examples, vec![serde_json::json!(user)]);
+
+        assert_eq!(example::<NarrowType>(), None);
+    }
+
+    #[test]
+    fn params_default_to_required_path_id() {
+        let params = Ttid::<MyType>::into_params(|| None);
+
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "id");
+        // utoipa types only implement `Debug` with its `debug` feature.
+        assert!(params[0].parameter_in == ParameterIn::Path);
+        assert!(params[0].required == Required::True);
+    }
+}
@@ -0,0 +1,82 @@
+use valuable::{Fields, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit};
+
+use crate::{IdType, Ttid};
+
+static FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("type"),
+    NamedField::new("timestamp_ms"),
+    NamedField::new("randomness"),
+];
+
+/// A struct of the type name, timestamp and randomness, which together
+/// identify the id, so subscribers record structured fields without
+/// rendering the string form.
+impl<T: IdType> Valuable for Ttid<T> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_named_fields(&NamedValues::new(
+            FIELDS,
+            &[
+                Value::String(self.id_type().as_type_name()),
+                Value::U64(self.timestamp_ms()),
+                Value::U64(self.randomness()),
+            ],
+        ));
+    }
+}
+
+impl<T: IdType> Structable for Ttid<T> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("Ttid", Fields::Named(FIELDS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[derive(Default)]
+    struct Collect(Vec<String>);
+
+    impl Visit for Collect {
+        fn visit_value(&mut self, value: Value<'_>) {
+            // `Value::visit` calls back into `visit_value`, visit the
+            // struct's fields instead.
+            if let Value::Structable(value) = value {
+                value.visit(self);
+            }
+        }
+
+        fn visit_named_fields(&mut self, named_values: &NamedValues<'_>) {
+            for (field, value) in named_values {
+                let value = match value {
+                    Value::String(value) => value.to_string(),
+                    Value::U64(value) => value.to_string(),
+                    other => panic!("unexpected value {other:?}"),
+                };
+                self.0.push(format!("{}={value}", field.name()));
+            }
+        }
+    }
+
+    #[test]
+    fn visits_id_parts() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+        assert!(matches!(id.definition().fields(), Fields::Named(fields) if fields.len() == 3));
+
+        let mut collect = Collect::default();
+        valuable::visit(&id, &mut collect);
+        assert_eq!(
+            collect.0,
+            [
+                "type=session",
+                "timestamp_ms=1700000000000",
+                "randomness=42",
+            ]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! The TTID bit layout on plain integers and byte arrays.
+//!
+//! These functions implement exactly the packing used by [`Ttid`](crate::Ttid)
+//! and [`RawTtid`](crate::RawTtid), without type domains or `uuid` types, for
+//! FFI layers and storage engines that handle ids as 16 bytes. The layout is
+//! described in `docs/spec.md` and is stable.
+//!
+//! ```
+//! use ttid::layout::{self, Parts};
+//!
+//! let bytes = layout::pack(1_700_000_000_000, 1, 42).unwrap();
+//! assert_eq!(
+//!     layout::unpack(&bytes).unwrap(),
+//!     Parts { timestamp_ms: 1_700_000_000_000, type_id: 1, randomness: 42 },
+//! );
+//! ```
+
+use uuid::Uuid;
+
+use crate::TtidError;
+use crate::deser;
+
+/// Number of timestamp bits.
+pub const TIMESTAMP_BITS: u32 = deser::TIMESTAMP_BITS;
+/// Number of type id bits.
+pub const TYPE_BITS: u32 = deser::TYPE_BITS;
+/// Number of randomness bits.
+pub const RANDOM_BITS: u32 = deser::RANDOM_BITS;
+/// Largest encodable timestamp in milliseconds.
+pub const TIMESTAMP_MAX: u64 = deser::TIMESTAMP_MAX;
+/// Mask of the randomness bits.
+pub const RANDOM_MASK: u64 = deser::RANDOM_MASK;
+
+/// Components of a TTID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Parts {
+    /// Unix timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// Numeric type id.
+    pub type_id: u16,
+    /// Random component, at most [`RANDOM_BITS`] bits.
+    pub randomness: u64,
+}
+
+/// Pack components into the 16 big-endian UUID bytes of a TTID.
+///
+/// `randomness` is masked to the low [`RANDOM_BITS`] bits. The type id is not
+/// checked, [`UNTYPED_TYPE_ID`](crate::UNTYPED_TYPE_ID) included.
+pub fn pack(timestamp_ms: u64, type_id: u16, randomness: u64) -> Result<[u8; 16], TtidError> {
+    if timestamp_ms > TIMESTAMP_MAX {
+        return Err(TtidError::TimestampOutOfRange);
+    }
+    let payload = deser::pack_payload(timestamp_ms, type_id, randomness);
+    Ok(deser::encode_payload_to_uuid(payload).into_bytes())
+}
+
+/// Unpack the components of TTID bytes produced by [`pack`].
+///
+/// Fails with [`TtidError::InvalidUuid`] if the UUIDv8 version or the RFC
+/// variant bits are not set.
+pub fn unpack(bytes: &[u8; 16]) -> Result<Parts, TtidError> {
+    let payload =
+        deser::decode_payload_from_uuid(Uuid::from_bytes(*bytes)).ok_or(TtidError::InvalidUuid)?;
+    Ok(Parts {
+        timestamp_ms: (payload >> (TYPE_BITS + RANDOM_BITS)) as u64,
+        type_id: (payload >> RANDOM_BITS) as u16,
+        randomness: payload as u64 & RANDOM_MASK,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ttid;
+    use crate::tests::MyType;
+
+    #[test]
+    fn matches_typed_encoding() {
+        let id = Ttid::<MyType>::from_parts(1_735_689_010_123, MyType::Session, u64::MAX).unwrap();
+        let bytes = pack(1_735_689_010_123, 777, u64::MAX).unwrap();
+
+        assert_eq!(&bytes, id.as_uuid().as_bytes());
+        assert_eq!(
+            unpack(&bytes).unwrap(),
+            Parts {
+                timestamp_ms: id.timestamp_ms(),
+                type_id: 777,
+                randomness: RANDOM_MASK,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_and_foreign_bytes() {
+        assert_eq!(
+            pack(TIMESTAMP_MAX + 1, 1, 0),
+            Err(TtidError::TimestampOutOfRange)
+        );
+        assert_eq!(unpack(&[0; 16]), Err(TtidError::InvalidUuid));
+        assert_eq!(
+            unpack(Uuid::new_v4().as_bytes()),
+            Err(TtidError::InvalidUuid)
+        );
+    }
+}
@@ -74,31 +74,221 @@
 //! assert_eq!(parsed, id);
 //! assert_eq!(parsed.id_type(), MyType::User);
 //! ```
+//!
+//! [`id_type!`] declares the same domain from a table of variants, type ids
+//! and type names.
+//!
+//! # Cargo features
+//!
+//! - `serde`: `Serialize`/`Deserialize` for [`Ttid`] and [`TtidPair`] in string
+//!   form, registries, [`registry::DynTtid`] and wrappers implemented with
+//!   [`impl_newtype!`].
+//! - `json` / `toml`: JSON and TOML import/export for [`registry::TypeRegistry`].
+//! - `well-known`: the `well_known` domain of standard infrastructure id types.
+//! - `actix-web`: the `actix_web` module with a `TtidPath` extractor,
+//!   rejecting invalid ids with `400 Bad Request` and the expected type name
+//!   prefix.
+//! - `arbitrary`: `Arbitrary` for [`Ttid`], generating valid ids for fuzz
+//!   targets.
+//! - `avro`: Apache Avro schemas and value conversions in the `avro`
+//!   module, with the UUID or the string form selectable per field.
+//! - `axum`: the `axum` module with a `TtidPath` extractor, rejecting invalid
+//!   ids with `400 Bad Request` and the expected type name prefix.
+//! - `bson`: BSON binary subtype 4 (UUID) conversions for [`Ttid`], for
+//!   MongoDB.
+//! - `bytemuck`: `NoUninit` and `CheckedBitPattern` for [`Ttid`], to cast
+//!   slices to and from `[[u8; 16]]` without copying.
+//! - `clickhouse`: `ttid::serde::as_clickhouse_uuid` for ClickHouse `UUID`
+//!   columns in `clickhouse::Row` types.
+//! - `diesel`: `ToSql`/`FromSql` for [`Ttid`] as Postgres `Uuid`.
+//!   `diesel-sqlite` adds SQLite `Binary` and `Text` mappings.
+//! - `digest`: `Ttid::update_hash`, feeding ids into `digest` hashers.
+//! - `garde`: `ttid::validate::garde::is_type`, a `garde` rule validating
+//!   string fields as ids of a domain.
+//! - `getrandom`: draw the randomness of new ids directly from `getrandom`
+//!   instead of generating a UUIDv4 per id.
+//! - `mysql`: `mysql_common` value conversions (`BINARY(16)`), for the `mysql`
+//!   and `mysql_async` drivers.
+//! - `no-panic`: verifies at link time that the infallible accessors, e.g.
+//!   [`Ttid::timestamp_ms`] and [`RawTtid::type_id`], contain no panic paths.
+//!   Only active in builds without debug assertions, e.g. `--release`; debug
+//!   builds with the feature compile as without it.
+//! - `opentelemetry`: `opentelemetry` `Value` and `KeyValue` conversions
+//!   for [`Ttid`], tagging ids under `<type-name>.id` keys.
+//! - `oracle`: `oracle` crate support for `RAW(16)` columns.
+//! - `postgres`: `ToSql`/`FromSql` from `postgres-types` for [`Ttid`] as
+//!   Postgres `uuid`, for `tokio-postgres` and `postgres`.
+//! - `proptest`: strategies generating valid ids in the `proptest` module.
+//! - `rand`: `Ttid::new_with_rng`, creating ids with randomness from a
+//!   caller-supplied `rand::Rng`.
+//! - `redis`: `ToRedisArgs`/`FromRedisValue` for [`Ttid`] as 16 bytes, and for
+//!   `RedisKey` as `<type-name>_<shortuuid>` for key names.
+//! - `rkyv`: zero-copy archiving of [`Ttid`], with a validated archived form.
+//! - `rocket`: `FromParam` and `FromFormField` for [`Ttid`], rejecting invalid
+//!   ids with `422 Unprocessable Entity`.
+//! - `rusqlite`: `ToSql`/`FromSql` for [`Ttid`] as 16-byte blobs, and for
+//!   `SqliteText` as `<type-name>_<shortuuid>` text.
+//! - `schemars`: `JsonSchema` for [`Ttid`], a string schema with a pattern
+//!   built from [`IdType::TYPE_NAMES`].
+//! - `sea-orm`: `ValueType`, `TryGetable` and `Value` conversions for [`Ttid`]
+//!   as `Uuid` columns, usable as entity model fields and primary keys.
+//! - `speedy`: `Readable`/`Writable` for [`Ttid`] as 16 UUID bytes.
+//! - `sqlx`: `Type`, `Encode` and `Decode` for [`Ttid`] on every sqlx
+//!   database with UUID support (Postgres `UUID`, MySQL `BINARY(16)`, SQLite
+//!   `BLOB`), also decoding string columns.
+//! - `testing`: the `testing` module with a mock clock and helpers creating
+//!   ids at fixed times, for downstream tests.
+//! - `tiberius`: SQL Server `uniqueidentifier` support and an order-preserving
+//!   GUID mapping.
+//! - `utoipa`: `ToSchema` and `IntoParams` for [`Ttid`], for OpenAPI documents.
+//! - `uuid-serde`: `ttid::serde::uuid_passthrough`, serializing [`Ttid`]
+//!   fields exactly like `uuid::Uuid` for wire-compatible migrations.
+//! - `validator`: `ttid::validate::is_type`, a `validator` custom function
+//!   validating string fields as ids of a domain.
+//! - `valuable`: `Valuable` for [`Ttid`] as a struct of type name, timestamp
+//!   and randomness, for structured `tracing` fields via
+//!   `tracing::field::valuable` without rendering the string form.
 
 use std::fmt;
 use std::marker::PhantomData;
+use std::num::NonZeroU64;
 use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use short_uuid::ShortUuid;
 use uuid::Uuid;
 
+#[cfg(feature = "actix-web")]
+pub mod actix_web;
+pub mod actor;
+pub mod audit;
+#[cfg(feature = "avro")]
+pub mod avro;
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod bench;
+pub mod bitemporal;
+mod canonical;
+pub mod codec;
+mod collections;
 mod deser;
+pub mod domain;
+mod envelope;
 mod error;
+mod etag;
+pub mod forensics;
+pub mod gc;
+pub mod generator;
+mod hash;
+mod id_type;
+mod integrations;
+pub mod layout;
+mod name;
+mod newtype;
+#[cfg(feature = "opentelemetry")]
+pub mod opentelemetry;
+pub mod outbox;
+mod pair;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+mod raw;
+pub mod registry;
+mod relay;
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod setops;
+pub mod slice;
+pub mod stats;
+pub mod storage;
+pub mod streams;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tombstone;
+mod url;
+#[cfg(any(feature = "garde", feature = "validator"))]
+pub mod validate;
+#[cfg(feature = "well-known")]
+pub mod well_known;
+pub mod workflow;
+pub use canonical::{canonicalize, same_entity};
+pub use collections::{TtidSet, TtidVec};
 use deser::{
     RANDOM_BITS, RANDOM_MASK, TIMESTAMP_MAX, TYPE_BITS, TYPE_ID_MAX, decode_payload_from_uuid,
-    encode_payload_to_uuid,
+    encode_payload_to_uuid, pack_payload, payload_of,
+};
+pub use envelope::{ENVELOPE_LEN, ENVELOPE_MAGIC};
+pub use error::{
+    ChainError, CodecError, EnvelopeError, ParseTtidError, RegistryConflict, RegistryError,
+    RegistryFormatError, TtidError, TypeNameError,
 };
-pub use error::{ParseTtidError, TtidError};
+pub use generator::generate;
+use hash::StableHasher;
+#[cfg(feature = "rkyv")]
+pub use integrations::ArchivedTtid;
+#[cfg(feature = "redis")]
+pub use integrations::RedisKey;
+#[cfg(feature = "rusqlite")]
+pub use integrations::SqliteText;
+pub use name::{SHORT_UUID_MAX_LEN, TTID_STRING_MAX_LEN, TYPE_NAME_MAX_LEN, validate_type_name};
+pub use newtype::TtidNewtype;
+pub use pair::TtidPair;
+pub use raw::RawTtid;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "diesel")]
+    pub use diesel;
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "sqlx")]
+    pub use sqlx;
+    pub use uuid::Uuid;
+
+    pub use crate::id_type::{max_len, valid_domain};
+}
+
+/// Type id reserved for untyped TTIDs.
+///
+/// Typed domains must not use it: [`Ttid`] rejects it on construction and
+/// decoding. Use [`RawTtid::untyped`] for placeholders and migrations.
+pub const UNTYPED_TYPE_ID: u16 = 0;
 
 /// Maps a Rust type enum to a compact numeric id and readable type name.
 ///
-/// The mapping must be stable for persisted data.
+/// The mapping must be stable for persisted data, and must not use
+/// [`UNTYPED_TYPE_ID`].
 ///
 /// - `to_type_id` / `from_type_id` map to the packed `16-bit` type field.
 /// - `as_type_name` / `from_type_name` map to the string prefix in
-///   `<type-name>_<shortuuid>`.
+///   `<type-name>_<shortuuid>`. Names must pass [`validate_type_name`].
 pub trait IdType: Sized + Copy {
+    /// Length in bytes of the longest name returned by `as_type_name`.
+    ///
+    /// Defaults to [`TYPE_NAME_MAX_LEN`]. Override with the exact value to get
+    /// a tighter [`MAX_STRING_LEN`](Self::MAX_STRING_LEN).
+    const MAX_TYPE_NAME_LEN: usize = TYPE_NAME_MAX_LEN;
+
+    /// Maximum length in bytes of a `<type-name>_<shortuuid>` string of this
+    /// domain, for sizing storage columns and fixed buffers.
+    ///
+    /// Derived from [`MAX_TYPE_NAME_LEN`](Self::MAX_TYPE_NAME_LEN), do not override.
+    const MAX_STRING_LEN: usize = Self::MAX_TYPE_NAME_LEN + 1 + SHORT_UUID_MAX_LEN;
+
+    /// Type names of all values of the domain, as returned by `as_type_name`.
+    ///
+    /// Optional, defaults to empty, meaning unknown. Used where the id format
+    /// is described up front, e.g. to restrict schema patterns to the known
+    /// type names.
+    const TYPE_NAMES: &'static [&'static str] = &[];
+
+    /// Type ids of all values of the domain, as returned by `to_type_id`.
+    ///
+    /// Optional, defaults to empty, meaning unknown. Used to check at compile
+    /// time that a domain fits the type id range it is composed into, see
+    /// [`domain`].
+    const TYPE_IDS: &'static [u16] = &[];
+
     /// Convert enum value to numeric type id.
     fn to_type_id(self) -> u16;
 
@@ -115,7 +305,24 @@ pub trait IdType: Sized + Copy {
 /// Typed TTID wrapper around `uuid::Uuid`.
 ///
 /// `T` is the type-domain enum implementing [`IdType`].
+///
+/// The layout is guaranteed to be that of `[u8; 16]` holding the big-endian
+/// UUID bytes, so slices of TTIDs can be reinterpreted as bytes for bulk IO,
+/// e.g. with the `bytemuck` feature.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(
+    feature = "diesel",
+    diesel(
+        sql_type = diesel::sql_types::Uuid,
+        sql_type = diesel::sql_types::Binary,
+        sql_type = diesel::sql_types::Text
+    )
+)]
+#[repr(transparent)]
 pub struct Ttid<T: IdType> {
     uuid: Uuid,
     marker: PhantomData<T>,
@@ -123,15 +330,30 @@ pub struct Ttid<T: IdType> {
 
 impl<T: IdType> Ttid<T> {
     /// Create a new TTID from current Unix timestamp in milliseconds,
-    /// `ty`, and 58 random bits derived from UUIDv4 randomness.
+    /// `ty`, and 58 random bits from the operating system's random source.
+    ///
+    /// Ids follow the system clock, also when it jumps backwards. Use a
+    /// [`TtidGenerator`](generator::TtidGenerator) to never go back in time.
+    ///
+    /// Fails with [`TtidError::TimestampOutOfRange`] if the system clock is
+    /// before the Unix epoch or beyond the 48-bit range.
     pub fn new(ty: T) -> Result<Self, TtidError> {
-        let now_ms = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("system time before unix epoch")
-            .as_millis() as u64;
-        let random_bits = Uuid::new_v4().as_u128() as u64 & RANDOM_MASK;
+        Self::from_parts(now_ms()?, ty, random_bits())
+    }
 
-        Self::from_parts(now_ms, ty, random_bits)
+    /// Create a TTID created at `time`, with fresh randomness, e.g. to
+    /// backfill historical records whose creation time is known.
+    ///
+    /// Sub-millisecond precision is truncated. Fails with
+    /// [`TtidError::TimestampOutOfRange`] if `time` is before the Unix epoch
+    /// or beyond the 48-bit range.
+    pub fn new_at(ty: T, time: SystemTime) -> Result<Self, TtidError> {
+        Self::new_at_ms(ty, unix_ms(time)?)
+    }
+
+    /// [`Self::new_at`] with a millisecond Unix timestamp.
+    pub fn new_at_ms(ty: T, timestamp_ms: u64) -> Result<Self, TtidError> {
+        Self::from_parts(timestamp_ms, ty, random_bits())
     }
 
     /// Construct from explicit components.
@@ -143,12 +365,11 @@ impl<T: IdType> Ttid<T> {
         if timestamp_ms > TIMESTAMP_MAX {
             return Err(TtidError::TimestampOutOfRange);
         }
+        if type_id == UNTYPED_TYPE_ID {
+            return Err(TtidError::ReservedTypeId);
+        }
 
-        let payload = ((timestamp_ms as u128) << (TYPE_BITS + RANDOM_BITS))
-            | ((type_id as u128) << RANDOM_BITS)
-            | ((randomness & RANDOM_MASK) as u128);
-
-        let uuid = encode_payload_to_uuid(payload);
+        let uuid = encode_payload_to_uuid(pack_payload(timestamp_ms, type_id, randomness));
         Ok(Self {
             uuid,
             marker: PhantomData,
@@ -160,6 +381,9 @@ impl<T: IdType> Ttid<T> {
         let payload = decode_payload_from_uuid(uuid).ok_or(TtidError::InvalidUuid)?;
         let type_id = ((payload >> RANDOM_BITS) & (TYPE_ID_MAX as u128)) as u16;
 
+        if type_id == UNTYPED_TYPE_ID {
+            return Err(TtidError::ReservedTypeId);
+        }
         if T::from_type_id(type_id).is_none() {
             return Err(TtidError::UnknownTypeId(type_id));
         }
@@ -171,37 +395,399 @@ impl<T: IdType> Ttid<T> {
     }
 
     /// Borrow the raw UUID value.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn as_uuid(&self) -> Uuid {
         self.uuid
     }
 
     /// Extract millisecond Unix timestamp.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn timestamp_ms(&self) -> u64 {
-        let payload = decode_payload_from_uuid(self.uuid).expect("internal TTID is always valid");
-        (payload >> (TYPE_BITS + RANDOM_BITS)) as u64
+        (payload_of(self.uuid) >> (TYPE_BITS + RANDOM_BITS)) as u64
     }
 
     /// Extract numeric type id.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn type_id(&self) -> u16 {
-        let payload = decode_payload_from_uuid(self.uuid).expect("internal TTID is always valid");
-        ((payload >> RANDOM_BITS) & (TYPE_ID_MAX as u128)) as u16
+        ((payload_of(self.uuid) >> RANDOM_BITS) & (TYPE_ID_MAX as u128)) as u16
     }
 
     /// Extract typed enum variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T::from_type_id` no longer accepts the type id validated on
+    /// construction, which violates the [`IdType`] contract. See
+    /// [`Self::try_id_type`] for a non-panicking variant.
     pub fn id_type(&self) -> T {
         T::from_type_id(self.type_id()).expect("type id validated at construction")
     }
 
+    /// Extract typed enum variant, failing with [`TtidError::UnknownTypeId`]
+    /// instead of panicking if the domain is inconsistent.
+    pub fn try_id_type(&self) -> Result<T, TtidError> {
+        let type_id = self.type_id();
+        T::from_type_id(type_id).ok_or(TtidError::UnknownTypeId(type_id))
+    }
+
     /// Extract random 58-bit component.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn randomness(&self) -> u64 {
-        let payload = decode_payload_from_uuid(self.uuid).expect("internal TTID is always valid");
-        (payload as u64) & RANDOM_MASK
+        (payload_of(self.uuid) as u64) & RANDOM_MASK
+    }
+
+    /// Return the UUID as big-endian unsigned 128-bit integer.
+    ///
+    /// Integer order is guaranteed to match time ordering: timestamp first,
+    /// then type id, then randomness. Useful for engines indexing on `u128`
+    /// rather than UUIDs.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn to_ordered_u128(&self) -> u128 {
+        self.uuid.as_u128()
+    }
+
+    /// Validate and wrap an integer produced by [`Self::to_ordered_u128`].
+    pub fn from_ordered_u128(value: u128) -> Result<Self, TtidError> {
+        Self::from_uuid(Uuid::from_u128(value))
+    }
+
+    /// Return the UUID as signed 128-bit integer, for databases without a UUID
+    /// or unsigned 128-bit type.
+    ///
+    /// The sign bit is flipped so signed integer order matches time ordering,
+    /// like [`Self::to_ordered_u128`].
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn to_ordered_i128(&self) -> i128 {
+        (self.uuid.as_u128() ^ (1 << 127)) as i128
+    }
+
+    /// Validate and wrap an integer produced by [`Self::to_ordered_i128`].
+    pub fn from_ordered_i128(value: i128) -> Result<Self, TtidError> {
+        Self::from_ordered_u128(value as u128 ^ (1 << 127))
+    }
+
+    /// Split the UUID into `(high, low)` signed 64-bit integers, for schemas
+    /// storing ids in two `BIGINT` columns.
+    ///
+    /// The sign bit of each half is flipped so ordering by `(high, low)`
+    /// matches time ordering.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn to_i64_pair(&self) -> (i64, i64) {
+        let value = self.uuid.as_u128();
+        let high = ((value >> 64) as u64 ^ (1 << 63)) as i64;
+        let low = (value as u64 ^ (1 << 63)) as i64;
+        (high, low)
+    }
+
+    /// Validate and wrap a pair produced by [`Self::to_i64_pair`].
+    pub fn from_i64_pair(high: i64, low: i64) -> Result<Self, TtidError> {
+        let high = (high as u64 ^ (1 << 63)) as u128;
+        let low = (low as u64 ^ (1 << 63)) as u128;
+        Self::from_ordered_u128((high << 64) | low)
+    }
+
+    /// Return the 16 UUID bytes in RFC 9562 (big-endian) order, the order
+    /// that sorts like the id.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn to_bytes_be(&self) -> [u8; 16] {
+        *self.uuid.as_bytes()
+    }
+
+    /// Validate and wrap bytes produced by [`Self::to_bytes_be`].
+    pub fn from_bytes_be(bytes: [u8; 16]) -> Result<Self, TtidError> {
+        Self::from_uuid(Uuid::from_bytes(bytes))
+    }
+
+    /// Return the 16 UUID bytes in mixed-endian GUID order: the first three
+    /// fields (4, 2 and 2 bytes) little-endian, the rest unchanged.
+    ///
+    /// This is the layout of SQL Server `uniqueidentifier` and the Windows
+    /// `GUID` struct. It does not sort like the id.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn to_bytes_le(&self) -> [u8; 16] {
+        self.uuid.to_bytes_le()
+    }
+
+    /// Validate and wrap bytes produced by [`Self::to_bytes_le`].
+    pub fn from_bytes_le(bytes: [u8; 16]) -> Result<Self, TtidError> {
+        Self::from_uuid(Uuid::from_bytes_le(bytes))
+    }
+
+    /// Return a copy with the timestamp replaced.
+    pub fn with_timestamp_ms(&self, timestamp_ms: u64) -> Result<Self, TtidError> {
+        if timestamp_ms > TIMESTAMP_MAX {
+            return Err(TtidError::TimestampOutOfRange);
+        }
+        Ok(self.repacked(timestamp_ms, self.randomness()))
+    }
+
+    /// Return a copy with the type replaced.
+    pub fn with_type(&self, ty: T) -> Result<Self, TtidError> {
+        Self::from_parts(self.timestamp_ms(), ty, self.randomness())
+    }
+
+    /// Return a copy with the randomness replaced.
+    ///
+    /// `randomness` values larger than 58 bits are masked to the low 58 bits.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn with_randomness(&self, randomness: u64) -> Self {
+        self.repacked(self.timestamp_ms(), randomness)
+    }
+
+    /// Re-encode with the type id kept. `timestamp_ms` must be in range.
+    fn repacked(&self, timestamp_ms: u64, randomness: u64) -> Self {
+        let payload = pack_payload(timestamp_ms, self.type_id(), randomness);
+        Self {
+            uuid: encode_payload_to_uuid(payload),
+            marker: PhantomData,
+        }
+    }
+
+    /// Derive a deterministic related id, e.g. for thumbnails or exports of a
+    /// source entity.
+    ///
+    /// The child keeps timestamp and type. Its randomness is a stable hash of
+    /// this id's randomness and `label`, so the same parent and label always
+    /// yield the same child, across processes and crate versions.
+    pub fn derive_child(&self, label: &str) -> Self {
+        let randomness = StableHasher::new()
+            .write(&self.randomness().to_le_bytes())
+            .write_str(label)
+            .finish();
+        self.with_randomness(randomness)
+    }
+
+    /// Create the id of type `ty` for `name` in `namespace`, the same for
+    /// every call, like a UUIDv5.
+    ///
+    /// For records keyed externally, e.g. imported repeatedly from another
+    /// system. Timestamp and randomness are stable hashes of `namespace` and
+    /// `name`, so the timestamp carries no creation time. Use
+    /// [`Self::new_deterministic_at`] to keep a known creation time. The
+    /// hash is not cryptographic: do not derive ids from attacker-chosen
+    /// names where collisions matter.
+    pub fn new_deterministic(ty: T, namespace: Uuid, name: &[u8]) -> Result<Self, TtidError> {
+        let mut hasher = deterministic_hasher(namespace, name);
+        let randomness = hasher.finish();
+        let timestamp_ms = hasher.write(b"timestamp").finish() >> (64 - 48);
+        Self::from_parts(timestamp_ms, ty, randomness)
+    }
+
+    /// Create the id of type `ty` for `name` in `namespace` at
+    /// `timestamp_ms`, e.g. the creation time of the external record.
+    ///
+    /// Like [`Self::new_deterministic`], but only the randomness is hashed.
+    pub fn new_deterministic_at(
+        timestamp_ms: u64,
+        ty: T,
+        namespace: Uuid,
+        name: &[u8],
+    ) -> Result<Self, TtidError> {
+        Self::from_parts(
+            timestamp_ms,
+            ty,
+            deterministic_hasher(namespace, name).finish(),
+        )
+    }
+
+    /// Create a revision id of type `ty` for `parent`, e.g. a document
+    /// version.
+    ///
+    /// The [`LINEAGE_BITS`] randomness bits below
+    /// [`TOMBSTONE_FLAG`](tombstone::TOMBSTONE_FLAG) hold a stable hash of
+    /// `parent`, checked by [`Self::belongs_to`], the flag is clear and the
+    /// remaining bits are random. The timestamp is the current time, but
+    /// never older than `parent`'s.
+    pub fn new_revision_of<P: IdType>(ty: T, parent: &Ttid<P>) -> Result<Self, TtidError> {
+        Self::revision_at(now_ms()?, ty, parent, random_bits())
+    }
+
+    fn revision_at<P: IdType>(
+        timestamp_ms: u64,
+        ty: T,
+        parent: &Ttid<P>,
+        randomness: u64,
+    ) -> Result<Self, TtidError> {
+        let randomness = (lineage_hash(parent.uuid) << LINEAGE_SHIFT)
+            | (randomness & (RANDOM_MASK >> (LINEAGE_BITS + 1)));
+        Self::from_parts(timestamp_ms.max(parent.timestamp_ms()), ty, randomness)
+    }
+
+    /// Whether this id may be a revision of `parent` created with
+    /// [`Self::new_revision_of`].
+    ///
+    /// A cheap sanity check, not a proof: an unrelated id passes with
+    /// probability 1 in 65536 if it is not older than `parent`. Tombstones of
+    /// revisions still belong to the parent.
+    pub fn belongs_to<P: IdType>(&self, parent: &Ttid<P>) -> bool {
+        self.lineage() == lineage_hash(parent.uuid) && self.timestamp_ms() >= parent.timestamp_ms()
+    }
+
+    /// The [`LINEAGE_BITS`] bits of the randomness holding a parent hash.
+    fn lineage(&self) -> u64 {
+        (self.randomness() >> LINEAGE_SHIFT) & ((1 << LINEAGE_BITS) - 1)
+    }
+
+    /// Derive a stable span id for tracing backends, to correlate spans with
+    /// the request or entity this id names without storing both.
+    ///
+    /// The span id is a 64-bit stable hash of the id, the same across
+    /// processes and crate versions, and never zero, which tracing and W3C
+    /// trace context reserve. Span ids only need to be unique within a trace:
+    /// collisions between different ids become likely only around 2<sup>32</sup>
+    /// ids in one search window of a backend.
+    pub fn span_id(&self) -> NonZeroU64 {
+        let hash = StableHasher::new()
+            .write_str("span")
+            .write(self.uuid.as_bytes())
+            .finish();
+        NonZeroU64::new(hash).unwrap_or(NonZeroU64::MIN)
     }
 
     /// Return shortuuid encoding of the underlying UUID.
     pub fn short_uuid(&self) -> ShortUuid {
         ShortUuid::from_uuid(&self.uuid)
     }
+
+    /// Render as a key safe for path-like key namespaces, e.g. Firestore
+    /// document ids or object store paths.
+    ///
+    /// The key is the `<type-name>_<shortuuid>` string form. It never contains
+    /// `/`, never starts with `.` and never has the form `__*__`: type names
+    /// are restricted by [`validate_type_name`] and the shortuuid uses the
+    /// alphanumeric base58 alphabet.
+    ///
+    /// Fails if the domain returns a type name failing
+    /// [`validate_type_name`], which violates the [`IdType`] contract.
+    pub fn to_key_string(&self) -> Result<String, TypeNameError> {
+        validate_type_name(self.id_type().as_type_name())?;
+        Ok(self.to_string())
+    }
+
+    /// Parse a key produced by [`Self::to_key_string`].
+    pub fn from_key_string(key: &str) -> Result<Self, ParseTtidError> {
+        key.parse()
+    }
+
+    /// Render a cache key `<namespace>:v<version>:<type-name>_<shortuuid>`.
+    ///
+    /// Bumping `version` invalidates all keys of a namespace at once. The
+    /// namespace may itself contain `:`.
+    pub fn cache_key(&self, namespace: &str, version: u32) -> String {
+        format!("{namespace}:v{version}:{self}")
+    }
+
+    /// Parse a key produced by [`Self::cache_key`] into its namespace,
+    /// version and id.
+    pub fn from_cache_key(key: &str) -> Result<(&str, u32, Self), ParseTtidError> {
+        let mut parts = key.rsplitn(3, ':');
+        let (Some(id), Some(version), Some(namespace)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ParseTtidError::InvalidFormat);
+        };
+        let version = version
+            .strip_prefix('v')
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|digits| digits.parse().ok())
+            .ok_or(ParseTtidError::InvalidFormat)?;
+        Ok((namespace, version, id.parse()?))
+    }
+
+    /// Parse a TTID at the start of `s`, returning it and the remainder.
+    ///
+    /// The shortuuid extends over the longest run of base58 characters, at
+    /// most [`SHORT_UUID_MAX_LEN`] bytes, e.g. `user_<shortuuid>/settings`
+    /// yields the id and `/settings`. An empty remainder means `s` is exactly
+    /// one id; whether anything else is trailing garbage is up to the caller.
+    pub fn parse_prefix(s: &str) -> Result<(Self, &str), ParseTtidError> {
+        let (type_name, rest) = s.split_once('_').ok_or(ParseTtidError::InvalidFormat)?;
+        let short_len = rest
+            .bytes()
+            .take(SHORT_UUID_MAX_LEN)
+            .take_while(|byte| url::SHORT_UUID_ALPHABET.contains(byte))
+            .count();
+        let (id, remainder) = s.split_at(type_name.len() + 1 + short_len);
+        Ok((id.parse()?, remainder))
+    }
+}
+
+/// Number of randomness bits of a revision id holding its parent's hash, see
+/// [`Ttid::new_revision_of`].
+pub const LINEAGE_BITS: u32 = 16;
+
+/// Position of the lowest lineage bit, right below the tombstone flag.
+const LINEAGE_SHIFT: u32 = RANDOM_BITS - 1 - LINEAGE_BITS;
+
+/// Stable [`LINEAGE_BITS`]-bit hash of a parent id.
+fn lineage_hash(parent: Uuid) -> u64 {
+    StableHasher::new().write(parent.as_bytes()).finish() >> (64 - LINEAGE_BITS)
+}
+
+/// Current Unix timestamp in milliseconds.
+///
+/// A system clock before the Unix epoch is reported as
+/// [`TtidError::TimestampOutOfRange`].
+pub(crate) fn now_ms() -> Result<u64, TtidError> {
+    unix_ms(SystemTime::now())
+}
+
+/// Milliseconds since the Unix epoch of `time`.
+fn unix_ms(time: SystemTime) -> Result<u64, TtidError> {
+    let elapsed = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| TtidError::TimestampOutOfRange)?;
+    u64::try_from(elapsed.as_millis()).map_err(|_| TtidError::TimestampOutOfRange)
+}
+
+/// Hasher over `namespace` and the length-prefixed `name`.
+fn deterministic_hasher(namespace: Uuid, name: &[u8]) -> StableHasher {
+    let mut hasher = StableHasher::new();
+    hasher
+        .write(namespace.as_bytes())
+        .write(&(name.len() as u64).to_le_bytes())
+        .write(name);
+    hasher
+}
+
+/// 58 random bits read directly from the operating system.
+///
+/// # Panics
+///
+/// Panics if the operating system's random source fails, like
+/// `Uuid::new_v4`.
+#[cfg(feature = "getrandom")]
+pub(crate) fn random_bits() -> u64 {
+    let mut bytes = [0; 8];
+    getrandom::fill(&mut bytes).expect("operating system random source failed");
+    u64::from_le_bytes(bytes) & RANDOM_MASK
+}
+
+/// 58 random bits derived from UUIDv4 randomness.
+#[cfg(not(feature = "getrandom"))]
+pub(crate) fn random_bits() -> u64 {
+    Uuid::new_v4().as_u128() as u64 & RANDOM_MASK
+}
+
+/// Fill `out` with 58 random bits each, with a single read from the
+/// operating system.
+///
+/// # Panics
+///
+/// Panics if the operating system's random source fails.
+#[cfg(feature = "getrandom")]
+pub(crate) fn fill_random_bits(out: &mut [u64]) {
+    let mut bytes = vec![0; out.len() * 8];
+    getrandom::fill(&mut bytes).expect("operating system random source failed");
+    for (bits, chunk) in out.iter_mut().zip(bytes.chunks_exact(8)) {
+        let chunk: [u8; 8] = chunk.try_into().expect("chunks have 8 bytes");
+        *bits = u64::from_le_bytes(chunk) & RANDOM_MASK;
+    }
+}
+
+/// Fill `out` with 58 random bits each.
+#[cfg(not(feature = "getrandom"))]
+pub(crate) fn fill_random_bits(out: &mut [u64]) {
+    out.fill_with(random_bits);
 }
 
 impl<T: IdType> fmt::Display for Ttid<T> {
@@ -240,6 +826,12 @@ impl<T: IdType> TryFrom<Uuid> for Ttid<T> {
     }
 }
 
+impl<T: IdType> AsRef<Uuid> for Ttid<T> {
+    fn as_ref(&self) -> &Uuid {
+        &self.uuid
+    }
+}
+
 impl<T: IdType> From<Ttid<T>> for Uuid {
     fn from(value: Ttid<T>) -> Self {
         value.uuid
@@ -247,11 +839,13 @@ impl<T: IdType> From<Ttid<T>> for Uuid {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use std::time::Duration;
+
     use super::*;
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    enum MyType {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub(crate) enum MyType {
         User,
         Org,
         Session,
@@ -259,6 +853,9 @@ mod tests {
     }
 
     impl IdType for MyType {
+        const MAX_TYPE_NAME_LEN: usize = 7;
+        const TYPE_NAMES: &'static [&'static str] = &["user", "org", "session", "max"];
+
         fn to_type_id(self) -> u16 {
             match self {
                 Self::User => 1,
@@ -299,7 +896,7 @@ mod tests {
     }
 
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    enum NarrowType {
+    pub(crate) enum NarrowType {
         User,
     }
 
@@ -338,6 +935,7 @@ mod tests {
         assert_eq!(ttid.timestamp_ms(), ts);
         assert_eq!(ttid.type_id(), 777);
         assert_eq!(ttid.id_type(), MyType::Session);
+        assert_eq!(ttid.try_id_type(), Ok(MyType::Session));
         assert_eq!(ttid.randomness(), rand);
 
         let uuid = ttid.as_uuid();
@@ -372,6 +970,39 @@ mod tests {
         assert!(ttid.timestamp_ms() <= after);
     }
 
+    #[test]
+    fn random_bits_fill_the_randomness_field() {
+        let samples: Vec<u64> = (0..64).map(|_| random_bits()).collect();
+        assert!(samples.iter().all(|bits| bits & !RANDOM_MASK == 0));
+        // Each of the 58 bits is set in some sample, with overwhelming odds.
+        assert_eq!(samples.iter().fold(0, |acc, bits| acc | bits), RANDOM_MASK);
+    }
+
+    /// Instantiates the `#[no_panic]` accessors, which fail to link if the
+    /// optimizer cannot remove every panic path. The checks are only active
+    /// in release builds: `cargo test --release --features no-panic`.
+    #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+    #[test]
+    fn accessors_are_panic_free() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 42).unwrap();
+        let raw = RawTtid::from_uuid(ttid.as_uuid()).unwrap();
+
+        assert_eq!(ttid.timestamp_ms(), raw.timestamp_ms());
+        assert_eq!(ttid.type_id(), raw.type_id());
+        assert_eq!(ttid.randomness(), raw.randomness());
+        assert!(!raw.is_untyped());
+        assert_eq!(ttid.to_ordered_u128(), raw.as_uuid().as_u128());
+        assert_eq!(
+            ttid.to_ordered_i128() as u128,
+            ttid.to_ordered_u128() ^ (1 << 127)
+        );
+        assert_eq!(
+            ttid.to_i64_pair().0,
+            (ttid.to_ordered_u128() >> 64) as i64 ^ i64::MIN
+        );
+        assert_eq!(ttid.with_randomness(7).randomness(), 7);
+    }
+
     #[test]
     fn display_and_parse_roundtrip() {
         let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
@@ -383,6 +1014,46 @@ mod tests {
         assert_eq!(parsed, ttid);
     }
 
+    #[test]
+    fn key_string_is_path_safe_and_roundtrips() {
+        for ty in [MyType::User, MyType::Org, MyType::Session, MyType::Max] {
+            let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, ty, u64::MAX).unwrap();
+            let key = ttid.to_key_string().unwrap();
+
+            assert_eq!(key, ttid.to_string());
+            assert!(!key.contains('/'));
+            assert!(!key.starts_with('.'));
+            assert!(!key.starts_with("__"));
+            assert_eq!(Ttid::<MyType>::from_key_string(&key).unwrap(), ttid);
+        }
+        assert!(matches!(
+            Ttid::<MyType>::from_key_string("user/x"),
+            Err(ParseTtidError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn parse_prefix_returns_remainder() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+
+        for suffix in ["", "/settings", ".json", "_0", "-x"] {
+            let input = format!("{ttid}{suffix}");
+            let (parsed, remainder) = Ttid::<MyType>::parse_prefix(&input).unwrap();
+            assert_eq!(parsed, ttid);
+            assert_eq!(remainder, suffix);
+        }
+
+        assert!(matches!(
+            Ttid::<MyType>::parse_prefix("settings/user"),
+            Err(ParseTtidError::InvalidFormat)
+        ));
+        assert!(matches!(
+            Ttid::<MyType>::parse_prefix("team_abc/settings"),
+            Err(ParseTtidError::UnknownTypeName)
+        ));
+        assert!(Ttid::<MyType>::parse_prefix("user_/settings").is_err());
+    }
+
     #[test]
     fn parse_rejects_missing_separator() {
         let err = "user".parse::<Ttid<MyType>>().unwrap_err();
@@ -428,6 +1099,253 @@ mod tests {
         assert!(matches!(err, TtidError::UnknownTypeId(777)));
     }
 
+    #[test]
+    fn rejects_reserved_untyped_type_id() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        struct Zero;
+
+        impl IdType for Zero {
+            fn to_type_id(self) -> u16 {
+                UNTYPED_TYPE_ID
+            }
+
+            fn from_type_id(id: u16) -> Option<Self> {
+                (id == UNTYPED_TYPE_ID).then_some(Zero)
+            }
+
+            fn as_type_name(self) -> &'static str {
+                "zero"
+            }
+
+            fn from_type_name(name: &str) -> Option<Self> {
+                (name == "zero").then_some(Zero)
+            }
+        }
+
+        let err = Ttid::<Zero>::from_parts(1_700_000_000_000, Zero, 1).unwrap_err();
+        assert!(matches!(err, TtidError::ReservedTypeId));
+
+        let untyped = RawTtid::untyped(1_700_000_000_000, 1).unwrap();
+        let err = Ttid::<Zero>::from_uuid(untyped.as_uuid()).unwrap_err();
+        assert!(matches!(err, TtidError::ReservedTypeId));
+    }
+
+    #[test]
+    fn copy_modifiers_replace_single_parts() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let moved = ttid.with_timestamp_ms(1_800_000_000_000).unwrap();
+        assert_eq!(moved.timestamp_ms(), 1_800_000_000_000);
+        assert_eq!(moved.id_type(), MyType::User);
+        assert_eq!(moved.randomness(), 42);
+
+        let retyped = ttid.with_type(MyType::Org).unwrap();
+        assert_eq!(retyped.timestamp_ms(), 1_700_000_000_000);
+        assert_eq!(retyped.id_type(), MyType::Org);
+        assert_eq!(retyped.randomness(), 42);
+
+        let rerolled = ttid.with_randomness(u64::MAX);
+        assert_eq!(rerolled.timestamp_ms(), 1_700_000_000_000);
+        assert_eq!(rerolled.randomness(), RANDOM_MASK);
+
+        let err = ttid.with_timestamp_ms(TIMESTAMP_MAX + 1).unwrap_err();
+        assert!(matches!(err, TtidError::TimestampOutOfRange));
+    }
+
+    #[test]
+    fn deterministic_ids_are_stable_per_name() {
+        let namespace = Uuid::from_u128(0x6ba7_b810_9dad_11d1_80b4_00c0_4fd4_30c8);
+        let id = Ttid::<MyType>::new_deterministic(MyType::User, namespace, b"crm:4711").unwrap();
+        assert_eq!(
+            id,
+            Ttid::new_deterministic(MyType::User, namespace, b"crm:4711").unwrap()
+        );
+        assert_ne!(
+            id,
+            Ttid::new_deterministic(MyType::User, namespace, b"crm:4712").unwrap()
+        );
+        assert_ne!(
+            id,
+            Ttid::new_deterministic(MyType::User, Uuid::nil(), b"crm:4711").unwrap()
+        );
+
+        let at = Ttid::<MyType>::new_deterministic_at(
+            1_700_000_000_000,
+            MyType::Org,
+            namespace,
+            b"crm:4711",
+        )
+        .unwrap();
+        assert_eq!(at.timestamp_ms(), 1_700_000_000_000);
+        assert_eq!(at.randomness(), id.randomness());
+        assert!(matches!(
+            Ttid::<MyType>::new_deterministic_at(TIMESTAMP_MAX + 1, MyType::Org, namespace, b""),
+            Err(TtidError::TimestampOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn derive_child_is_deterministic_per_label() {
+        let parent = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let thumb = parent.derive_child("thumbnail");
+        assert_eq!(thumb, parent.derive_child("thumbnail"));
+        assert_eq!(thumb.timestamp_ms(), parent.timestamp_ms());
+        assert_eq!(thumb.id_type(), parent.id_type());
+        assert_eq!(thumb.randomness(), 0x0300_7b25_0b0f_15cd);
+
+        assert_ne!(thumb, parent);
+        assert_ne!(thumb, parent.derive_child("export"));
+        assert_ne!(thumb, parent.with_randomness(43).derive_child("thumbnail"));
+    }
+
+    #[test]
+    fn cache_keys_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        let key = id.cache_key("profiles", 3);
+        assert_eq!(key, format!("profiles:v3:{id}"));
+        assert_eq!(Ttid::from_cache_key(&key), Ok(("profiles", 3, id)));
+
+        let nested = id.cache_key("app:profiles", u32::MAX);
+        assert_eq!(
+            Ttid::from_cache_key(&nested),
+            Ok(("app:profiles", u32::MAX, id))
+        );
+
+        for key in [
+            id.to_string(),
+            format!("v3:{id}"),
+            format!("profiles:3:{id}"),
+            format!("profiles:v+3:{id}"),
+            format!("profiles:v4294967296:{id}"),
+            "profiles:v3:user_".to_owned(),
+        ] {
+            assert!(Ttid::<MyType>::from_cache_key(&key).is_err(), "{key}");
+        }
+    }
+
+    #[test]
+    fn span_id_is_stable_per_id() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+
+        assert_eq!(id.span_id(), id.span_id());
+        assert_eq!(id.span_id().get(), 0x22f8_79e2_174a_4785);
+        assert_ne!(id.span_id(), id.with_randomness(43).span_id());
+        assert_ne!(id.span_id(), id.with_type(MyType::Org).unwrap().span_id());
+    }
+
+    #[test]
+    fn revisions_belong_to_their_parent() {
+        let parent = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 42).unwrap();
+        let other = parent.with_randomness(43);
+
+        let revision = Ttid::<MyType>::new_revision_of(MyType::Session, &parent).unwrap();
+        assert_eq!(revision.id_type(), MyType::Session);
+        assert!(revision.belongs_to(&parent));
+        assert!(!revision.belongs_to(&other));
+        assert!(!parent.belongs_to(&revision));
+
+        let a = Ttid::<MyType>::revision_at(1_700_000_000_001, MyType::User, &parent, 0).unwrap();
+        let b = Ttid::<MyType>::revision_at(1_700_000_000_002, MyType::User, &parent, RANDOM_MASK)
+            .unwrap();
+        assert_eq!(a.lineage(), b.lineage());
+        assert_eq!(a.randomness() >> LINEAGE_SHIFT, a.lineage());
+        assert!(a.belongs_to(&parent) && b.belongs_to(&parent));
+        assert!(!b.is_tombstone());
+        assert!(b.tombstone().unwrap().belongs_to(&parent));
+
+        // Clock behind the parent: the revision takes the parent's timestamp.
+        let early = Ttid::<MyType>::revision_at(1_000, MyType::User, &parent, 0).unwrap();
+        assert_eq!(early.timestamp_ms(), parent.timestamp_ms());
+        assert!(early.belongs_to(&parent));
+        assert!(!early.with_timestamp_ms(1_000).unwrap().belongs_to(&parent));
+    }
+
+    #[test]
+    fn ordered_u128_roundtrips_and_follows_time_order() {
+        let ts = 1_700_000_000_000;
+        let ids = [
+            Ttid::<MyType>::from_parts(ts, MyType::Session, RANDOM_MASK).unwrap(),
+            Ttid::<MyType>::from_parts(ts + 1, MyType::User, 0).unwrap(),
+            Ttid::<MyType>::from_parts(ts + 1, MyType::Org, 0).unwrap(),
+            Ttid::<MyType>::from_parts(ts + 1, MyType::Org, 1).unwrap(),
+        ];
+
+        for pair in ids.windows(2) {
+            assert!(pair[0].to_ordered_u128() < pair[1].to_ordered_u128());
+        }
+        for id in ids {
+            assert_eq!(Ttid::from_ordered_u128(id.to_ordered_u128()).unwrap(), id);
+        }
+
+        let err = Ttid::<MyType>::from_ordered_u128(Uuid::new_v4().as_u128()).unwrap_err();
+        assert!(matches!(err, TtidError::InvalidUuid));
+    }
+
+    #[test]
+    fn signed_integer_mappings_roundtrip_and_follow_time_order() {
+        let ts = 1_700_000_000_000;
+        let ids = [
+            Ttid::<MyType>::from_parts(0, MyType::User, 0).unwrap(),
+            Ttid::<MyType>::from_parts(ts, MyType::Session, RANDOM_MASK).unwrap(),
+            Ttid::<MyType>::from_parts(ts + 1, MyType::User, 0).unwrap(),
+            Ttid::<MyType>::from_parts(ts + 1, MyType::User, 1).unwrap(),
+            Ttid::<MyType>::from_parts(TIMESTAMP_MAX, MyType::Max, RANDOM_MASK).unwrap(),
+        ];
+
+        for pair in ids.windows(2) {
+            assert!(pair[0].to_ordered_i128() < pair[1].to_ordered_i128());
+            assert!(pair[0].to_i64_pair() < pair[1].to_i64_pair());
+        }
+        for id in ids {
+            assert_eq!(Ttid::from_ordered_i128(id.to_ordered_i128()).unwrap(), id);
+            let (high, low) = id.to_i64_pair();
+            assert_eq!(Ttid::from_i64_pair(high, low).unwrap(), id);
+        }
+
+        let err = Ttid::<MyType>::from_i64_pair(0, 0).unwrap_err();
+        assert!(matches!(err, TtidError::InvalidUuid));
+    }
+
+    #[test]
+    fn byte_orders_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(0x0102_0304_0506, MyType::Org, 42).unwrap();
+        let be = id.to_bytes_be();
+        let le = id.to_bytes_le();
+        assert_eq!(be[..6], [1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            le[..8],
+            [be[3], be[2], be[1], be[0], be[5], be[4], be[7], be[6]]
+        );
+        assert_eq!(le[8..], be[8..]);
+
+        assert_eq!(Ttid::from_bytes_be(be).unwrap(), id);
+        assert_eq!(Ttid::from_bytes_le(le).unwrap(), id);
+        let err = Ttid::<MyType>::from_bytes_be(le).unwrap_err();
+        assert!(matches!(err, TtidError::InvalidUuid));
+    }
+
+    #[test]
+    fn new_at_uses_the_given_time() {
+        let time = UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_999);
+        let a = Ttid::<MyType>::new_at(MyType::Org, time).unwrap();
+        let b = Ttid::<MyType>::new_at(MyType::Org, time).unwrap();
+        assert_eq!(a.timestamp_ms(), 1_700_000_000_123);
+        assert_eq!(a.id_type(), MyType::Org);
+        assert_ne!(a, b);
+
+        let before_epoch = UNIX_EPOCH - Duration::from_millis(1);
+        assert!(matches!(
+            Ttid::<MyType>::new_at(MyType::Org, before_epoch),
+            Err(TtidError::TimestampOutOfRange)
+        ));
+        assert!(matches!(
+            Ttid::<MyType>::new_at_ms(MyType::Org, TIMESTAMP_MAX + 1),
+            Err(TtidError::TimestampOutOfRange)
+        ));
+    }
+
     #[test]
     fn validates_part_limits() {
         let too_large_ts = TIMESTAMP_MAX + 1;
@@ -458,6 +1376,15 @@ mod tests {
         assert_eq!(parsed.timestamp_ms(), 1_700_000_000_000);
     }
 
+    #[test]
+    fn max_string_len_bounds_rendered_ids() {
+        assert_eq!(MyType::MAX_STRING_LEN, 30);
+        assert_eq!(NarrowType::MAX_STRING_LEN, TTID_STRING_MAX_LEN);
+
+        let ttid = Ttid::<MyType>::from_parts(TIMESTAMP_MAX, MyType::Session, RANDOM_MASK).unwrap();
+        assert!(ttid.to_string().len() <= MyType::MAX_STRING_LEN);
+    }
+
     #[test]
     fn two_new_ids_are_distinct() {
         let a = Ttid::<MyType>::new(MyType::User).unwrap();
@@ -0,0 +1,87 @@
+use crate::TypeNameError;
+
+/// Maximum length in bytes of a type name returned by
+/// [`IdType::as_type_name`](crate::IdType::as_type_name).
+///
+/// Together with the fixed shortuuid length this bounds the total string
+/// length of a TTID, so storage columns can be sized up front.
+pub const TYPE_NAME_MAX_LEN: usize = 16;
+
+/// Maximum length in bytes of the shortuuid part of a TTID string.
+pub const SHORT_UUID_MAX_LEN: usize = 22;
+
+/// Maximum length in bytes of any TTID string, `<type-name>_<shortuuid>`.
+///
+/// See [`IdType::MAX_STRING_LEN`](crate::IdType::MAX_STRING_LEN) for a
+/// tighter per-domain bound.
+pub const TTID_STRING_MAX_LEN: usize = TYPE_NAME_MAX_LEN + 1 + SHORT_UUID_MAX_LEN;
+
+/// Validate a type name against the TTID naming rules.
+///
+/// A valid name is non-empty, at most [`TYPE_NAME_MAX_LEN`] bytes long and
+/// consists of ASCII letters, digits and `-` only. In particular `_` is not
+/// allowed, since it separates the type name from the shortuuid.
+///
+/// The string form of an id whose type name passes this check therefore only
+/// contains ASCII letters, digits, `-` and `_`, and composite string forms
+/// such as [`TtidPair`](crate::TtidPair) split on other characters.
+/// Displaying a [`Ttid`](crate::Ttid) does not check the name returned by
+/// [`IdType::as_type_name`](crate::IdType::as_type_name):
+/// [`id_type!`](crate::id_type!) domains are validated at compile time,
+/// hand-written domains must only return valid names.
+///
+/// This is a `const fn`, so domains can check their names at compile time:
+///
+/// ```
+/// const _: () = assert!(ttid::validate_type_name("user").is_ok());
+/// ```
+pub const fn validate_type_name(name: &str) -> Result<(), TypeNameError> {
+    let bytes = name.as_bytes();
+
+    if bytes.is_empty() {
+        return Err(TypeNameError::Empty);
+    }
+    if bytes.len() > TYPE_NAME_MAX_LEN {
+        return Err(TypeNameError::TooLong(bytes.len()));
+    }
+
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if !(byte.is_ascii_alphanumeric() || byte == b'-') {
+            return Err(TypeNameError::InvalidCharacter { index });
+        }
+        index += 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_names() {
+        assert_eq!(validate_type_name("user"), Ok(()));
+        assert_eq!(validate_type_name("api-key2"), Ok(()));
+        assert_eq!(validate_type_name("abcdefghijklmnop"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        assert_eq!(validate_type_name(""), Err(TypeNameError::Empty));
+        assert_eq!(
+            validate_type_name("abcdefghijklmnopq"),
+            Err(TypeNameError::TooLong(17))
+        );
+        assert_eq!(
+            validate_type_name("org_member"),
+            Err(TypeNameError::InvalidCharacter { index: 3 })
+        );
+        assert_eq!(
+            validate_type_name("usér"),
+            Err(TypeNameError::InvalidCharacter { index: 2 })
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{IdType, Ttid};
+
+/// Newtype wrapper around a [`Ttid`], e.g. `struct UserId(Ttid<MyType>)`.
+///
+/// Implement it with [`impl_newtype!`](crate::impl_newtype), which also
+/// forwards the common trait surface of [`Ttid`] to the wrapper.
+pub trait TtidNewtype: Sized {
+    /// Type domain of the wrapped TTID.
+    type Domain: IdType;
+
+    /// Wrap a TTID.
+    fn from_inner(inner: Ttid<Self::Domain>) -> Self;
+
+    /// Return the wrapped TTID.
+    fn inner(&self) -> Ttid<Self::Domain>;
+}
+
+/// Implement [`TtidNewtype`] and forward the [`Ttid`] trait surface for a
+/// tuple struct wrapping a single `Ttid<Domain>`.
+///
+/// Generates:
+/// - [`TtidNewtype`],
+/// - `Display` and `FromStr` in `<type-name>_<shortuuid>` form,
+/// - `From` conversions between the wrapper and `Ttid<Domain>`,
+/// - `PartialOrd`/`Ord` and `Hash` by the underlying UUID, consistent with a
+///   derived `PartialEq`/`Eq` on the wrapper,
+/// - `AsRef<Uuid>`,
+/// - with the `serde` feature, `Serialize`/`Deserialize` in string form,
+/// - with the `sqlx` feature, `sqlx::Type`/`Encode`/`Decode` like [`Ttid`],
+/// - with the `diesel` feature, `diesel::serialize::ToSql` and
+///   `diesel::deserialize::FromSql` for every SQL type [`Ttid`] supports.
+///   Diesel requires the wrapper to implement `Debug`; derive
+///   `AsExpression` and `FromSqlRow` on it to use it in typed queries.
+///
+/// ```
+/// use ttid::{IdType, Ttid, TtidNewtype};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// enum MyType {
+///     User,
+/// }
+///
+/// impl IdType for MyType {
+///     fn to_type_id(self) -> u16 {
+///         1
+///     }
+///
+///     fn from_type_id(id: u16) -> Option<Self> {
+///         (id == 1).then_some(Self::User)
+///     }
+///
+///     fn as_type_name(self) -> &'static str {
+///         "user"
+///     }
+///
+///     fn from_type_name(name: &str) -> Option<Self> {
+///         (name == "user").then_some(Self::User)
+///     }
+/// }
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// struct UserId(Ttid<MyType>);
+///
+/// ttid::impl_newtype!(UserId, MyType);
+///
+/// let id = UserId::from_inner(Ttid::new(MyType::User).unwrap());
+/// let parsed: UserId = id.to_string().parse().unwrap();
+/// assert_eq!(parsed, id);
+/// ```
+#[macro_export]
+macro_rules! impl_newtype {
+    ($name:ident, $domain:ty) => {
+        impl $crate::TtidNewtype for $name {
+            type Domain = $domain;
+
+            fn from_inner(inner: $crate::Ttid<$domain>) -> Self {
+                Self(inner)
+            }
+
+            fn inner(&self) -> $crate::Ttid<$domain> {
+                self.0
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::core::str::FromStr for $name {
+            type Err = $crate::ParseTtidError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                s.parse::<$crate::Ttid<$domain>>().map(Self)
+            }
+        }
+
+        impl ::core::convert::From<$crate::Ttid<$domain>> for $name {
+            fn from(value: $crate::Ttid<$domain>) -> Self {
+                Self(value)
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::Ttid<$domain> {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl ::core::cmp::PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl ::core::cmp::Ord for $name {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                self.0.as_uuid().cmp(&other.0.as_uuid())
+            }
+        }
+
+        impl ::core::hash::Hash for $name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                ::core::hash::Hash::hash(&self.0.as_uuid(), state)
+            }
+        }
+
+        impl ::core::convert::AsRef<$crate::__private::Uuid> for $name {
+            fn as_ref(&self) -> &$crate::__private::Uuid {
+                self.0.as_ref()
+            }
+        }
+
+        $crate::__impl_newtype_serde!($name);
+        $crate::__impl_newtype_sqlx!($name, $domain);
+        $crate::__impl_newtype_diesel!($name, $domain);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_serde {
+    ($name:ident) => {
+        impl $crate::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::__private::serde::Deserialize::deserialize(deserializer).map(Self)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_serde {
+    ($name:ident) => {};
+}
+
+#[cfg(feature = "sqlx")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_sqlx {
+    ($name:ident, $domain:ty) => {
+        impl<DB: $crate::__private::sqlx::Database> $crate::__private::sqlx::Type<DB> for $name
+        where
+            $crate::Ttid<$domain>: $crate::__private::sqlx::Type<DB>,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <$crate::Ttid<$domain> as $crate::__private::sqlx::Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <$crate::Ttid<$domain> as $crate::__private::sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'q, DB: $crate::__private::sqlx::Database> $crate::__private::sqlx::Encode<'q, DB>
+            for $name
+        where
+            $crate::Ttid<$domain>: $crate::__private::sqlx::Encode<'q, DB>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as $crate::__private::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> ::core::result::Result<
+                $crate::__private::sqlx::encode::IsNull,
+                $crate::__private::sqlx::error::BoxDynError,
+            > {
+                $crate::__private::sqlx::Encode::<'q, DB>::encode_by_ref(&self.0, buf)
+            }
+
+            fn produces(&self) -> ::core::option::Option<DB::TypeInfo> {
+                $crate::__private::sqlx::Encode::<'q, DB>::produces(&self.0)
+            }
+
+            fn size_hint(&self) -> usize {
+                $crate::__private::sqlx::Encode::<'q, DB>::size_hint(&self.0)
+            }
+        }
+
+        impl<'r, DB: $crate::__private::sqlx::Database> $crate::__private::sqlx::Decode<'r, DB>
+            for $name
+        where
+            $crate::Ttid<$domain>: $crate::__private::sqlx::Decode<'r, DB>,
+        {
+            fn decode(
+                value: <DB as $crate::__private::sqlx::Database>::ValueRef<'r>,
+            ) -> ::core::result::Result<Self, $crate::__private::sqlx::error::BoxDynError> {
+                <$crate::Ttid<$domain> as $crate::__private::sqlx::Decode<'r, DB>>::decode(value)
+                    .map(Self)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "sqlx"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_sqlx {
+    ($name:ident, $domain:ty) => {};
+}
+
+#[cfg(feature = "diesel")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_diesel {
+    ($name:ident, $domain:ty) => {
+        impl<ST, DB> $crate::__private::diesel::serialize::ToSql<ST, DB> for $name
+        where
+            DB: $crate::__private::diesel::backend::Backend,
+            $crate::Ttid<$domain>: $crate::__private::diesel::serialize::ToSql<ST, DB>,
+        {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut $crate::__private::diesel::serialize::Output<'b, '_, DB>,
+            ) -> $crate::__private::diesel::serialize::Result {
+                $crate::__private::diesel::serialize::ToSql::<ST, DB>::to_sql(&self.0, out)
+            }
+        }
+
+        impl<ST, DB> $crate::__private::diesel::deserialize::FromSql<ST, DB> for $name
+        where
+            DB: $crate::__private::diesel::backend::Backend,
+            $crate::Ttid<$domain>: $crate::__private::diesel::deserialize::FromSql<ST, DB>,
+        {
+            fn from_sql(
+                bytes: <DB as $crate::__private::diesel::backend::Backend>::RawValue<'_>,
+            ) -> $crate::__private::diesel::deserialize::Result<Self> {
+                let inner: $crate::Ttid<$domain> =
+                    $crate::__private::diesel::deserialize::FromSql::<ST, DB>::from_sql(bytes)?;
+                ::core::result::Result::Ok(Self(inner))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "diesel"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_diesel {
+    ($name:ident, $domain:ty) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::MyType;
+    use crate::{ParseTtidError, Ttid, TtidNewtype};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct UserId(Ttid<MyType>);
+
+    crate::impl_newtype!(UserId, MyType);
+
+    #[test]
+    fn newtype_forwards_string_form_and_conversions() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+        let id = UserId::from_inner(ttid);
+
+        assert_eq!(id.inner(), ttid);
+        assert_eq!(id.to_string(), ttid.to_string());
+        assert_eq!(id.to_string().parse::<UserId>().unwrap(), id);
+        assert_eq!(UserId::from(ttid), id);
+        assert_eq!(Ttid::from(id), ttid);
+        assert!(matches!(
+            "user".parse::<UserId>(),
+            Err(ParseTtidError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn newtype_forwards_ordering_hash_and_uuid() {
+        let early = UserId(Ttid::from_parts(1_000, MyType::User, 9).unwrap());
+        let late = UserId(Ttid::from_parts(2_000, MyType::User, 1).unwrap());
+
+        let mut ids = vec![late, early];
+        ids.sort();
+        assert_eq!(ids, vec![early, late]);
+
+        let set: std::collections::HashSet<_> = [early, late, early].into_iter().collect();
+        assert_eq!(set.len(), 2);
+
+        let uuid: &uuid::Uuid = early.as_ref();
+        assert_eq!(*uuid, early.inner().as_uuid());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn newtype_serde_uses_string_form() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 42).unwrap();
+        let id = UserId(ttid);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{ttid}\""));
+        assert_eq!(serde_json::from_str::<UserId>(&json).unwrap(), id);
+    }
+
+    #[cfg(feature = "sqlx")]
+    #[tokio::test]
+    async fn newtype_sqlx_forwards_to_ttid() {
+        use sqlx::{Connection, Row, SqliteConnection};
+
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        let id = UserId(Ttid::from_parts(1_700_000_000_000, MyType::User, 42).unwrap());
+        let row = sqlx::query("SELECT ? AS id")
+            .bind(id)
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<UserId, _>("id"), id);
+    }
+
+    #[cfg(feature = "diesel-sqlite")]
+    #[test]
+    fn newtype_diesel_forwards_to_ttid() {
+        use diesel::prelude::*;
+        use diesel::sql_types::Binary;
+
+        #[derive(QueryableByName)]
+        struct Row {
+            #[diesel(sql_type = Binary)]
+            id: UserId,
+        }
+
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        let id = UserId(Ttid::from_parts(1_700_000_000_000, MyType::User, 42).unwrap());
+        let row: Row = diesel::sql_query("SELECT ? AS id")
+            .bind::<Binary, _>(id)
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(row.id, id);
+    }
+}
@@ -0,0 +1,71 @@
+//! [OpenTelemetry](https://docs.rs/opentelemetry) attributes.
+//!
+//! Ids are tagged under the key `<type-name>.id`, e.g. `user.id`, following
+//! the semantic convention naming of entity ids, with the
+//! `<type-name>_<shortuuid>` string form as value:
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use opentelemetry::KeyValue;
+//!
+//! let id = Ttid::new(User).unwrap();
+//! let attribute = KeyValue::from(id);
+//! assert_eq!(attribute.key.as_str(), "user.id");
+//! assert_eq!(attribute.value.as_str(), id.to_string());
+//! ```
+//!
+//! Use [`attribute_key`] with `KeyValue::new` to tag an id under another
+//! name, e.g. the owner of a resource.
+
+use ::opentelemetry::{Key, KeyValue, Value};
+
+use crate::{IdType, Ttid};
+
+/// Suffix appended to the type name to form the attribute key of an id.
+pub const ID_KEY_SUFFIX: &str = ".id";
+
+/// Attribute key `<name>.id` for ids of role `name`, e.g. `owner.id`.
+pub fn attribute_key(name: &str) -> Key {
+    Key::new(format!("{name}{ID_KEY_SUFFIX}"))
+}
+
+/// The `<type-name>_<shortuuid>` string form.
+impl<T: IdType> From<Ttid<T>> for Value {
+    fn from(id: Ttid<T>) -> Self {
+        Value::from(id.to_string())
+    }
+}
+
+/// The id under the key `<type-name>.id`.
+impl<T: IdType> From<Ttid<T>> for KeyValue {
+    fn from(id: Ttid<T>) -> Self {
+        KeyValue::new(attribute_key(id.id_type().as_type_name()), id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn attributes_use_the_type_name_key() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+
+        let attribute = KeyValue::from(id);
+        assert_eq!(attribute.key, Key::from_static_str("session.id"));
+        assert_eq!(attribute.value, Value::from(id.to_string()));
+
+        let owner = KeyValue::new(attribute_key("owner"), id);
+        assert_eq!(owner.key.as_str(), "owner.id");
+        assert_eq!(owner.value.as_str(), id.to_string());
+    }
+}
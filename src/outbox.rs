@@ -0,0 +1,186 @@
+//! Id conventions for the transactional outbox pattern.
+//!
+//! An outbox table keyed by [`OutboxRecordId`] is written in the same
+//! transaction as the entity change and drained by a relay in id order. Ids
+//! embed their creation time, so the relay polls `WHERE id > $last` without
+//! a separate sequence column. Consumers deduplicate redelivered records by
+//! their record id.
+//!
+//! The [`dedupe_key`] of a record names the triggering entity and event, e.g.
+//! `order_hnP4K7MiDaGjM9R6vMshtY:created`. Storing it in a unique column
+//! keeps a retried transaction from enqueuing the same event twice:
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # enum Kind { Order, Outbox }
+//! # impl IdType for Kind {
+//! #     fn to_type_id(self) -> u16 { self as u16 + 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> {
+//! #         match id { 1 => Some(Kind::Order), 2 => Some(Kind::Outbox), _ => None }
+//! #     }
+//! #     fn as_type_name(self) -> &'static str {
+//! #         match self { Kind::Order => "order", Kind::Outbox => "outbox" }
+//! #     }
+//! #     fn from_type_name(name: &str) -> Option<Self> {
+//! #         match name { "order" => Some(Kind::Order), "outbox" => Some(Kind::Outbox), _ => None }
+//! #     }
+//! # }
+//! use ttid::outbox::{self, OutboxRecordId};
+//!
+//! let order = Ttid::new(Kind::Order).unwrap();
+//! let record = OutboxRecordId::new(Kind::Outbox).unwrap();
+//! let key = outbox::dedupe_key(&order, "created");
+//! // INSERT INTO outbox (id, dedupe_key, payload) VALUES ($record, $key, ...)
+//! assert_eq!(outbox::parse_dedupe_key::<Kind>(&key).unwrap(), (order, "created"));
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::{IdType, ParseTtidError, Ttid, TtidError};
+
+/// Separator between entity id and event name in a [`dedupe_key`], not part
+/// of any id with a [valid](crate::validate_type_name) type name.
+const DEDUPE_SEPARATOR: char = ':';
+
+/// Id of an outbox record, distinct from entity ids so the two cannot be
+/// mixed up.
+///
+/// Displays, parses and orders like the wrapped [`Ttid`].
+#[derive(Clone, Copy, Debug)]
+pub struct OutboxRecordId<T: IdType>(pub Ttid<T>);
+
+impl<T: IdType> OutboxRecordId<T> {
+    /// New record id of the outbox type `ty` at the current time.
+    ///
+    /// For strictly increasing ids within a millisecond, wrap ids from a
+    /// [`MonotonicGenerator`](crate::generator::MonotonicGenerator) instead.
+    pub fn new(ty: T) -> Result<Self, TtidError> {
+        Ttid::new(ty).map(Self)
+    }
+
+    /// The wrapped id.
+    pub fn as_ttid(&self) -> Ttid<T> {
+        self.0
+    }
+}
+
+impl<T: IdType> From<Ttid<T>> for OutboxRecordId<T> {
+    fn from(id: Ttid<T>) -> Self {
+        Self(id)
+    }
+}
+
+impl<T: IdType> From<OutboxRecordId<T>> for Ttid<T> {
+    fn from(id: OutboxRecordId<T>) -> Self {
+        id.0
+    }
+}
+
+impl<T: IdType> PartialEq for OutboxRecordId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_uuid() == other.0.as_uuid()
+    }
+}
+
+impl<T: IdType> Eq for OutboxRecordId<T> {}
+
+impl<T: IdType> PartialOrd for OutboxRecordId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: IdType> Ord for OutboxRecordId<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.as_uuid().cmp(&other.0.as_uuid())
+    }
+}
+
+impl<T: IdType> Hash for OutboxRecordId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_uuid().hash(state);
+    }
+}
+
+impl<T: IdType> fmt::Display for OutboxRecordId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<T: IdType> FromStr for OutboxRecordId<T> {
+    type Err = ParseTtidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+/// Deduplication key `<entity>:<event>` of the outbox record for `event` of
+/// `entity`, see the [module docs](self).
+///
+/// `event` should identify the change, e.g. `created` or `updated:v3`, and
+/// may itself contain `:`.
+pub fn dedupe_key<E: IdType>(entity: &Ttid<E>, event: &str) -> String {
+    format!("{entity}{DEDUPE_SEPARATOR}{event}")
+}
+
+/// Parse a key produced by [`dedupe_key`] into the entity id and event.
+pub fn parse_dedupe_key<E: IdType>(key: &str) -> Result<(Ttid<E>, &str), ParseTtidError> {
+    let (entity, event) = key
+        .split_once(DEDUPE_SEPARATOR)
+        .ok_or(ParseTtidError::InvalidFormat)?;
+    Ok((entity.parse()?, event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn record_ids_order_like_ttids() {
+        let early = Ttid::<MyType>::from_parts(1_000, MyType::Session, 9).unwrap();
+        let late = Ttid::<MyType>::from_parts(2_000, MyType::Session, 1).unwrap();
+        let mut records = [OutboxRecordId::from(late), OutboxRecordId::from(early)];
+        records.sort();
+        assert_eq!(records.map(Ttid::from), [early, late]);
+
+        let text = records[0].to_string();
+        assert_eq!(text, early.to_string());
+        assert_eq!(text.parse::<OutboxRecordId<MyType>>().unwrap(), records[0]);
+        assert_eq!(
+            OutboxRecordId::new(MyType::Session)
+                .unwrap()
+                .as_ttid()
+                .id_type(),
+            MyType::Session
+        );
+    }
+
+    #[test]
+    fn dedupe_keys_link_to_the_entity() {
+        let entity = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 42).unwrap();
+
+        let key = dedupe_key(&entity, "updated:v3");
+        assert_eq!(key, format!("{entity}:updated:v3"));
+        assert_eq!(
+            parse_dedupe_key::<MyType>(&key).unwrap(),
+            (entity, "updated:v3")
+        );
+        assert_ne!(dedupe_key(&entity, "created"), key);
+
+        assert_eq!(
+            parse_dedupe_key::<MyType>(&entity.to_string()),
+            Err(ParseTtidError::InvalidFormat)
+        );
+        assert_eq!(
+            parse_dedupe_key::<MyType>("team_x:created"),
+            Err(ParseTtidError::UnknownTypeName)
+        );
+    }
+}
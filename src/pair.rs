@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// Separator between the two ids in the string form of a [`TtidPair`], not
+/// part of any id with a [valid](crate::validate_type_name) type name.
+const PAIR_SEPARATOR: &str = "..";
+
+/// Composite key of two typed ids, e.g. a follower → followee edge.
+///
+/// The string form is `<first>..<second>`, e.g.
+/// `user_hnP4K7MiDaGjM9R6vMshtY..org_5Gq9RmvqjvsvRbT3eJgQ1u`. Ordering is by
+/// `first`, then `second`, each in binary UUID order.
+#[derive(Clone, Copy, Debug)]
+pub struct TtidPair<A: IdType, B: IdType> {
+    first: Ttid<A>,
+    second: Ttid<B>,
+}
+
+impl<A: IdType, B: IdType> TtidPair<A, B> {
+    /// Combine two ids.
+    pub fn new(first: Ttid<A>, second: Ttid<B>) -> Self {
+        Self { first, second }
+    }
+
+    /// Return the first id.
+    pub fn first(&self) -> Ttid<A> {
+        self.first
+    }
+
+    /// Return the second id.
+    pub fn second(&self) -> Ttid<B> {
+        self.second
+    }
+
+    /// Split into `(first, second)`.
+    pub fn into_parts(self) -> (Ttid<A>, Ttid<B>) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: IdType, B: IdType> From<(Ttid<A>, Ttid<B>)> for TtidPair<A, B> {
+    fn from((first, second): (Ttid<A>, Ttid<B>)) -> Self {
+        Self::new(first, second)
+    }
+}
+
+impl<A: IdType, B: IdType> PartialEq for TtidPair<A, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<A: IdType, B: IdType> Eq for TtidPair<A, B> {}
+
+impl<A: IdType, B: IdType> PartialOrd for TtidPair<A, B> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<A: IdType, B: IdType> Ord for TtidPair<A, B> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.first.as_uuid(), self.second.as_uuid())
+            .cmp(&(other.first.as_uuid(), other.second.as_uuid()))
+    }
+}
+
+impl<A: IdType, B: IdType> Hash for TtidPair<A, B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.first.as_uuid().hash(state);
+        self.second.as_uuid().hash(state);
+    }
+}
+
+impl<A: IdType, B: IdType> fmt::Display for TtidPair<A, B> {
+    /// Formats as `<first>..<second>`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{PAIR_SEPARATOR}{}", self.first, self.second)
+    }
+}
+
+impl<A: IdType, B: IdType> FromStr for TtidPair<A, B> {
+    type Err = ParseTtidError;
+
+    /// Parses `<first>..<second>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, second) = s
+            .split_once(PAIR_SEPARATOR)
+            .ok_or(ParseTtidError::InvalidFormat)?;
+        Ok(Self::new(first.parse()?, second.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn pair_string_form_roundtrips() {
+        let user = Ttid::<MyType>::from_parts(1_000, MyType::User, 1).unwrap();
+        let org = Ttid::<MyType>::from_parts(2_000, MyType::Org, 2).unwrap();
+        let pair = TtidPair::new(user, org);
+
+        let text = pair.to_string();
+        assert_eq!(text, format!("{user}..{org}"));
+        assert_eq!(text.parse::<TtidPair<MyType, MyType>>().unwrap(), pair);
+        assert_eq!(pair.into_parts(), (user, org));
+
+        assert!(matches!(
+            user.to_string().parse::<TtidPair<MyType, MyType>>(),
+            Err(ParseTtidError::InvalidFormat)
+        ));
+        assert!(matches!(
+            format!("{user}..org").parse::<TtidPair<MyType, MyType>>(),
+            Err(ParseTtidError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn pair_orders_by_first_then_second() {
+        let a = Ttid::<MyType>::from_parts(1_000, MyType::User, 1).unwrap();
+        let b = Ttid::<MyType>::from_parts(2_000, MyType::User, 1).unwrap();
+
+        let mut pairs = vec![
+            TtidPair::new(b, a),
+            TtidPair::new(a, b),
+            TtidPair::new(a, a),
+        ];
+        pairs.sort();
+
+        assert_eq!(
+            pairs,
+            vec![
+                TtidPair::new(a, a),
+                TtidPair::new(a, b),
+                TtidPair::new(b, a),
+            ]
+        );
+    }
+}
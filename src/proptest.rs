@@ -0,0 +1,118 @@
+//! [`proptest`](https://docs.rs/proptest) strategies for TTIDs.
+//!
+//! Types are picked from [`IdType::TYPE_NAMES`]. For domains not listing
+//! their names, strategies probe every type id with [`IdType::from_type_id`]
+//! once on construction.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     const TYPE_NAMES: &'static [&'static str] = &["user"];
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use proptest::prelude::*;
+//! use proptest::test_runner::TestRunner;
+//! use ttid::proptest::ttid_in_range;
+//!
+//! let mut runner = TestRunner::default();
+//! runner
+//!     .run(&ttid_in_range::<User>(0..1_000_000), |id| {
+//!         prop_assert_eq!(id.to_string().parse::<Ttid<User>>().unwrap(), id);
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+use ::proptest::prelude::*;
+use ::proptest::sample::select;
+
+use crate::deser::TIMESTAMP_MAX;
+use crate::{IdType, Ttid, UNTYPED_TYPE_ID};
+
+/// Any valid TTID of domain `T`.
+pub fn any_ttid<T: IdType + Debug + 'static>() -> impl Strategy<Value = Ttid<T>> {
+    ttid_with(any_type(), 0..=TIMESTAMP_MAX)
+}
+
+/// TTIDs of domain `T` with timestamps in `timestamps_ms`.
+///
+/// # Panics
+///
+/// Panics if the range is empty or exceeds the 48-bit timestamp range.
+pub fn ttid_in_range<T: IdType + Debug + 'static>(
+    timestamps_ms: Range<u64>,
+) -> impl Strategy<Value = Ttid<T>> {
+    assert!(
+        timestamps_ms.start < timestamps_ms.end && timestamps_ms.end - 1 <= TIMESTAMP_MAX,
+        "invalid TTID timestamp range {timestamps_ms:?}"
+    );
+    ttid_with(any_type(), timestamps_ms)
+}
+
+/// TTIDs of type `ty`.
+pub fn ttid_of_type<T: IdType + Debug + 'static>(ty: T) -> impl Strategy<Value = Ttid<T>> {
+    ttid_with(Just(ty), 0..=TIMESTAMP_MAX)
+}
+
+fn ttid_with<T: IdType + Debug>(
+    types: impl Strategy<Value = T>,
+    timestamps_ms: impl Strategy<Value = u64>,
+) -> impl Strategy<Value = Ttid<T>> {
+    (types, timestamps_ms, any::<u64>()).prop_map(|(ty, timestamp_ms, randomness)| {
+        Ttid::from_parts(timestamp_ms, ty, randomness).expect("timestamp within range")
+    })
+}
+
+/// # Panics
+///
+/// Panics if the domain has no types.
+fn any_type<T: IdType + Debug + 'static>() -> BoxedStrategy<T> {
+    if T::TYPE_NAMES.is_empty() {
+        let types: Vec<T> = (UNTYPED_TYPE_ID + 1..=u16::MAX)
+            .filter_map(T::from_type_id)
+            .collect();
+        select(types).boxed()
+    } else {
+        select(T::TYPE_NAMES)
+            .prop_map(|name| {
+                T::from_type_name(name).expect("TYPE_NAMES lists names accepted by from_type_name")
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    proptest! {
+        #[test]
+        fn any_ttid_is_valid(id in any_ttid::<MyType>()) {
+            prop_assert_eq!(Ttid::<MyType>::from_uuid(id.as_uuid()), Ok(id));
+        }
+
+        #[test]
+        fn unlisted_domains_yield_known_types(id in any_ttid::<NarrowType>()) {
+            prop_assert_eq!(id.id_type(), NarrowType::User);
+        }
+
+        #[test]
+        fn ttid_in_range_respects_bounds(id in ttid_in_range::<MyType>(1_000..2_000)) {
+            prop_assert!((1_000..2_000).contains(&id.timestamp_ms()));
+        }
+
+        #[test]
+        fn ttid_of_type_fixes_the_type(id in ttid_of_type(MyType::Org)) {
+            prop_assert_eq!(id.id_type(), MyType::Org);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use uuid::Uuid;
+
+use crate::deser::{
+    RANDOM_BITS, RANDOM_MASK, TIMESTAMP_MAX, TYPE_BITS, TYPE_ID_MAX, decode_payload_from_uuid,
+    encode_payload_to_uuid, pack_payload, payload_of,
+};
+use crate::{IdType, Ttid, TtidError, UNTYPED_TYPE_ID, now_ms, random_bits};
+
+/// TTID with a validated UUIDv8 layout but no type domain attached.
+///
+/// Useful when handling ids of mixed types, e.g. in ingestion jobs, before
+/// they are resolved into a concrete [`Ttid<T>`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RawTtid {
+    uuid: Uuid,
+}
+
+impl RawTtid {
+    /// Create an untyped TTID from the current time and fresh randomness.
+    ///
+    /// The type id is [`UNTYPED_TYPE_ID`].
+    pub fn new_untyped() -> Result<Self, TtidError> {
+        Self::untyped(now_ms()?, random_bits())
+    }
+
+    /// Construct an untyped TTID from explicit components.
+    ///
+    /// Untyped ids are placeholders, e.g. for migrations, and are rejected by
+    /// every typed domain. `randomness` is masked to the low 58 bits.
+    pub fn untyped(timestamp_ms: u64, randomness: u64) -> Result<Self, TtidError> {
+        Self::from_parts(timestamp_ms, UNTYPED_TYPE_ID, randomness)
+    }
+
+    /// Construct from explicit components without resolving the type id.
+    pub(crate) fn from_parts(
+        timestamp_ms: u64,
+        type_id: u16,
+        randomness: u64,
+    ) -> Result<Self, TtidError> {
+        if timestamp_ms > TIMESTAMP_MAX {
+            return Err(TtidError::TimestampOutOfRange);
+        }
+
+        let payload = pack_payload(timestamp_ms, type_id, randomness);
+        Ok(Self {
+            uuid: encode_payload_to_uuid(payload),
+        })
+    }
+
+    /// Validate and wrap a UUID as raw TTID.
+    ///
+    /// Only the layout is checked, the type id is not resolved.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn from_uuid(uuid: Uuid) -> Result<Self, TtidError> {
+        decode_payload_from_uuid(uuid).ok_or(TtidError::InvalidUuid)?;
+        Ok(Self { uuid })
+    }
+
+    /// Validate and wrap raw UUID bytes as raw TTID.
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<Self, TtidError> {
+        Self::from_uuid(Uuid::from_bytes(bytes))
+    }
+
+    /// Borrow the raw UUID value.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn as_uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Extract millisecond Unix timestamp.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn timestamp_ms(&self) -> u64 {
+        (payload_of(self.uuid) >> (TYPE_BITS + RANDOM_BITS)) as u64
+    }
+
+    /// Extract numeric type id.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn type_id(&self) -> u16 {
+        ((payload_of(self.uuid) >> RANDOM_BITS) & (TYPE_ID_MAX as u128)) as u16
+    }
+
+    /// Extract random 58-bit component.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn randomness(&self) -> u64 {
+        (payload_of(self.uuid) as u64) & RANDOM_MASK
+    }
+
+    /// Whether the type id is [`UNTYPED_TYPE_ID`].
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn is_untyped(&self) -> bool {
+        self.type_id() == UNTYPED_TYPE_ID
+    }
+
+    /// Resolve into a typed TTID of domain `T`.
+    pub fn typed<T: IdType>(self) -> Result<Ttid<T>, TtidError> {
+        Ttid::from_uuid(self.uuid)
+    }
+}
+
+impl TryFrom<Uuid> for RawTtid {
+    type Error = TtidError;
+
+    fn try_from(value: Uuid) -> Result<Self, Self::Error> {
+        Self::from_uuid(value)
+    }
+}
+
+impl From<RawTtid> for Uuid {
+    fn from(value: RawTtid) -> Self {
+        value.uuid
+    }
+}
+
+impl<T: IdType> From<Ttid<T>> for RawTtid {
+    fn from(value: Ttid<T>) -> Self {
+        Self {
+            uuid: value.as_uuid(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[test]
+    fn raw_exposes_parts_and_resolves_type() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 9).unwrap();
+        let raw = RawTtid::from_uuid(ttid.as_uuid()).unwrap();
+
+        assert_eq!(raw.timestamp_ms(), 1_700_000_000_000);
+        assert_eq!(raw.type_id(), 777);
+        assert_eq!(raw.randomness(), 9);
+        assert_eq!(raw.typed::<MyType>().unwrap(), ttid);
+        assert!(matches!(
+            raw.typed::<NarrowType>(),
+            Err(TtidError::UnknownTypeId(777))
+        ));
+    }
+
+    #[test]
+    fn untyped_ids_are_rejected_by_typed_domains() {
+        let raw = RawTtid::untyped(1_700_000_000_000, 5).unwrap();
+
+        assert!(raw.is_untyped());
+        assert_eq!(raw.type_id(), UNTYPED_TYPE_ID);
+        assert_eq!(raw.timestamp_ms(), 1_700_000_000_000);
+        assert_eq!(raw.randomness(), 5);
+        assert!(matches!(
+            raw.typed::<MyType>(),
+            Err(TtidError::ReservedTypeId)
+        ));
+        assert!(!RawTtid::new_untyped().unwrap().as_uuid().is_nil());
+    }
+
+    #[test]
+    fn raw_rejects_non_ttid_uuid() {
+        let err = RawTtid::from_uuid(Uuid::new_v4()).unwrap_err();
+        assert!(matches!(err, TtidError::InvalidUuid));
+    }
+}
@@ -0,0 +1,662 @@
+//! Runtime mapping between numeric type ids and type names.
+//!
+//! A [`TypeRegistry`] is the dynamic counterpart of an [`IdType`](crate::IdType)
+//! enum, for services that load their type table from configuration.
+//! [`DynTtid`] is the matching dynamically typed id.
+//!
+//! Registries can be exchanged as artifacts in a compact binary format
+//! ([`TypeRegistry::to_bytes`]), and with the `json` / `toml` features as
+//! JSON or TOML documents:
+//!
+//! ```toml
+//! version = 1
+//!
+//! [[types]]
+//! id = 1
+//! name = "user"
+//! ```
+
+#[cfg(feature = "serde")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+use serde::de::{DeserializeSeed, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use short_uuid::ShortUuid;
+
+use crate::{
+    ParseTtidError, RawTtid, RegistryConflict, RegistryError, RegistryFormatError, TtidError,
+    UNTYPED_TYPE_ID, validate_type_name,
+};
+
+/// Version of the registry import/export formats.
+pub const REGISTRY_FORMAT_VERSION: u32 = 1;
+
+/// Magic prefix of the binary registry format.
+const BINARY_MAGIC: &[u8; 4] = b"TTRG";
+
+/// Validated table of `(type_id, type_name)` entries.
+///
+/// Ids and names are unique, ids are not [`UNTYPED_TYPE_ID`], and names pass
+/// [`validate_type_name`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeRegistry {
+    /// Entries sorted by type id.
+    entries: Vec<(u16, String)>,
+    by_name: HashMap<String, u16>,
+}
+
+impl TypeRegistry {
+    /// Build a registry from `(type_id, type_name)` entries.
+    ///
+    /// All entries are checked before failing, so the returned error lists
+    /// every duplicate id, duplicate name and invalid name at once.
+    pub fn new<I, S>(entries: I) -> Result<Self, RegistryError>
+    where
+        I: IntoIterator<Item = (u16, S)>,
+        S: Into<String>,
+    {
+        let mut conflicts = Vec::new();
+        let mut by_id: HashMap<u16, String> = HashMap::new();
+        let mut by_name: HashMap<String, u16> = HashMap::new();
+
+        for (id, name) in entries {
+            let name = name.into();
+
+            if id == UNTYPED_TYPE_ID {
+                conflicts.push(RegistryConflict::ReservedId { name: name.clone() });
+            }
+            if let Err(error) = validate_type_name(&name) {
+                conflicts.push(RegistryConflict::InvalidName {
+                    id,
+                    name: name.clone(),
+                    error,
+                });
+            }
+            if let Some(existing) = by_id.get(&id) {
+                conflicts.push(RegistryConflict::DuplicateId {
+                    id,
+                    names: (existing.clone(), name.clone()),
+                });
+            }
+            if let Some(&existing) = by_name.get(&name) {
+                conflicts.push(RegistryConflict::DuplicateName {
+                    name: name.clone(),
+                    ids: (existing, id),
+                });
+            }
+
+            by_id.entry(id).or_insert_with(|| name.clone());
+            by_name.entry(name).or_insert(id);
+        }
+
+        if !conflicts.is_empty() {
+            return Err(RegistryError { conflicts });
+        }
+
+        let mut entries: Vec<_> = by_id.into_iter().collect();
+        entries.sort_unstable_by_key(|(id, _)| *id);
+        Ok(Self { entries, by_name })
+    }
+
+    /// Combine two registries into one.
+    ///
+    /// Entries present in both with the same id and name are merged. Any entry
+    /// that disagrees on id or name is reported as a conflict.
+    pub fn merge(&self, other: &TypeRegistry) -> Result<TypeRegistry, RegistryError> {
+        let extra = other
+            .iter()
+            .filter(|(id, name)| self.name_of(*id) != Some(*name));
+        TypeRegistry::new(self.iter().chain(extra))
+    }
+
+    /// Resolve a type id to its name.
+    pub fn name_of(&self, type_id: u16) -> Option<&str> {
+        self.entries
+            .binary_search_by_key(&type_id, |(id, _)| *id)
+            .ok()
+            .map(|idx| self.entries[idx].1.as_str())
+    }
+
+    /// Resolve a type name to its id.
+    pub fn id_of(&self, type_name: &str) -> Option<u16> {
+        self.by_name.get(type_name).copied()
+    }
+
+    /// Iterate over `(type_id, type_name)` entries in type id order.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &str)> {
+        self.entries.iter().map(|(id, name)| (*id, name.as_str()))
+    }
+
+    /// Number of registered types.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the registry has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl TypeRegistry {
+    /// Encode into the compact binary format.
+    ///
+    /// Layout: magic `TTRG`, format version (`u8`), entry count (`u16`), then
+    /// per entry the type id (`u16`), name length (`u8`) and name bytes. All
+    /// integers are big-endian.
+    ///
+    /// Fails with [`RegistryFormatError::TooLarge`] if the entry count or a
+    /// name length exceeds its field.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RegistryFormatError> {
+        let count = u16::try_from(self.entries.len()).map_err(|_| RegistryFormatError::TooLarge)?;
+        let mut out = Vec::with_capacity(7 + self.entries.len() * 8);
+        out.extend_from_slice(BINARY_MAGIC);
+        out.push(REGISTRY_FORMAT_VERSION as u8);
+        out.extend_from_slice(&count.to_be_bytes());
+        for (id, name) in &self.entries {
+            let len = u8::try_from(name.len()).map_err(|_| RegistryFormatError::TooLarge)?;
+            out.extend_from_slice(&id.to_be_bytes());
+            out.push(len);
+            out.extend_from_slice(name.as_bytes());
+        }
+        Ok(out)
+    }
+
+    /// Decode from the compact binary format produced by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RegistryFormatError> {
+        let mut input = bytes;
+        let mut take = |len: usize| -> Result<&[u8], RegistryFormatError> {
+            if input.len() < len {
+                return Err(RegistryFormatError::Truncated);
+            }
+            let (head, rest) = input.split_at(len);
+            input = rest;
+            Ok(head)
+        };
+
+        if take(BINARY_MAGIC.len())? != BINARY_MAGIC {
+            return Err(RegistryFormatError::InvalidMagic);
+        }
+        let version = take(1)?[0] as u32;
+        if version != REGISTRY_FORMAT_VERSION {
+            return Err(RegistryFormatError::UnsupportedVersion(version));
+        }
+
+        let count = u16::from_be_bytes(take(2)?.try_into().expect("two bytes"));
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let id = u16::from_be_bytes(take(2)?.try_into().expect("two bytes"));
+            let len = take(1)?[0] as usize;
+            let name =
+                std::str::from_utf8(take(len)?).map_err(|_| RegistryFormatError::InvalidUtf8)?;
+            entries.push((id, name.to_owned()));
+        }
+
+        if !input.is_empty() {
+            return Err(RegistryFormatError::TrailingData);
+        }
+
+        Ok(TypeRegistry::new(entries)?)
+    }
+}
+
+impl TypeRegistry {
+    /// Parse a `<type-name>_<shortuuid>` string, resolving the type name
+    /// through this registry.
+    ///
+    /// Follows the same rules as [`Ttid::from_str`](crate::Ttid).
+    pub fn parse(&self, s: &str) -> Result<DynTtid, ParseTtidError> {
+        let (type_name, short) = s.split_once('_').ok_or(ParseTtidError::InvalidFormat)?;
+
+        let type_id = self
+            .id_of(type_name)
+            .ok_or(ParseTtidError::UnknownTypeName)?;
+        let short = ShortUuid::parse_str(short).map_err(|_| ParseTtidError::InvalidShortUuid)?;
+
+        let raw = RawTtid::from_uuid(short.to_uuid())?;
+        if raw.type_id() != type_id {
+            return Err(ParseTtidError::TypeMismatch);
+        }
+
+        Ok(DynTtid { raw })
+    }
+
+    /// Format as `<type-name>_<shortuuid>`.
+    ///
+    /// Returns `None` if the id's type is not known by this registry.
+    pub fn format(&self, id: &DynTtid) -> Option<String> {
+        let type_name = self.name_of(id.type_id())?;
+        Some(format!(
+            "{type_name}_{}",
+            ShortUuid::from_uuid(&id.raw.as_uuid())
+        ))
+    }
+}
+
+/// TTID whose type is resolved through a [`TypeRegistry`] at runtime.
+///
+/// Use [`TypeRegistry::parse`] and [`TypeRegistry::format`] for the string
+/// form. With the `serde` feature, (de)serialization resolves type names
+/// through the registry set by `with_registry`, or through an explicit
+/// `DynTtidSeed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DynTtid {
+    raw: RawTtid,
+}
+
+impl DynTtid {
+    /// Wrap a raw TTID whose type id is known by `registry`.
+    pub fn from_raw(raw: RawTtid, registry: &TypeRegistry) -> Result<Self, TtidError> {
+        let type_id = raw.type_id();
+        if registry.name_of(type_id).is_none() {
+            return Err(TtidError::UnknownTypeId(type_id));
+        }
+        Ok(Self { raw })
+    }
+
+    /// Return the untyped TTID.
+    pub fn as_raw(&self) -> RawTtid {
+        self.raw
+    }
+
+    /// Extract numeric type id.
+    pub fn type_id(&self) -> u16 {
+        self.raw.type_id()
+    }
+
+    /// Resolve the type name through `registry`.
+    pub fn type_name<'r>(&self, registry: &'r TypeRegistry) -> Option<&'r str> {
+        registry.name_of(self.type_id())
+    }
+}
+
+impl From<DynTtid> for RawTtid {
+    fn from(value: DynTtid) -> Self {
+        value.raw
+    }
+}
+
+#[cfg(feature = "serde")]
+thread_local! {
+    static THREAD_REGISTRY: RefCell<Option<Arc<TypeRegistry>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `registry` as the registry used to (de)serialize [`DynTtid`]
+/// values on the current thread.
+///
+/// Calls can be nested, the previous registry is restored afterwards.
+#[cfg(feature = "serde")]
+pub fn with_registry<R>(registry: Arc<TypeRegistry>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Arc<TypeRegistry>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            THREAD_REGISTRY.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    let previous = THREAD_REGISTRY.with(|current| current.borrow_mut().replace(registry));
+    let _restore = Restore(previous);
+    f()
+}
+
+#[cfg(feature = "serde")]
+fn thread_registry() -> Option<Arc<TypeRegistry>> {
+    THREAD_REGISTRY.with(|current| current.borrow().clone())
+}
+
+#[cfg(feature = "serde")]
+const NO_THREAD_REGISTRY: &str = "no TTID type registry set, use ttid::registry::with_registry";
+
+/// Serializes as `<type-name>_<shortuuid>`, using the registry set by
+/// [`with_registry`].
+#[cfg(feature = "serde")]
+impl Serialize for DynTtid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        let registry = thread_registry().ok_or_else(|| S::Error::custom(NO_THREAD_REGISTRY))?;
+        let text = registry
+            .format(self)
+            .ok_or_else(|| S::Error::custom(TtidError::UnknownTypeId(self.type_id())))?;
+        serializer.serialize_str(&text)
+    }
+}
+
+/// Deserializes from `<type-name>_<shortuuid>`, using the registry set by
+/// [`with_registry`].
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DynTtid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let registry = thread_registry().ok_or_else(|| D::Error::custom(NO_THREAD_REGISTRY))?;
+        DynTtidSeed(&registry).deserialize(deserializer)
+    }
+}
+
+/// [`DeserializeSeed`] parsing a [`DynTtid`] with an explicit registry.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug)]
+pub struct DynTtidSeed<'r>(pub &'r TypeRegistry);
+
+#[cfg(feature = "serde")]
+impl<'de> DeserializeSeed<'de> for DynTtidSeed<'_> {
+    type Value = DynTtid;
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<DynTtid, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Visitor<'_> for DynTtidSeed<'_> {
+    type Value = DynTtid;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a TTID string in <type-name>_<shortuuid> format")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<DynTtid, E> {
+        self.0.parse(v).map_err(E::custom)
+    }
+}
+
+/// Serialized registry document shared by the JSON and TOML formats.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RegistryDocument {
+    version: u32,
+    types: Vec<RegistryDocumentEntry>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RegistryDocumentEntry {
+    id: u16,
+    name: String,
+}
+
+#[cfg(feature = "serde")]
+impl RegistryDocument {
+    fn from_registry(registry: &TypeRegistry) -> Self {
+        Self {
+            version: REGISTRY_FORMAT_VERSION,
+            types: registry
+                .iter()
+                .map(|(id, name)| RegistryDocumentEntry {
+                    id,
+                    name: name.to_owned(),
+                })
+                .collect(),
+        }
+    }
+
+    fn into_registry(self) -> Result<TypeRegistry, RegistryFormatError> {
+        if self.version != REGISTRY_FORMAT_VERSION {
+            return Err(RegistryFormatError::UnsupportedVersion(self.version));
+        }
+        Ok(TypeRegistry::new(
+            self.types.into_iter().map(|entry| (entry.id, entry.name)),
+        )?)
+    }
+}
+
+/// Serializes as a versioned `{ version, types: [{ id, name }] }` document.
+#[cfg(feature = "serde")]
+impl Serialize for TypeRegistry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RegistryDocument::from_registry(self).serialize(serializer)
+    }
+}
+
+/// Deserializes and validates a versioned registry document.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TypeRegistry {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RegistryDocument::deserialize(deserializer)?
+            .into_registry()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "json")]
+impl TypeRegistry {
+    /// Encode as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&RegistryDocument::from_registry(self))
+            .expect("registry document always serializes")
+    }
+
+    /// Decode and validate a JSON document.
+    pub fn from_json(s: &str) -> Result<Self, RegistryFormatError> {
+        serde_json::from_str::<RegistryDocument>(s)
+            .map_err(|err| RegistryFormatError::Syntax(err.to_string()))?
+            .into_registry()
+    }
+}
+
+#[cfg(feature = "toml")]
+impl TypeRegistry {
+    /// Encode as a TOML document.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(&RegistryDocument::from_registry(self))
+            .expect("registry document always serializes")
+    }
+
+    /// Decode and validate a TOML document.
+    pub fn from_toml(s: &str) -> Result<Self, RegistryFormatError> {
+        toml::from_str::<RegistryDocument>(s)
+            .map_err(|err| RegistryFormatError::Syntax(err.to_string()))?
+            .into_registry()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+    use crate::{Ttid, TypeNameError};
+
+    #[test]
+    fn resolves_ids_and_names() {
+        let registry = TypeRegistry::new([(2, "org"), (1, "user")]).unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.name_of(1), Some("user"));
+        assert_eq!(registry.id_of("org"), Some(2));
+        assert_eq!(registry.name_of(3), None);
+        assert_eq!(registry.id_of("session"), None);
+        assert_eq!(
+            registry.iter().collect::<Vec<_>>(),
+            vec![(1, "user"), (2, "org")]
+        );
+    }
+
+    #[test]
+    fn reports_all_conflicts() {
+        let err = TypeRegistry::new([
+            (1, "user"),
+            (1, "member"),
+            (2, "user"),
+            (3, "bad_name"),
+            (0, "placeholder"),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            err.conflicts,
+            vec![
+                RegistryConflict::DuplicateId {
+                    id: 1,
+                    names: ("user".into(), "member".into()),
+                },
+                RegistryConflict::DuplicateName {
+                    name: "user".into(),
+                    ids: (1, 2),
+                },
+                RegistryConflict::InvalidName {
+                    id: 3,
+                    name: "bad_name".into(),
+                    error: TypeNameError::InvalidCharacter { index: 3 },
+                },
+                RegistryConflict::ReservedId {
+                    name: "placeholder".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_combines_and_detects_conflicts() {
+        let base = TypeRegistry::new([(1, "user"), (2, "org")]).unwrap();
+        let plugin = TypeRegistry::new([(2, "org"), (3, "invoice")]).unwrap();
+
+        let merged = base.merge(&plugin).unwrap();
+        assert_eq!(
+            merged.iter().collect::<Vec<_>>(),
+            vec![(1, "user"), (2, "org"), (3, "invoice")]
+        );
+
+        let clashing = TypeRegistry::new([(2, "team"), (4, "user")]).unwrap();
+        let err = base.merge(&clashing).unwrap_err();
+        assert_eq!(err.conflicts.len(), 2);
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        let registry = TypeRegistry::new([(1, "user"), (777, "session")]).unwrap();
+
+        let bytes = registry.to_bytes().unwrap();
+        assert_eq!(&bytes[..5], b"TTRG\x01");
+        assert_eq!(TypeRegistry::from_bytes(&bytes).unwrap(), registry);
+
+        // Unreachable through `new`, which limits names to 16 bytes.
+        let oversized = TypeRegistry {
+            entries: vec![(1, "a".repeat(256))],
+            by_name: HashMap::new(),
+        };
+        assert_eq!(oversized.to_bytes(), Err(RegistryFormatError::TooLarge));
+    }
+
+    #[test]
+    fn binary_rejects_malformed_input() {
+        let bytes = TypeRegistry::new([(1, "user")])
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        assert_eq!(
+            TypeRegistry::from_bytes(b"NOPE\x01\x00\x00"),
+            Err(RegistryFormatError::InvalidMagic)
+        );
+        assert_eq!(
+            TypeRegistry::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(RegistryFormatError::Truncated)
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            TypeRegistry::from_bytes(&trailing),
+            Err(RegistryFormatError::TrailingData)
+        );
+
+        let mut future = bytes;
+        future[4] = 2;
+        assert_eq!(
+            TypeRegistry::from_bytes(&future),
+            Err(RegistryFormatError::UnsupportedVersion(2))
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_roundtrip() {
+        let registry = TypeRegistry::new([(1, "user"), (2, "org")]).unwrap();
+
+        let json = registry.to_json();
+        assert_eq!(TypeRegistry::from_json(&json).unwrap(), registry);
+
+        let err = TypeRegistry::from_json(
+            r#"{"version":1,"types":[{"id":1,"name":"a"},{"id":1,"name":"b"}]}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, RegistryFormatError::Registry(_)));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_roundtrip() {
+        let registry = TypeRegistry::new([(1, "user"), (2, "org")]).unwrap();
+
+        let toml = registry.to_toml();
+        assert_eq!(TypeRegistry::from_toml(&toml).unwrap(), registry);
+
+        let err = TypeRegistry::from_toml("version = 9\ntypes = []\n").unwrap_err();
+        assert_eq!(err, RegistryFormatError::UnsupportedVersion(9));
+    }
+
+    #[test]
+    fn parse_and_format_dyn_ttid() {
+        let registry = TypeRegistry::new([(1, "user"), (777, "session")]).unwrap();
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 9).unwrap();
+
+        let parsed = registry.parse(&ttid.to_string()).unwrap();
+        assert_eq!(parsed.as_raw(), RawTtid::from(ttid));
+        assert_eq!(parsed.type_name(&registry), Some("session"));
+        assert_eq!(registry.format(&parsed), Some(ttid.to_string()));
+
+        let other = TypeRegistry::new([(1, "user")]).unwrap();
+        assert_eq!(other.format(&parsed), None);
+        assert!(matches!(
+            DynTtid::from_raw(parsed.as_raw(), &other),
+            Err(TtidError::UnknownTypeId(777))
+        ));
+    }
+
+    #[test]
+    fn parse_dyn_ttid_rejects_mismatched_prefix() {
+        let registry = TypeRegistry::new([(1, "user"), (2, "org")]).unwrap();
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 9).unwrap();
+        let wrong = ttid.to_string().replacen("user_", "org_", 1);
+
+        assert_eq!(registry.parse(&wrong), Err(ParseTtidError::TypeMismatch));
+        assert_eq!(
+            registry.parse("team_abc"),
+            Err(ParseTtidError::UnknownTypeName)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dyn_ttid_serde_uses_registry() {
+        let registry = Arc::new(TypeRegistry::new([(1, "user")]).unwrap());
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 9).unwrap();
+        let id = DynTtid::from_raw(ttid.into(), &registry).unwrap();
+        let json = format!("\"{ttid}\"");
+
+        assert!(serde_json::to_string(&id).is_err());
+
+        let encoded = with_registry(registry.clone(), || serde_json::to_string(&id)).unwrap();
+        assert_eq!(encoded, json);
+
+        let decoded: DynTtid =
+            with_registry(registry.clone(), || serde_json::from_str(&json)).unwrap();
+        assert_eq!(decoded, id);
+
+        let mut de = serde_json::Deserializer::from_str(&json);
+        assert_eq!(DynTtidSeed(&registry).deserialize(&mut de).unwrap(), id);
+    }
+}
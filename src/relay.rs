@@ -0,0 +1,132 @@
+//! GraphQL Relay global object identifiers.
+
+use crate::{IdType, ParseTtidError, Ttid};
+
+/// Standard base64 alphabet, as used by the Relay reference implementation.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl<T: IdType> Ttid<T> {
+    /// Render as a Relay global id, the padded standard base64 encoding of
+    /// `<type-name>:<shortuuid>`.
+    ///
+    /// The type name is the one of the `<type-name>_<shortuuid>` string form,
+    /// so a single `node(id:)` resolver can dispatch on it.
+    pub fn to_global_id(&self) -> String {
+        let type_name = self.id_type().as_type_name();
+        encode_base64(format!("{type_name}:{}", self.short_uuid()).as_bytes())
+    }
+
+    /// Parse a global id produced by [`Self::to_global_id`].
+    ///
+    /// The type name must match the type encoded in the id, as for the
+    /// string form.
+    pub fn from_global_id(global_id: &str) -> Result<Self, ParseTtidError> {
+        let decoded = decode_base64(global_id).ok_or(ParseTtidError::InvalidFormat)?;
+        let decoded = String::from_utf8(decoded).map_err(|_| ParseTtidError::InvalidFormat)?;
+        let (type_name, short) = decoded
+            .split_once(':')
+            .filter(|(type_name, _)| !type_name.contains('_'))
+            .ok_or(ParseTtidError::InvalidFormat)?;
+        format!("{type_name}_{short}").parse()
+    }
+}
+
+/// Padded standard base64.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | u32::from(byte) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(BASE64_ALPHABET[(group >> (18 - 6 * index)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode padded standard base64, `None` if `text` is not valid.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    for (chunk_index, chunk) in text.chunks(4).enumerate() {
+        let padding = chunk.iter().rev().take_while(|&&byte| byte == b'=').count();
+        let is_last = (chunk_index + 1) * 4 == text.len();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return None;
+        }
+
+        let mut group = 0u32;
+        for (index, byte) in chunk[..4 - padding].iter().enumerate() {
+            let digit = BASE64_ALPHABET.iter().position(|c| c == byte)?;
+            group |= (digit as u32) << (18 - 6 * index);
+        }
+        for index in 0..3 - padding {
+            out.push((group >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn base64_matches_rfc_4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(encode_base64(plain.as_bytes()), encoded);
+            assert_eq!(decode_base64(encoded).unwrap(), plain.as_bytes());
+        }
+        for invalid in ["Zg=", "Zg==Zg==", "Z===", "Zm9v!A==", "Zm=v"] {
+            assert_eq!(decode_base64(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn global_id_roundtrip() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+        let global_id = id.to_global_id();
+
+        let decoded = String::from_utf8(decode_base64(&global_id).unwrap()).unwrap();
+        assert_eq!(decoded, format!("session:{}", id.short_uuid()));
+        assert_eq!(Ttid::<MyType>::from_global_id(&global_id).unwrap(), id);
+
+        let user = encode_base64(format!("user:{}", id.short_uuid()).as_bytes());
+        assert_eq!(
+            Ttid::<MyType>::from_global_id(&user),
+            Err(ParseTtidError::TypeMismatch)
+        );
+        let underscored = encode_base64(id.to_string().as_bytes());
+        assert_eq!(
+            Ttid::<MyType>::from_global_id(&underscored),
+            Err(ParseTtidError::InvalidFormat)
+        );
+        assert_eq!(
+            Ttid::<MyType>::from_global_id(&id.to_string()),
+            Err(ParseTtidError::InvalidFormat)
+        );
+    }
+}
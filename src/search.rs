@@ -0,0 +1,53 @@
+//! Binary search helpers over sorted UUID columns.
+
+use uuid::Uuid;
+
+use crate::deser::{TIMESTAMP_BITS, TIMESTAMP_MAX};
+
+/// Number of leading UUID bytes holding the big-endian timestamp.
+const TIMESTAMP_BYTES: usize = (TIMESTAMP_BITS / 8) as usize;
+
+/// Find the index of the first UUID whose embedded timestamp is at or after
+/// `timestamp_ms`.
+///
+/// `ids` must be sorted in binary UUID order. Since the TTID layout is
+/// timestamp-first, the timestamp occupies the leading 6 bytes and is compared
+/// directly without decoding. Returns `ids.len()` if every id is earlier.
+pub fn lower_bound_for_time(ids: &[Uuid], timestamp_ms: u64) -> usize {
+    if timestamp_ms > TIMESTAMP_MAX {
+        return ids.len();
+    }
+
+    let be = timestamp_ms.to_be_bytes();
+    let prefix = &be[be.len() - TIMESTAMP_BYTES..];
+    ids.partition_point(|uuid| &uuid.as_bytes()[..TIMESTAMP_BYTES] < prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ttid;
+    use crate::tests::MyType;
+
+    fn uuid(ts: u64, ty: MyType, rand: u64) -> Uuid {
+        Ttid::<MyType>::from_parts(ts, ty, rand).unwrap().as_uuid()
+    }
+
+    #[test]
+    fn lower_bound_finds_first_id_at_or_after_timestamp() {
+        let ids = [
+            uuid(1_000, MyType::Session, u64::MAX),
+            uuid(2_000, MyType::User, 0),
+            uuid(2_000, MyType::Org, 5),
+            uuid(3_000, MyType::User, 1),
+        ];
+
+        assert_eq!(lower_bound_for_time(&ids, 0), 0);
+        assert_eq!(lower_bound_for_time(&ids, 1_000), 0);
+        assert_eq!(lower_bound_for_time(&ids, 1_001), 1);
+        assert_eq!(lower_bound_for_time(&ids, 2_000), 1);
+        assert_eq!(lower_bound_for_time(&ids, 2_001), 3);
+        assert_eq!(lower_bound_for_time(&ids, 3_001), 4);
+        assert_eq!(lower_bound_for_time(&ids, TIMESTAMP_MAX + 1), 4);
+    }
+}
@@ -0,0 +1,904 @@
+//! `serde` support for [`Ttid`] and the composite id types.
+//!
+//! TTIDs serialize in their `<type-name>_<shortuuid>` string form for
+//! human-readable formats, and as the 16 raw UUID bytes for binary formats
+//! (bincode, postcard, MessagePack), like `uuid::Uuid`. Pairs always use their
+//! `<first>..<second>` string form. [`TtidSet`] and [`TtidVec`] serialize as
+//! sequences of TTID strings, or as their compact binary form.
+//!
+//! The submodules provide alternate wire forms for `Ttid<T>` fields, for use
+//! with `#[serde(with = "...")]`:
+//!
+//! - [`as_uuid`]: hyphenated UUID string for human-readable formats, 16 raw
+//!   bytes otherwise, like `uuid::Uuid`,
+//! - [`as_uuid_string`]: always the hyphenated UUID string,
+//! - [`as_bytes`]: always the 16 raw bytes,
+//! - `as_clickhouse_uuid` (with the `clickhouse` feature): ClickHouse `UUID`
+//!   columns in `clickhouse::Row` types, with an `option` submodule for
+//!   `Nullable(UUID)`,
+//! - `uuid_passthrough` (with the `uuid-serde` feature): exactly the
+//!   `Serialize`/`Deserialize` implementations of `uuid::Uuid`, for schemas
+//!   that must not change on the wire when a field becomes typed.
+//!
+//! For migrating a field between plain UUIDs and TTID strings, both of the
+//! following accept either string form, and differ in what they write:
+//!
+//! - [`accept_legacy`]: writes the TTID string, like the default form,
+//! - [`emit_legacy`]: writes the UUID, like [`as_uuid`].
+//!
+//! Both validate the TTID layout and type id for either form, so a plain UUID
+//! of a foreign type is rejected like its TTID string. `accept_legacy` is the
+//! lenient mode for APIs accepting both forms, e.g. with
+//! `#[serde(deserialize_with = "ttid::serde::accept_legacy::deserialize")]`.
+//!
+//! Roll out `accept_legacy` to consumers first, then switch producers from
+//! `emit_legacy` to the default form. `emit_legacy` serves as the reverse
+//! adapter for rollbacks.
+//!
+//! TTIDs work as map keys (`HashMap`, `BTreeMap`) in JSON and other formats
+//! serializing keys as human-readable strings. Formats that are not
+//! human-readable would write keys as bytes, which string-keyed formats
+//! reject. [`string_keys`] writes keys in the `<type-name>_<shortuuid>` form
+//! regardless of the format, with values in their own configured form.
+//! [`TtidPair`] and [`VersionedId`] always serialize as strings and work as
+//! map keys in every format.
+//!
+//! Deserialization validates the TTID layout and type id. Formats storing bytes
+//! as strings or sequences (e.g. JSON arrays) are supported, and byte fields
+//! also accept the UUID string in self-describing formats, to ease migrations.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Row {
+//!     #[serde(with = "ttid::serde::as_uuid_string")]
+//!     id: Ttid<User>,
+//! }
+//!
+//! let id = Ttid::new(User).unwrap();
+//! let json = serde_json::to_string(&Row { id }).unwrap();
+//! assert_eq!(json, format!(r#"{{"id":"{}"}}"#, id.as_uuid()));
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use ::serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use uuid::Uuid;
+
+use crate::bitemporal::VersionedId;
+use crate::outbox::OutboxRecordId;
+use crate::{IdType, Ttid, TtidPair, TtidSet, TtidVec};
+
+impl<T: IdType> Serialize for Ttid<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            as_bytes::serialize(self, serializer)
+        }
+    }
+}
+
+impl<'de, T: IdType> Deserialize<'de> for Ttid<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ParseVisitor::new(
+                "a TTID string in <type-name>_<shortuuid> format",
+            ))
+        } else {
+            as_bytes::deserialize(deserializer)
+        }
+    }
+}
+
+impl<A: IdType, B: IdType> Serialize for TtidPair<A, B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, A: IdType, B: IdType> Deserialize<'de> for TtidPair<A, B> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(ParseVisitor::new(
+            "a TTID pair string in <first>..<second> format",
+        ))
+    }
+}
+
+impl<T: IdType, V: IdType> Serialize for VersionedId<T, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: IdType, V: IdType> Deserialize<'de> for VersionedId<T, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(ParseVisitor::new(
+            "a versioned id string in <entity>@<version> format",
+        ))
+    }
+}
+
+impl<T: IdType> Serialize for OutboxRecordId<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: IdType> Deserialize<'de> for OutboxRecordId<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ttid::deserialize(deserializer).map(Self)
+    }
+}
+
+impl<T: IdType> Serialize for TtidSet<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+}
+
+impl<'de, T: IdType> Deserialize<'de> for TtidSet<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            Ok(Vec::<Ttid<T>>::deserialize(deserializer)?
+                .into_iter()
+                .collect())
+        } else {
+            let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+            Self::from_bytes(&bytes).map_err(D::Error::custom)
+        }
+    }
+}
+
+impl<T: IdType> Serialize for TtidVec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+}
+
+impl<'de, T: IdType> Deserialize<'de> for TtidVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            Vec::<Ttid<T>>::deserialize(deserializer).map(Self::from)
+        } else {
+            let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+            Self::from_bytes(&bytes).map_err(D::Error::custom)
+        }
+    }
+}
+
+/// Collects a byte string, also from formats encoding bytes as sequences.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a compact TTID batch as bytes")
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Parses a string form via `FromStr`, also from borrowed and transient
+/// strings.
+struct ParseVisitor<V> {
+    expecting: &'static str,
+    marker: PhantomData<V>,
+}
+
+impl<V> ParseVisitor<V> {
+    fn new(expecting: &'static str) -> Self {
+        Self {
+            expecting,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<V> Visitor<'_> for ParseVisitor<V>
+where
+    V: FromStr,
+    V::Err: fmt::Display,
+{
+    type Value = V;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<V, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+/// Serialize as UUID: hyphenated string for human-readable formats, raw bytes
+/// otherwise.
+pub mod as_uuid {
+    use super::*;
+
+    /// Serialize `id` as UUID.
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            super::as_uuid_string::serialize(id, serializer)
+        } else {
+            super::as_bytes::serialize(id, serializer)
+        }
+    }
+
+    /// Deserialize a TTID from a UUID string or bytes.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        if deserializer.is_human_readable() {
+            super::as_uuid_string::deserialize(deserializer)
+        } else {
+            super::as_bytes::deserialize(deserializer)
+        }
+    }
+}
+
+/// Serialize as hyphenated UUID string.
+pub mod as_uuid_string {
+    use super::*;
+
+    /// Serialize `id` as hyphenated UUID string.
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(
+            id.as_uuid()
+                .hyphenated()
+                .encode_lower(&mut Uuid::encode_buffer()),
+        )
+    }
+
+    /// Deserialize a TTID from a UUID string.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        deserializer.deserialize_str(UuidVisitor(PhantomData))
+    }
+}
+
+/// Serialize as the 16 raw UUID bytes.
+pub mod as_bytes {
+    use super::*;
+
+    /// Serialize `id` as 16 raw bytes.
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(id.as_uuid().as_bytes())
+    }
+
+    /// Deserialize a TTID from bytes, a byte sequence or a UUID string.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        deserializer.deserialize_bytes(UuidVisitor(PhantomData))
+    }
+}
+
+/// Serialize as a ClickHouse `UUID` for the `clickhouse` crate's `Row`
+/// types, using `clickhouse::serde::uuid`.
+#[cfg(feature = "clickhouse")]
+pub mod as_clickhouse_uuid {
+    use super::*;
+
+    /// Serialize `id` as ClickHouse `UUID`.
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ::clickhouse::serde::uuid::serialize(&id.as_uuid(), serializer)
+    }
+
+    /// Deserialize a TTID from a ClickHouse `UUID`.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        let uuid = ::clickhouse::serde::uuid::deserialize(deserializer)?;
+        Ttid::from_uuid(uuid).map_err(D::Error::custom)
+    }
+
+    /// Serialize `Option<Ttid<T>>` as ClickHouse `Nullable(UUID)`.
+    pub mod option {
+        use super::*;
+
+        /// Serialize `id` as ClickHouse `Nullable(UUID)`.
+        pub fn serialize<T: IdType, S: Serializer>(
+            id: &Option<Ttid<T>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            ::clickhouse::serde::uuid::option::serialize(&id.map(|id| id.as_uuid()), serializer)
+        }
+
+        /// Deserialize an optional TTID from a ClickHouse `Nullable(UUID)`.
+        pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Ttid<T>>, D::Error> {
+            ::clickhouse::serde::uuid::option::deserialize(deserializer)?
+                .map(Ttid::from_uuid)
+                .transpose()
+                .map_err(D::Error::custom)
+        }
+    }
+}
+
+/// Serialize exactly like `uuid::Uuid`, by delegating to its `serde`
+/// implementations.
+///
+/// Unlike [`as_uuid`], the wire form follows the `uuid` crate, including the
+/// string forms it accepts and its handling of binary formats. Decoded UUIDs
+/// must still hold a valid TTID of the domain.
+#[cfg(feature = "uuid-serde")]
+pub mod uuid_passthrough {
+    use super::*;
+
+    /// Serialize `id` like its `uuid::Uuid`.
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        id.as_uuid().serialize(serializer)
+    }
+
+    /// Deserialize a `uuid::Uuid` and validate it as TTID.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        Ttid::from_uuid(Uuid::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Read TTID strings and legacy plain UUID strings, write TTID strings.
+pub mod accept_legacy {
+    use super::*;
+
+    /// Serialize `id` in the default form.
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        id.serialize(serializer)
+    }
+
+    /// Deserialize a TTID from a TTID string or a UUID string.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        deserialize_either(deserializer)
+    }
+}
+
+/// Read TTID strings and legacy plain UUID strings, write UUIDs.
+pub mod emit_legacy {
+    use super::*;
+
+    /// Serialize `id` as UUID, see [`as_uuid`].
+    pub fn serialize<T: IdType, S: Serializer>(
+        id: &Ttid<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::as_uuid::serialize(id, serializer)
+    }
+
+    /// Deserialize a TTID from a TTID string or a UUID string.
+    pub fn deserialize<'de, T: IdType, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Ttid<T>, D::Error> {
+        deserialize_either(deserializer)
+    }
+}
+
+/// Serialize maps keyed by TTIDs with keys in the string form, for any format.
+///
+/// Works with every map type iterating over `(&Ttid<T>, &V)` pairs and
+/// collectable from `(Ttid<T>, V)` pairs, e.g. `HashMap` and `BTreeMap`.
+pub mod string_keys {
+    use super::*;
+
+    /// Serialize `map` with keys as `<type-name>_<shortuuid>` strings.
+    pub fn serialize<'a, T, V, M, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: IdType + 'a,
+        V: Serialize + 'a,
+        &'a M: IntoIterator<Item = (&'a Ttid<T>, &'a V)>,
+        S: Serializer,
+    {
+        serializer.collect_map(map.into_iter().map(|(key, value)| (StringKey(*key), value)))
+    }
+
+    /// Deserialize a map with keys in the `<type-name>_<shortuuid>` form.
+    pub fn deserialize<'de, T, V, M, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        T: IdType,
+        V: Deserialize<'de>,
+        M: FromIterator<(Ttid<T>, V)>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(StringKeyMapVisitor(PhantomData, PhantomData))
+    }
+}
+
+/// A TTID (de)serialized in the string form regardless of the format.
+struct StringKey<T: IdType>(Ttid<T>);
+
+impl<T: IdType> Serialize for StringKey<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de, T: IdType> Deserialize<'de> for StringKey<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_str(ParseVisitor::new(
+                "a TTID string in <type-name>_<shortuuid> format",
+            ))
+            .map(Self)
+    }
+}
+
+/// Collects a map with [`StringKey`] keys.
+struct StringKeyMapVisitor<T, V, M>(PhantomData<(T, V)>, PhantomData<fn() -> M>);
+
+impl<'de, T, V, M> Visitor<'de> for StringKeyMapVisitor<T, V, M>
+where
+    T: IdType,
+    V: Deserialize<'de>,
+    M: FromIterator<(Ttid<T>, V)>,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map keyed by TTID strings")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<M, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+        while let Some((StringKey(key), value)) = map.next_entry::<StringKey<T>, V>()? {
+            entries.push((key, value));
+        }
+        Ok(entries.into_iter().collect())
+    }
+}
+
+/// Deserialize either string form, or raw bytes in binary formats.
+fn deserialize_either<'de, T: IdType, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Ttid<T>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(EitherVisitor(PhantomData))
+    } else {
+        as_bytes::deserialize(deserializer)
+    }
+}
+
+/// Parses a TTID string, or a UUID string if there is no `_` separator.
+struct EitherVisitor<T>(PhantomData<T>);
+
+impl<T: IdType> Visitor<'_> for EitherVisitor<T> {
+    type Value = Ttid<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a TTID string or UUID string")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Ttid<T>, E> {
+        if v.contains('_') {
+            v.parse().map_err(E::custom)
+        } else {
+            UuidVisitor(PhantomData).visit_str(v)
+        }
+    }
+}
+
+/// Accepts every UUID wire form of the `with` modules.
+struct UuidVisitor<T>(PhantomData<T>);
+
+impl<T: IdType> UuidVisitor<T> {
+    fn validate<E: Error>(uuid: Uuid) -> Result<Ttid<T>, E> {
+        Ttid::from_uuid(uuid).map_err(E::custom)
+    }
+}
+
+impl<'de, T: IdType> Visitor<'de> for UuidVisitor<T> {
+    type Value = Ttid<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a TTID as UUID string or 16 bytes")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Ttid<T>, E> {
+        Self::validate(Uuid::parse_str(v).map_err(E::custom)?)
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Ttid<T>, E> {
+        let bytes = <[u8; 16]>::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))?;
+        Self::validate(Uuid::from_bytes(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Ttid<T>, A::Error> {
+        let mut bytes = [0u8; 16];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(17, &self));
+        }
+        Self::validate(Uuid::from_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitemporal::VersionedId;
+    use crate::outbox::OutboxRecordId;
+    use crate::tests::{MyType, NarrowType};
+    use crate::{Ttid, TtidPair, TtidSet, TtidVec};
+
+    #[test]
+    fn ttid_serializes_as_string_form() {
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+
+        let json = serde_json::to_string(&ttid).unwrap();
+        assert_eq!(json, format!("\"{ttid}\""));
+        assert_eq!(serde_json::from_str::<Ttid<MyType>>(&json).unwrap(), ttid);
+
+        assert!(serde_json::from_str::<Ttid<NarrowType>>(&json).is_err());
+        assert!(serde_json::from_str::<Ttid<MyType>>("42").is_err());
+
+        let record = OutboxRecordId(ttid);
+        assert_eq!(serde_json::to_string(&record).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<OutboxRecordId<MyType>>(&json).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn ttid_serializes_as_bytes_for_binary_formats() {
+        use serde_test::{Configure, Token, assert_de_tokens_error, assert_tokens};
+
+        let ttid = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 42).unwrap();
+        let bytes: &'static [u8] = Box::leak(Box::new(*ttid.as_uuid().as_bytes()));
+
+        assert_tokens(&ttid.compact(), &[Token::Bytes(bytes)]);
+        assert_de_tokens_error::<serde_test::Compact<Ttid<MyType>>>(
+            &[Token::Bytes(&[0; 4])],
+            "invalid length 4, expected a TTID as UUID string or 16 bytes",
+        );
+    }
+
+    #[test]
+    fn pair_serializes_as_string_form() {
+        let user = Ttid::<MyType>::from_parts(1_000, MyType::User, 1).unwrap();
+        let org = Ttid::<MyType>::from_parts(2_000, MyType::Org, 2).unwrap();
+        let pair = TtidPair::new(user, org);
+
+        let json = serde_json::to_string(&pair).unwrap();
+        assert_eq!(json, format!("\"{user}..{org}\""));
+        assert_eq!(
+            serde_json::from_str::<TtidPair<MyType, MyType>>(&json).unwrap(),
+            pair
+        );
+
+        let versioned = VersionedId::new(org, user);
+        let json = serde_json::to_string(&versioned).unwrap();
+        assert_eq!(json, format!("\"{org}@{user}\""));
+        assert_eq!(
+            serde_json::from_str::<VersionedId<MyType, MyType>>(&json).unwrap(),
+            versioned
+        );
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Row {
+        #[serde(with = "crate::serde::as_uuid")]
+        uuid: Ttid<MyType>,
+        #[serde(with = "crate::serde::as_uuid_string")]
+        text: Ttid<MyType>,
+        #[serde(with = "crate::serde::as_bytes")]
+        bytes: Ttid<MyType>,
+    }
+
+    #[test]
+    fn with_modules_use_alternate_wire_forms() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        let row = Row {
+            uuid: id,
+            text: id,
+            bytes: id,
+        };
+
+        let json = serde_json::to_value(&row).unwrap();
+        let uuid = id.as_uuid().to_string();
+        assert_eq!(json["uuid"], uuid.as_str());
+        assert_eq!(json["text"], uuid.as_str());
+        assert_eq!(
+            json["bytes"],
+            serde_json::to_value(id.as_uuid().as_bytes()).unwrap()
+        );
+        assert_eq!(serde_json::from_value::<Row>(json).unwrap(), row);
+
+        let migrated = serde_json::json!({ "uuid": uuid, "text": uuid, "bytes": uuid });
+        assert_eq!(serde_json::from_value::<Row>(migrated).unwrap(), row);
+    }
+
+    #[test]
+    fn with_modules_reject_non_ttid_uuids() {
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let json = serde_json::json!({ "uuid": uuid, "text": uuid, "bytes": uuid });
+        assert!(serde_json::from_value::<Row>(json).is_err());
+    }
+
+    #[test]
+    fn collections_serialize_as_strings_or_compact_bytes() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        let early = Ttid::<MyType>::from_parts(1_000, MyType::User, 1).unwrap();
+        let late = Ttid::<MyType>::from_parts(2_000, MyType::Org, 2).unwrap();
+        let set: TtidSet<MyType> = [late, early].into_iter().collect();
+        let list = TtidVec::from(vec![late, early]);
+
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, format!(r#"["{early}","{late}"]"#));
+        assert_eq!(serde_json::from_str::<TtidSet<MyType>>(&json).unwrap(), set);
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, format!(r#"["{late}","{early}"]"#));
+        assert_eq!(
+            serde_json::from_str::<TtidVec<MyType>>(&json).unwrap(),
+            list
+        );
+
+        let bytes: &'static [u8] = Box::leak(set.to_bytes().into_boxed_slice());
+        assert_tokens(&set.compact(), &[Token::Bytes(bytes)]);
+        let bytes: &'static [u8] = Box::leak(list.to_bytes().into_boxed_slice());
+        assert_tokens(&list.compact(), &[Token::Bytes(bytes)]);
+    }
+
+    #[cfg(feature = "clickhouse")]
+    #[test]
+    fn clickhouse_uuid_is_a_u64_pair() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Event {
+            #[serde(with = "crate::serde::as_clickhouse_uuid")]
+            id: Ttid<MyType>,
+            #[serde(with = "crate::serde::as_clickhouse_uuid::option")]
+            parent: Option<Ttid<MyType>>,
+        }
+
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 7).unwrap();
+        let (high, low) = id.as_uuid().as_u64_pair();
+        assert_tokens(
+            &Event { id, parent: None }.compact(),
+            &[
+                Token::Struct {
+                    name: "Event",
+                    len: 2,
+                },
+                Token::Str("id"),
+                Token::Tuple { len: 2 },
+                Token::U64(high),
+                Token::U64(low),
+                Token::TupleEnd,
+                Token::Str("parent"),
+                Token::None,
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[cfg(feature = "uuid-serde")]
+    #[test]
+    fn uuid_passthrough_matches_uuid() {
+        use uuid::Uuid;
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Row {
+            #[serde(with = "crate::serde::uuid_passthrough")]
+            id: Ttid<MyType>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Plain {
+            id: Uuid,
+        }
+
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        let json = serde_json::to_value(Row { id }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_value(Plain { id: id.as_uuid() }).unwrap()
+        );
+        assert_eq!(serde_json::from_value::<Row>(json).unwrap(), Row { id });
+
+        let simple = serde_json::json!({ "id": id.as_uuid().simple().to_string() });
+        assert_eq!(serde_json::from_value::<Row>(simple).unwrap(), Row { id });
+        let nil = serde_json::json!({ "id": Uuid::nil() });
+        assert!(serde_json::from_value::<Row>(nil).is_err());
+    }
+
+    #[test]
+    fn legacy_adapters_accept_both_forms() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Event {
+            #[serde(with = "crate::serde::accept_legacy")]
+            new: Ttid<MyType>,
+            #[serde(with = "crate::serde::emit_legacy")]
+            old: Ttid<MyType>,
+        }
+
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 3).unwrap();
+        let uuid = id.as_uuid().to_string();
+        let event = Event { new: id, old: id };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "new": id.to_string(), "old": uuid })
+        );
+
+        for (new, old) in [
+            (uuid.clone(), id.to_string()),
+            (id.to_string(), uuid.clone()),
+        ] {
+            let json = serde_json::json!({ "new": new, "old": old });
+            assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+        }
+
+        let foreign = uuid::Uuid::new_v4().to_string();
+        let json = serde_json::json!({ "new": foreign, "old": uuid });
+        assert!(serde_json::from_value::<Event>(json).is_err());
+    }
+
+    #[test]
+    fn lenient_deserialization_validates_type_of_both_forms() {
+        #[derive(serde::Deserialize)]
+        struct Request {
+            #[serde(deserialize_with = "crate::serde::accept_legacy::deserialize")]
+            id: Ttid<NarrowType>,
+        }
+
+        let session = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Session, 3).unwrap();
+        for text in [session.to_string(), session.as_uuid().to_string()] {
+            let json = serde_json::json!({ "id": text });
+            assert!(serde_json::from_value::<Request>(json).is_err());
+        }
+
+        let user = Ttid::<NarrowType>::from_parts(1_700_000_000_000, NarrowType::User, 3).unwrap();
+        for text in [user.to_string(), user.as_uuid().to_string()] {
+            let json = serde_json::json!({ "id": text });
+            assert_eq!(serde_json::from_value::<Request>(json).unwrap().id, user);
+        }
+    }
+
+    #[test]
+    fn ttids_work_as_map_keys() {
+        use std::collections::{BTreeMap, HashMap};
+
+        use serde_test::{Configure, Token, assert_tokens};
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Index {
+            #[serde(with = "crate::serde::string_keys")]
+            owners: BTreeMap<Ttid<MyType>, Ttid<MyType>>,
+        }
+
+        let org = Ttid::<MyType>::from_parts(1_000, MyType::Org, 1).unwrap();
+        let user = Ttid::<MyType>::from_parts(2_000, MyType::User, 2).unwrap();
+
+        let names = HashMap::from([(user, "ada".to_string())]);
+        let json = serde_json::to_string(&names).unwrap();
+        assert_eq!(json, format!(r#"{{"{user}":"ada"}}"#));
+        assert_eq!(
+            serde_json::from_str::<HashMap<Ttid<MyType>, String>>(&json).unwrap(),
+            names
+        );
+
+        let index = Index {
+            owners: BTreeMap::from([(org, user)]),
+        };
+        let json = serde_json::to_value(&index).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "owners": { org.to_string(): user.to_string() } })
+        );
+        assert_eq!(serde_json::from_value::<Index>(json).unwrap(), index);
+
+        // Binary formats keep values as bytes but keys as strings.
+        let key: &'static str = Box::leak(org.to_string().into_boxed_str());
+        let value: &'static [u8] = Box::leak(Box::new(*user.as_uuid().as_bytes()));
+        assert_tokens(
+            &index.compact(),
+            &[
+                Token::Struct {
+                    name: "Index",
+                    len: 1,
+                },
+                Token::Str("owners"),
+                Token::Map { len: Some(1) },
+                Token::Str(key),
+                Token::Bytes(value),
+                Token::MapEnd,
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn composite_ids_work_as_map_keys() {
+        use std::collections::HashMap;
+
+        let org = Ttid::<MyType>::from_parts(1_000, MyType::Org, 1).unwrap();
+        let user = Ttid::<MyType>::from_parts(2_000, MyType::User, 2).unwrap();
+
+        let members = HashMap::from([(TtidPair::new(org, user), 3u8)]);
+        let json = serde_json::to_value(&members).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ TtidPair::new(org, user).to_string(): 3 })
+        );
+        assert_eq!(
+            serde_json::from_value::<HashMap<TtidPair<MyType, MyType>, u8>>(json).unwrap(),
+            members
+        );
+
+        let versions = HashMap::from([(VersionedId::new(org, user), true)]);
+        let json = serde_json::to_string(&versions).unwrap();
+        assert_eq!(
+            serde_json::from_str::<HashMap<VersionedId<MyType, MyType>, bool>>(&json).unwrap(),
+            versions
+        );
+
+        let err = serde_json::from_str::<HashMap<Ttid<MyType>, u8>>(r#"{"user_0":1}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid shortuuid value"), "{err}");
+    }
+}
@@ -0,0 +1,357 @@
+//! Experimental set algebra over large TTID sets.
+//!
+//! [`TtidBitmap`] follows the layout of roaring bitmaps: ids are split into
+//! buckets by the high timestamp bits, each bucket holds its ids sorted, plus
+//! a 64-bit summary of their randomness. Set operations only merge buckets
+//! present on both sides, and intersections skip buckets whose summaries are
+//! disjoint without looking at their ids.
+//!
+//! The API may change in minor releases.
+
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::marker::PhantomData;
+
+use uuid::Uuid;
+
+use crate::{IdType, Ttid};
+
+/// Number of low timestamp bits shared within a bucket, about 65 seconds.
+const BUCKET_SHIFT: u32 = 16;
+
+/// Set of TTIDs bucketed by time, for union and intersection of sets with
+/// millions of ids, e.g. in feed and fanout systems.
+///
+/// Iteration is in binary UUID order, which is time order.
+#[derive(Clone, Debug)]
+pub struct TtidBitmap<T: IdType> {
+    buckets: BTreeMap<u64, Bucket>,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+/// Ids of one time bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Bucket {
+    /// Bit `n` is set if an id's randomness is `n` modulo 64. Randomness is
+    /// uniformly distributed, so this acts as a one-hash bloom filter.
+    summary: u64,
+    /// Sorted, unique.
+    ids: Vec<Uuid>,
+}
+
+impl Bucket {
+    fn from_ids(ids: Vec<Uuid>) -> Self {
+        Self {
+            summary: summary_of(&ids),
+            ids,
+        }
+    }
+}
+
+fn bucket_key(id: &Uuid) -> u64 {
+    let bytes = id.as_bytes();
+    let timestamp = u64::from_be_bytes([
+        0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5],
+    ]);
+    timestamp >> BUCKET_SHIFT
+}
+
+fn summary_of(ids: &[Uuid]) -> u64 {
+    ids.iter().fold(0, |mask, id| mask | summary_bit(id))
+}
+
+fn summary_bit(id: &Uuid) -> u64 {
+    // The last byte holds the low randomness bits.
+    1 << (id.as_bytes()[15] & 63)
+}
+
+impl<T: IdType> TtidBitmap<T> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self {
+            buckets: BTreeMap::new(),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Number of ids in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the set contains no ids.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert an id, returning whether it was not present yet.
+    pub fn insert(&mut self, id: Ttid<T>) -> bool {
+        let uuid = id.as_uuid();
+        let bucket = self.buckets.entry(bucket_key(&uuid)).or_default();
+        match bucket.ids.binary_search(&uuid) {
+            Ok(_) => false,
+            Err(index) => {
+                bucket.ids.insert(index, uuid);
+                bucket.summary |= summary_bit(&uuid);
+                self.len += 1;
+                true
+            }
+        }
+    }
+
+    /// Remove an id, returning whether it was present.
+    pub fn remove(&mut self, id: &Ttid<T>) -> bool {
+        let uuid = id.as_uuid();
+        let Entry::Occupied(mut entry) = self.buckets.entry(bucket_key(&uuid)) else {
+            return false;
+        };
+        let Ok(index) = entry.get().ids.binary_search(&uuid) else {
+            return false;
+        };
+
+        let bucket = entry.get_mut();
+        bucket.ids.remove(index);
+        bucket.summary = summary_of(&bucket.ids);
+        if bucket.ids.is_empty() {
+            entry.remove();
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Whether the set contains `id`.
+    pub fn contains(&self, id: &Ttid<T>) -> bool {
+        let uuid = id.as_uuid();
+        self.buckets.get(&bucket_key(&uuid)).is_some_and(|bucket| {
+            bucket.summary & summary_bit(&uuid) != 0 && bucket.ids.binary_search(&uuid).is_ok()
+        })
+    }
+
+    /// Iterate in time order.
+    pub fn iter(&self) -> impl Iterator<Item = Ttid<T>> + '_ {
+        self.buckets
+            .values()
+            .flat_map(|bucket| bucket.ids.iter())
+            .map(|uuid| Ttid::from_uuid(*uuid).expect("ids validated on insert"))
+    }
+
+    /// Ids in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut buckets = self.buckets.clone();
+        for (key, theirs) in &other.buckets {
+            match buckets.entry(*key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(theirs.clone());
+                }
+                Entry::Occupied(mut entry) => {
+                    let ours = entry.get_mut();
+                    ours.ids = merge(&ours.ids, &theirs.ids, true, true, true);
+                    ours.summary |= theirs.summary;
+                }
+            }
+        }
+        Self::from_buckets(buckets)
+    }
+
+    /// Ids in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (small, large) = if self.buckets.len() <= other.buckets.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let buckets = small
+            .buckets
+            .iter()
+            .filter_map(|(key, ours)| {
+                let theirs = large.buckets.get(key)?;
+                if ours.summary & theirs.summary == 0 {
+                    return None;
+                }
+                let ids = merge(&ours.ids, &theirs.ids, false, true, false);
+                (!ids.is_empty()).then(|| (*key, Bucket::from_ids(ids)))
+            })
+            .collect();
+        Self::from_buckets(buckets)
+    }
+
+    /// Ids in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let buckets = self
+            .buckets
+            .iter()
+            .filter_map(|(key, ours)| match other.buckets.get(key) {
+                Some(theirs) if ours.summary & theirs.summary != 0 => {
+                    let ids = merge(&ours.ids, &theirs.ids, true, false, false);
+                    (!ids.is_empty()).then(|| (*key, Bucket::from_ids(ids)))
+                }
+                _ => Some((*key, ours.clone())),
+            })
+            .collect();
+        Self::from_buckets(buckets)
+    }
+
+    fn from_buckets(buckets: BTreeMap<u64, Bucket>) -> Self {
+        Self {
+            len: buckets.values().map(|bucket| bucket.ids.len()).sum(),
+            buckets,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Merge two sorted, unique lists, keeping ids only in `a`, in both, and only
+/// in `b` as selected.
+fn merge(a: &[Uuid], b: &[Uuid], only_a: bool, both: bool, only_b: bool) -> Vec<Uuid> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => {
+                if only_a {
+                    out.push(a[i]);
+                }
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                if only_b {
+                    out.push(b[j]);
+                }
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                if both {
+                    out.push(a[i]);
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    if only_a {
+        out.extend_from_slice(&a[i..]);
+    }
+    if only_b {
+        out.extend_from_slice(&b[j..]);
+    }
+    out
+}
+
+impl<T: IdType> Default for TtidBitmap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: IdType> PartialEq for TtidBitmap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.buckets == other.buckets
+    }
+}
+
+impl<T: IdType> Eq for TtidBitmap<T> {}
+
+impl<T: IdType> FromIterator<Ttid<T>> for TtidBitmap<T> {
+    fn from_iter<I: IntoIterator<Item = Ttid<T>>>(iter: I) -> Self {
+        let mut grouped: BTreeMap<u64, Vec<Uuid>> = BTreeMap::new();
+        for id in iter {
+            let uuid = id.as_uuid();
+            grouped.entry(bucket_key(&uuid)).or_default().push(uuid);
+        }
+
+        let buckets = grouped
+            .into_iter()
+            .map(|(key, mut ids)| {
+                ids.sort_unstable();
+                ids.dedup();
+                (key, Bucket::from_ids(ids))
+            })
+            .collect();
+        Self::from_buckets(buckets)
+    }
+}
+
+impl<T: IdType> Extend<Ttid<T>> for TtidBitmap<T> {
+    fn extend<I: IntoIterator<Item = Ttid<T>>>(&mut self, iter: I) {
+        let other: Self = iter.into_iter().collect();
+        *self = self.union(&other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    fn ids(range: std::ops::Range<u64>, step: u64) -> Vec<Ttid<MyType>> {
+        range
+            .step_by(step as usize)
+            .map(|n| {
+                let rand = n.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                Ttid::from_parts(1_700_000_000_000 + n * 997, MyType::User, rand).unwrap()
+            })
+            .collect()
+    }
+
+    fn sorted(mut ids: Vec<Ttid<MyType>>) -> Vec<Ttid<MyType>> {
+        ids.sort_by_key(|id| id.as_uuid());
+        ids.dedup();
+        ids
+    }
+
+    #[test]
+    fn bitmap_basic_membership() {
+        let mut set = TtidBitmap::new();
+        let [a, b] = [ids(0..1, 1)[0], ids(1..2, 1)[0]];
+
+        assert!(set.insert(b));
+        assert!(set.insert(a));
+        assert!(!set.insert(a));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![a, b]);
+
+        assert!(set.remove(&a));
+        assert!(!set.remove(&a));
+        assert!(!set.contains(&a));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn set_operations_match_naive_results() {
+        let evens = ids(0..20_000, 2);
+        let thirds = ids(0..20_000, 3);
+        let a: TtidBitmap<MyType> = evens.iter().copied().collect();
+        let b: TtidBitmap<MyType> = thirds.iter().copied().collect();
+
+        let union = a.union(&b);
+        let expected = sorted(evens.iter().chain(&thirds).copied().collect());
+        assert_eq!(union.len(), expected.len());
+        assert_eq!(union.iter().collect::<Vec<_>>(), expected);
+
+        let intersection = a.intersection(&b);
+        let expected = ids(0..20_000, 6);
+        assert_eq!(intersection.len(), expected.len());
+        assert_eq!(intersection.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(b.intersection(&a), intersection);
+
+        let difference = a.difference(&b);
+        let expected: Vec<_> = evens
+            .iter()
+            .copied()
+            .filter(|id| !thirds.contains(id))
+            .collect();
+        assert_eq!(difference.iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn extend_merges_into_existing_buckets() {
+        let mut set: TtidBitmap<MyType> = ids(0..10, 2).into_iter().collect();
+        set.extend(ids(0..10, 1));
+        assert_eq!(set, ids(0..10, 1).into_iter().collect());
+        assert_eq!(set.len(), 10);
+    }
+}
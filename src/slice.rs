@@ -0,0 +1,217 @@
+//! Utilities for batches of TTIDs.
+
+use std::collections::HashMap;
+
+use crate::{IdType, RawTtid, Ttid};
+
+/// Group raw TTIDs by their numeric type id.
+///
+/// Input order is preserved within each group.
+pub fn partition_by_type(ids: &[RawTtid]) -> HashMap<u16, Vec<RawTtid>> {
+    let mut groups: HashMap<u16, Vec<RawTtid>> = HashMap::new();
+    for id in ids {
+        groups.entry(id.type_id()).or_default().push(*id);
+    }
+    groups
+}
+
+/// Keep only the ids whose type id is known by `T`, converted to [`Ttid<T>`].
+///
+/// Input order is preserved.
+pub fn filter_type<T: IdType>(ids: &[RawTtid]) -> Vec<Ttid<T>> {
+    ids.iter().filter_map(|id| id.typed::<T>().ok()).collect()
+}
+
+/// Group TTIDs into buckets of `bucket_ms` milliseconds by embedded timestamp.
+///
+/// Returns `(bucket_start_ms, ids)` pairs ordered by bucket start. Input is
+/// expected to be sorted by timestamp; unsorted input is sorted into a copy
+/// first.
+///
+/// # Panics
+///
+/// Panics if `bucket_ms` is `0`.
+pub fn group_by_bucket<T: IdType>(ids: &[Ttid<T>], bucket_ms: u64) -> Vec<(u64, Vec<Ttid<T>>)> {
+    assert!(bucket_ms > 0, "bucket_ms must be non-zero");
+
+    let sorted;
+    let ids = if is_sorted_by_time(ids) {
+        ids
+    } else {
+        let mut copy = ids.to_vec();
+        sort_by_time(&mut copy);
+        sorted = copy;
+        &sorted
+    };
+
+    let mut groups: Vec<(u64, Vec<Ttid<T>>)> = Vec::new();
+    for id in ids {
+        let ts = id.timestamp_ms();
+        let bucket = ts - ts % bucket_ms;
+        match groups.last_mut() {
+            Some((start, group)) if *start == bucket => group.push(*id),
+            _ => groups.push((bucket, vec![*id])),
+        }
+    }
+    groups
+}
+
+/// Check whether TTIDs are ordered by embedded timestamp (non-decreasing).
+pub fn is_sorted_by_time<T: IdType>(ids: &[Ttid<T>]) -> bool {
+    ids.windows(2)
+        .all(|w| w[0].timestamp_ms() <= w[1].timestamp_ms())
+}
+
+/// Below this length [`sort_by_time`] falls back to a comparison sort.
+const RADIX_SORT_THRESHOLD: usize = 256;
+
+/// Sort TTIDs by time, then type id, then randomness.
+///
+/// This is the binary UUID order, which is timestamp-first. Large inputs are
+/// sorted with an LSD radix sort over the fixed-width 16-byte big-endian key,
+/// skipping byte positions shared by all ids (e.g. high timestamp bytes and the
+/// version/variant bits).
+pub fn sort_by_time<T: IdType>(ids: &mut [Ttid<T>]) {
+    if ids.len() < RADIX_SORT_THRESHOLD {
+        ids.sort_unstable_by_key(|id| id.as_uuid());
+        return;
+    }
+
+    let len = ids.len();
+    let mut scratch = ids.to_vec();
+    let mut in_scratch = false;
+
+    for byte in (0..16).rev() {
+        let key = |id: &Ttid<T>| id.as_uuid().as_bytes()[byte] as usize;
+
+        let mut counts = [0usize; 256];
+        let src = if in_scratch { &scratch[..] } else { &ids[..] };
+        for id in src {
+            counts[key(id)] += 1;
+        }
+        if counts.contains(&len) {
+            continue;
+        }
+
+        let mut offsets = [0usize; 256];
+        let mut sum = 0;
+        for (offset, count) in offsets.iter_mut().zip(counts) {
+            *offset = sum;
+            sum += count;
+        }
+
+        let (src, dst) = if in_scratch {
+            (&scratch[..], &mut ids[..])
+        } else {
+            (&ids[..], &mut scratch[..])
+        };
+        for id in src {
+            let bucket = key(id);
+            dst[offsets[bucket]] = *id;
+            offsets[bucket] += 1;
+        }
+        in_scratch = !in_scratch;
+    }
+
+    if in_scratch {
+        ids.copy_from_slice(&scratch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    fn raw(ts: u64, ty: MyType, rand: u64) -> RawTtid {
+        Ttid::<MyType>::from_parts(ts, ty, rand).unwrap().into()
+    }
+
+    #[test]
+    fn partition_groups_by_type_id_in_order() {
+        let ids = [
+            raw(1, MyType::User, 1),
+            raw(2, MyType::Org, 2),
+            raw(3, MyType::User, 3),
+            raw(4, MyType::Session, 4),
+        ];
+
+        let groups = partition_by_type(&ids);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&1], vec![ids[0], ids[2]]);
+        assert_eq!(groups[&2], vec![ids[1]]);
+        assert_eq!(groups[&777], vec![ids[3]]);
+    }
+
+    #[test]
+    fn filter_type_keeps_ids_known_by_domain() {
+        let ids = [
+            raw(1, MyType::User, 1),
+            raw(2, MyType::Session, 2),
+            raw(3, MyType::User, 3),
+        ];
+
+        let users = filter_type::<NarrowType>(&ids);
+
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].as_uuid(), ids[0].as_uuid());
+        assert_eq!(users[1].as_uuid(), ids[2].as_uuid());
+    }
+
+    #[test]
+    fn group_by_bucket_splits_sorted_input() {
+        let ids = [
+            Ttid::<MyType>::from_parts(1_000, MyType::User, 1).unwrap(),
+            Ttid::<MyType>::from_parts(1_500, MyType::Org, 2).unwrap(),
+            Ttid::<MyType>::from_parts(2_000, MyType::User, 3).unwrap(),
+            Ttid::<MyType>::from_parts(4_999, MyType::User, 4).unwrap(),
+        ];
+
+        let groups = group_by_bucket(&ids, 1_000);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0], (1_000, vec![ids[0], ids[1]]));
+        assert_eq!(groups[1], (2_000, vec![ids[2]]));
+        assert_eq!(groups[2], (4_000, vec![ids[3]]));
+    }
+
+    #[test]
+    fn group_by_bucket_sorts_unsorted_input() {
+        let late = Ttid::<MyType>::from_parts(3_000, MyType::User, 1).unwrap();
+        let early = Ttid::<MyType>::from_parts(1_000, MyType::User, 2).unwrap();
+
+        let groups = group_by_bucket(&[late, early], 1_000);
+
+        assert_eq!(groups, vec![(1_000, vec![early]), (3_000, vec![late])]);
+    }
+
+    #[test]
+    fn sort_by_time_matches_uuid_order() {
+        let mut state = 0x1234_5678_9abc_def0u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            state
+        };
+
+        let types = [MyType::User, MyType::Org, MyType::Session];
+        let mut ids: Vec<_> = (0..1_000)
+            .map(|_| {
+                let ts = 1_700_000_000_000 + next() % 10_000;
+                let ty = types[(next() % 3) as usize];
+                Ttid::<MyType>::from_parts(ts, ty, next()).unwrap()
+            })
+            .collect();
+        assert!(!is_sorted_by_time(&ids));
+
+        let mut expected = ids.clone();
+        expected.sort_by_key(|id| id.as_uuid());
+
+        sort_by_time(&mut ids);
+
+        assert_eq!(ids, expected);
+        assert!(is_sorted_by_time(&ids));
+    }
+}
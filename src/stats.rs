@@ -0,0 +1,269 @@
+//! Lightweight per-type statistics over observed ids.
+//!
+//! A [`Collector`] is fed ids, e.g. as they are generated or parsed at a
+//! service boundary, and summarizes them per type from their embedded
+//! timestamps: how many were seen, the time range they span, and how many
+//! were created per second. With the `serde` feature the [`Report`] can be
+//! exported as is, e.g. from a debug endpoint.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! use ttid::stats::Collector;
+//!
+//! let mut collector = Collector::new();
+//! collector.record(Ttid::from_parts(1_000, User, 1).unwrap());
+//! collector.record(Ttid::from_parts(1_500, User, 2).unwrap());
+//!
+//! let report = collector.report();
+//! assert_eq!(report.types[0].count, 2);
+//! assert_eq!(report.types[0].rate.max, 2);
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::{IdType, Ttid};
+
+/// Accumulates per-type statistics of observed ids, see the
+/// [module docs](self).
+///
+/// Memory grows with the number of distinct seconds the observed ids were
+/// created in, per type.
+#[derive(Clone, Debug)]
+pub struct Collector<T: IdType> {
+    types: BTreeMap<u16, TypeCounter<T>>,
+}
+
+/// Running counters of one type.
+#[derive(Clone, Debug)]
+struct TypeCounter<T> {
+    id_type: T,
+    min_timestamp_ms: u64,
+    max_timestamp_ms: u64,
+    /// Number of ids per second since the epoch.
+    per_second: BTreeMap<u64, u64>,
+}
+
+impl<T: IdType> Collector<T> {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self {
+            types: BTreeMap::new(),
+        }
+    }
+
+    /// Record an observed id.
+    pub fn record(&mut self, id: Ttid<T>) {
+        let timestamp_ms = id.timestamp_ms();
+        let counter = self
+            .types
+            .entry(id.type_id())
+            .or_insert_with(|| TypeCounter {
+                id_type: id.id_type(),
+                min_timestamp_ms: timestamp_ms,
+                max_timestamp_ms: timestamp_ms,
+                per_second: BTreeMap::new(),
+            });
+        counter.min_timestamp_ms = counter.min_timestamp_ms.min(timestamp_ms);
+        counter.max_timestamp_ms = counter.max_timestamp_ms.max(timestamp_ms);
+        *counter.per_second.entry(timestamp_ms / 1000).or_default() += 1;
+    }
+
+    /// Total number of recorded ids.
+    pub fn len(&self) -> u64 {
+        self.types
+            .values()
+            .flat_map(|counter| counter.per_second.values())
+            .sum()
+    }
+
+    /// Whether no id was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Forget all recorded ids.
+    pub fn clear(&mut self) {
+        self.types.clear();
+    }
+
+    /// Summarize the recorded ids, ordered by type id.
+    pub fn report(&self) -> Report {
+        let types = self
+            .types
+            .iter()
+            .map(|(&type_id, counter)| TypeStats {
+                type_name: counter.id_type.as_type_name(),
+                type_id,
+                count: counter.per_second.values().sum(),
+                min_timestamp_ms: counter.min_timestamp_ms,
+                max_timestamp_ms: counter.max_timestamp_ms,
+                rate: RatePercentiles::of(counter),
+            })
+            .collect();
+        Report { types }
+    }
+}
+
+impl<T: IdType> Default for Collector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: IdType> Extend<Ttid<T>> for Collector<T> {
+    fn extend<I: IntoIterator<Item = Ttid<T>>>(&mut self, iter: I) {
+        for id in iter {
+            self.record(id);
+        }
+    }
+}
+
+impl<T: IdType> FromIterator<Ttid<T>> for Collector<T> {
+    fn from_iter<I: IntoIterator<Item = Ttid<T>>>(iter: I) -> Self {
+        let mut collector = Self::new();
+        collector.extend(iter);
+        collector
+    }
+}
+
+/// Snapshot of a [`Collector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Report {
+    /// Statistics per observed type, ordered by type id.
+    pub types: Vec<TypeStats>,
+}
+
+/// Statistics of the observed ids of one type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypeStats {
+    /// Name of the type.
+    pub type_name: &'static str,
+    /// Numeric id of the type.
+    pub type_id: u16,
+    /// Number of observed ids.
+    pub count: u64,
+    /// Oldest embedded timestamp, in milliseconds since the Unix epoch.
+    pub min_timestamp_ms: u64,
+    /// Newest embedded timestamp, in milliseconds since the Unix epoch.
+    pub max_timestamp_ms: u64,
+    /// Ids created per second.
+    pub rate: RatePercentiles,
+}
+
+/// Percentiles of the number of ids created per second.
+///
+/// Taken over every second from the oldest to the newest observed id of the
+/// type, seconds without ids count as zero. Uses the nearest-rank method.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RatePercentiles {
+    /// Median.
+    pub p50: u64,
+    /// 90th percentile.
+    pub p90: u64,
+    /// 99th percentile.
+    pub p99: u64,
+    /// Busiest second.
+    pub max: u64,
+}
+
+impl RatePercentiles {
+    fn of<T>(counter: &TypeCounter<T>) -> Self {
+        let mut busy: Vec<u64> = counter.per_second.values().copied().collect();
+        busy.sort_unstable();
+        let seconds = counter.max_timestamp_ms / 1000 - counter.min_timestamp_ms / 1000 + 1;
+        let idle = seconds - busy.len() as u64;
+
+        let percentile = |p: u64| {
+            let rank = (seconds * p).div_ceil(100);
+            match rank.checked_sub(idle + 1) {
+                Some(index) => busy[index as usize],
+                None => 0,
+            }
+        };
+        Self {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: busy.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    fn id(timestamp_ms: u64, ty: MyType) -> Ttid<MyType> {
+        Ttid::from_parts(timestamp_ms, ty, timestamp_ms).unwrap()
+    }
+
+    #[test]
+    fn reports_counts_and_ranges_per_type() {
+        let collector: Collector<MyType> = [
+            id(5_000, MyType::Session),
+            id(1_200, MyType::User),
+            id(3_400, MyType::User),
+            id(2_000, MyType::User),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(collector.len(), 4);
+
+        let report = collector.report();
+        let names: Vec<_> = report.types.iter().map(|stats| stats.type_name).collect();
+        assert_eq!(names, ["user", "session"]);
+
+        let user = &report.types[0];
+        assert_eq!(user.type_id, 1);
+        assert_eq!(user.count, 3);
+        assert_eq!(user.min_timestamp_ms, 1_200);
+        assert_eq!(user.max_timestamp_ms, 3_400);
+
+        let session = &report.types[1];
+        assert_eq!(session.count, 1);
+        assert_eq!(
+            session.rate,
+            RatePercentiles {
+                p50: 1,
+                p90: 1,
+                p99: 1,
+                max: 1
+            }
+        );
+    }
+
+    #[test]
+    fn rate_percentiles_include_idle_seconds() {
+        let mut collector = Collector::new();
+        // 10 seconds: one burst of 20, one second with 2, eight idle.
+        collector.extend((0..20).map(|n| id(100_000 + n, MyType::User)));
+        collector.extend((0..2).map(|n| id(109_000 + n, MyType::User)));
+
+        let rate = collector.report().types[0].rate;
+        assert_eq!(
+            rate,
+            RatePercentiles {
+                p50: 0,
+                p90: 2,
+                p99: 20,
+                max: 20
+            }
+        );
+
+        collector.clear();
+        assert!(collector.is_empty());
+        assert_eq!(collector.report(), Report { types: Vec::new() });
+    }
+}
@@ -0,0 +1,91 @@
+//! Key layouts for blob stores keyed by entity ids.
+
+use std::fmt::Write;
+
+use crate::{IdType, Ttid};
+
+/// Build an object key prefix `<prefix>/<shard>/<type-name>_<shortuuid>/`
+/// for storing blobs belonging to `id` in S3, GCS or similar stores.
+///
+/// Object stores partition by key prefix, and TTID strings of one type share
+/// their leading characters. The shard segment spreads keys over `shards`
+/// partitions: it is derived from the uniformly distributed randomness of the
+/// id and rendered as fixed-width lowercase hex, e.g. `3f` for 256 shards.
+/// It is stable, so the key can always be recomputed from the id alone.
+///
+/// Trailing `/` in `prefix` is ignored, an empty prefix yields a key starting
+/// with the shard segment.
+///
+/// # Panics
+///
+/// Panics if `shards` is `0`.
+///
+/// ```
+/// # use ttid::{IdType, Ttid};
+/// # #[derive(Clone, Copy)]
+/// # struct User;
+/// # impl IdType for User {
+/// #     fn to_type_id(self) -> u16 { 1 }
+/// #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+/// #     fn as_type_name(self) -> &'static str { "user" }
+/// #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+/// # }
+/// let id = Ttid::from_parts(1_700_000_000_000, User, 0x3f).unwrap();
+/// let key = ttid::storage::object_key(&id, "avatars", 256);
+/// assert_eq!(key, format!("avatars/3f/{id}/"));
+/// ```
+pub fn object_key<T: IdType>(id: &Ttid<T>, prefix: &str, shards: u16) -> String {
+    assert!(shards > 0, "shards must be non-zero");
+
+    let shard = id.randomness() % u64::from(shards);
+    let width = shard_width(shards);
+
+    let mut key = String::new();
+    let prefix = prefix.trim_end_matches('/');
+    if !prefix.is_empty() {
+        key.push_str(prefix);
+        key.push('/');
+    }
+    write!(key, "{shard:0width$x}/{id}/").expect("writing to String");
+    key
+}
+
+/// Number of hex digits needed to render every shard index below `shards`.
+fn shard_width(shards: u16) -> usize {
+    let max = shards - 1;
+    let bits = u16::BITS - max.leading_zeros();
+    (bits as usize).div_ceil(4).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn object_key_layout() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::User, 0x13f).unwrap();
+
+        assert_eq!(object_key(&id, "blobs", 256), format!("blobs/3f/{id}/"));
+        assert_eq!(object_key(&id, "blobs/", 16), format!("blobs/f/{id}/"));
+        assert_eq!(object_key(&id, "a/b", 4096), format!("a/b/13f/{id}/"));
+        assert_eq!(object_key(&id, "", 1), format!("0/{id}/"));
+    }
+
+    #[test]
+    fn shard_width_covers_all_shards() {
+        assert_eq!(shard_width(1), 1);
+        assert_eq!(shard_width(16), 1);
+        assert_eq!(shard_width(17), 2);
+        assert_eq!(shard_width(256), 2);
+        assert_eq!(shard_width(257), 3);
+        assert_eq!(shard_width(u16::MAX), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "shards must be non-zero")]
+    fn object_key_rejects_zero_shards() {
+        let id = Ttid::<MyType>::from_parts(1, MyType::User, 1).unwrap();
+        object_key(&id, "blobs", 0);
+    }
+}
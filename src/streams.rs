@@ -0,0 +1,118 @@
+//! Event-time helpers for stream processors consuming TTID-keyed records.
+//!
+//! Ids embed their creation time, so a consumer can derive event-time
+//! watermarks from the keys alone:
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy)]
+//! # struct Order;
+//! # impl IdType for Order {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Order) }
+//! #     fn as_type_name(self) -> &'static str { "order" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "order").then_some(Order) }
+//! # }
+//! use ttid::streams::Watermark;
+//!
+//! let mut watermark = Watermark::new();
+//! watermark.observe(&Ttid::from_parts(10_000, Order, 1).unwrap());
+//!
+//! let straggler = Ttid::from_parts(4_000, Order, 2).unwrap();
+//! assert!(watermark.is_late(&straggler, Duration::from_secs(5)));
+//! assert!(!watermark.is_late(&straggler, Duration::from_secs(6)));
+//! ```
+
+use std::time::Duration;
+
+use crate::{IdType, Ttid};
+
+/// Event-time watermark over the ids seen by a consumer.
+///
+/// Tracks the greatest id observed. Records whose id is older than that id
+/// by more than the allowed lateness are late, e.g. to be dropped or routed
+/// to a side output after their window was emitted.
+#[derive(Clone, Copy, Debug)]
+pub struct Watermark<T: IdType> {
+    max_seen: Option<Ttid<T>>,
+}
+
+impl<T: IdType> Watermark<T> {
+    /// Watermark that has not seen any id yet.
+    pub const fn new() -> Self {
+        Self { max_seen: None }
+    }
+
+    /// Record `id`, advancing the watermark if it is the greatest id so far.
+    ///
+    /// Returns whether the watermark advanced.
+    pub fn observe(&mut self, id: &Ttid<T>) -> bool {
+        let advances = self
+            .max_seen
+            .is_none_or(|max_seen| id.as_uuid() > max_seen.as_uuid());
+        if advances {
+            self.max_seen = Some(*id);
+        }
+        advances
+    }
+
+    /// The greatest id observed.
+    pub fn max_seen(&self) -> Option<Ttid<T>> {
+        self.max_seen
+    }
+
+    /// Event time in milliseconds below which records are late, the
+    /// timestamp of the greatest id minus `allowed_lateness`.
+    ///
+    /// `None` before the first observation.
+    pub fn watermark_ms(&self, allowed_lateness: Duration) -> Option<u64> {
+        let lateness_ms = u64::try_from(allowed_lateness.as_millis()).unwrap_or(u64::MAX);
+        self.max_seen
+            .map(|max_seen| max_seen.timestamp_ms().saturating_sub(lateness_ms))
+    }
+
+    /// Whether `id` is older than the greatest id observed by more than
+    /// `allowed_lateness`.
+    ///
+    /// Nothing is late before the first observation.
+    pub fn is_late(&self, id: &Ttid<T>, allowed_lateness: Duration) -> bool {
+        self.watermark_ms(allowed_lateness)
+            .is_some_and(|watermark_ms| id.timestamp_ms() < watermark_ms)
+    }
+}
+
+impl<T: IdType> Default for Watermark<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    fn id(timestamp_ms: u64, randomness: u64) -> Ttid<MyType> {
+        Ttid::from_parts(timestamp_ms, MyType::Session, randomness).unwrap()
+    }
+
+    #[test]
+    fn tracks_the_greatest_id() {
+        let mut watermark = Watermark::default();
+        let lateness = Duration::from_millis(100);
+        assert!(!watermark.is_late(&id(0, 0), lateness));
+        assert_eq!(watermark.watermark_ms(lateness), None);
+
+        assert!(watermark.observe(&id(1_000, 5)));
+        assert!(!watermark.observe(&id(900, 9)));
+        assert!(!watermark.observe(&id(1_000, 5)));
+        assert!(watermark.observe(&id(1_000, 6)));
+        assert_eq!(watermark.max_seen(), Some(id(1_000, 6)));
+        assert_eq!(watermark.watermark_ms(lateness), Some(900));
+
+        assert!(!watermark.is_late(&id(900, 0), lateness));
+        assert!(watermark.is_late(&id(899, 0), lateness));
+        assert!(!watermark.is_late(&id(0, 0), Duration::MAX));
+    }
+}
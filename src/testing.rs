@@ -0,0 +1,181 @@
+//! Utilities for tests of code creating or inspecting TTIDs.
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy)]
+//! # struct Order;
+//! # impl IdType for Order {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Order) }
+//! #     fn as_type_name(self) -> &'static str { "order" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "order").then_some(Order) }
+//! # }
+//! use std::time::Duration;
+//! use ttid::generator::TtidGenerator;
+//! use ttid::testing::{MockClock, timestamp_ms, ttid_at};
+//!
+//! let old = ttid_at(Order, "2024-01-01T00:00:00Z");
+//! assert_eq!(old.timestamp_ms(), 1_704_067_200_000);
+//!
+//! let clock = MockClock::new(timestamp_ms("2024-06-01T12:00:00+02:00"));
+//! let ids = TtidGenerator::with_clock(&clock);
+//! clock.advance(Duration::from_secs(60));
+//! assert_eq!(
+//!     ids.generate(Order).unwrap().timestamp_ms(),
+//!     timestamp_ms("2024-06-01T10:01:00Z")
+//! );
+//! ```
+
+use crate::{IdType, TIMESTAMP_MAX, Ttid, random_bits};
+
+/// A clock that only moves when told to, for generators under test.
+pub use crate::generator::ManualClock as MockClock;
+
+/// Assert the codec invariants for any 128-bit `payload`; only the low 122
+/// bits are used.
+///
+/// Panics if encoding does not produce a UUIDv8 with the RFC variant, or if
+/// decoding does not restore the payload.
+///
+/// ```
+/// ttid::testing::codec_invariants(0x3ff_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+/// ```
+pub use crate::deser::codec_invariants;
+
+/// Id of type `ty` created at `timestamp`, an RFC 3339 date-time such as
+/// `2024-01-01T00:00:00Z`, with fresh randomness.
+///
+/// Panics if `timestamp` is invalid or outside the 48-bit range.
+pub fn ttid_at<T: IdType>(ty: T, timestamp: &str) -> Ttid<T> {
+    Ttid::from_parts(timestamp_ms(timestamp), ty, random_bits()).expect("ttid_at: invalid type")
+}
+
+/// Millisecond Unix timestamp of an RFC 3339 date-time, e.g.
+/// `2024-01-01T00:00:00Z` or `2024-01-01T01:00:00.250+01:00`.
+///
+/// Fractions beyond milliseconds are truncated. Panics if `timestamp` is
+/// invalid or outside the 48-bit range.
+pub fn timestamp_ms(timestamp: &str) -> u64 {
+    parse_rfc3339_ms(timestamp)
+        .filter(|&ms| ms <= TIMESTAMP_MAX)
+        .unwrap_or_else(|| panic!("invalid timestamp {timestamp:?}"))
+}
+
+fn parse_rfc3339_ms(s: &str) -> Option<u64> {
+    let s = s.as_bytes();
+    let digits = |range: std::ops::Range<usize>| -> Option<i64> {
+        let part = s.get(range)?;
+        part.iter().all(u8::is_ascii_digit).then(|| {
+            part.iter()
+                .fold(0, |acc, digit| acc * 10 + i64::from(digit - b'0'))
+        })
+    };
+    let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+    if !separators.iter().all(|&(at, sep)| s.get(at) == Some(&sep))
+        || !matches!(s.get(10), Some(b'T' | b't' | b' '))
+    {
+        return None;
+    }
+    let (year, month, day) = (digits(0..4)?, digits(5..7)?, digits(8..10)?);
+    let (hour, minute, second) = (digits(11..13)?, digits(14..16)?, digits(17..19)?);
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let mut rest = &s[19..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        let len = fraction.iter().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        millis = fraction[..len.min(3)]
+            .iter()
+            .chain(std::iter::repeat(&b'0'))
+            .take(3)
+            .fold(0, |acc, digit| acc * 10 + i64::from(digit - b'0'));
+        rest = &fraction[len..];
+    }
+    let offset_minutes = match rest {
+        b"Z" | b"z" => 0,
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let field = |a: u8, b: u8| {
+                (a.is_ascii_digit() && b.is_ascii_digit())
+                    .then(|| i64::from(a - b'0') * 10 + i64::from(b - b'0'))
+            };
+            let (hours, minutes) = (field(*h1, *h2)?, field(*m1, *m2)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 60 + minutes;
+            if *sign == b'-' { -offset } else { offset }
+        }
+        _ => return None,
+    };
+
+    let seconds = days_from_civil(year, month, day) * 86_400
+        + (hour * 60 + minute - offset_minutes) * 60
+        + second;
+    u64::try_from(seconds * 1_000 + millis).ok()
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn parses_rfc3339_timestamps() {
+        assert_eq!(timestamp_ms("1970-01-01T00:00:00Z"), 0);
+        assert_eq!(timestamp_ms("2024-01-01T00:00:00Z"), 1_704_067_200_000);
+        assert_eq!(timestamp_ms("2024-02-29T12:30:45.5Z"), 1_709_209_845_500);
+        assert_eq!(
+            timestamp_ms("2024-01-01T01:00:00.123456+01:00"),
+            1_704_067_200_123
+        );
+        assert_eq!(timestamp_ms("2023-12-31T19:00:00-05:00"), 1_704_067_200_000);
+
+        for invalid in [
+            "2023-02-29T00:00:00Z",
+            "2024-01-01T24:00:00Z",
+            "2024-01-01T00:00:00",
+            "2024-01-01 00:00:00+0100",
+            "2024-01-01T00:00:00.Z",
+            "1969-12-31T23:59:59Z",
+        ] {
+            assert_eq!(parse_rfc3339_ms(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn creates_ids_at_fixed_times() {
+        let id = ttid_at(MyType::Org, "2024-01-01T00:00:00Z");
+        assert_eq!(id.timestamp_ms(), 1_704_067_200_000);
+        assert_eq!(id.id_type(), MyType::Org);
+    }
+}
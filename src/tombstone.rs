@@ -0,0 +1,100 @@
+//! Tombstone ids, for storage engines that record deletions as sibling keys.
+//!
+//! The tombstone of an entity id keeps its timestamp and type, so it sorts
+//! into the same time range, and is derived deterministically: the highest
+//! randomness bit, [`TOMBSTONE_FLAG`], is set and the remaining randomness
+//! bits, including any [node id](crate::generator::NodeId) or revision
+//! lineage, are kept.
+//!
+//! The TTID layout has no spare bit, the flag is taken from the randomness.
+//! Domains adopting the convention must create live ids with the flag clear,
+//! leaving them 57 random bits, with [`Ttid::new_live`] or a generator
+//! reserving the flag, e.g.
+//! [`TtidGenerator::reserve_tombstone_flag`](crate::generator::TtidGenerator::reserve_tombstone_flag):
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # struct Doc;
+//! # impl IdType for Doc {
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(Doc) }
+//! #     fn as_type_name(self) -> &'static str { "doc" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "doc").then_some(Doc) }
+//! # }
+//! let id = Ttid::new_live(Doc).unwrap();
+//! assert!(!id.is_tombstone());
+//!
+//! let tombstone = id.tombstone().unwrap();
+//! assert!(tombstone.is_tombstone());
+//! assert_eq!(tombstone.tombstoned_entity(), Some(id));
+//! assert_eq!(tombstone.tombstone(), None);
+//! ```
+
+use crate::{IdType, RANDOM_MASK, Ttid, TtidError};
+
+/// Randomness bit flagging tombstone ids.
+pub const TOMBSTONE_FLAG: u64 = 1 << 57;
+
+/// Randomness bits of a live id under the tombstone convention.
+pub(crate) const ENTITY_MASK: u64 = RANDOM_MASK & !TOMBSTONE_FLAG;
+
+impl<T: IdType> Ttid<T> {
+    /// Create a live id of type `ty`, like [`Ttid::new`] but with
+    /// [`TOMBSTONE_FLAG`] clear.
+    pub fn new_live(ty: T) -> Result<Self, TtidError> {
+        let id = Self::new(ty)?;
+        Ok(id.with_randomness(id.randomness() & ENTITY_MASK))
+    }
+
+    /// The tombstone companion of this entity id, see the
+    /// [module docs](crate::tombstone).
+    ///
+    /// `None` if this id already has [`TOMBSTONE_FLAG`] set, i.e. is a
+    /// tombstone or a live id not created under the convention.
+    pub fn tombstone(&self) -> Option<Self> {
+        (!self.is_tombstone()).then(|| self.with_randomness(self.randomness() | TOMBSTONE_FLAG))
+    }
+
+    /// Whether [`TOMBSTONE_FLAG`] is set.
+    ///
+    /// Only meaningful in domains that create live ids with the flag clear.
+    pub fn is_tombstone(&self) -> bool {
+        self.randomness() & TOMBSTONE_FLAG != 0
+    }
+
+    /// The entity id this tombstone belongs to, `None` if this is not a
+    /// tombstone.
+    pub fn tombstoned_entity(&self) -> Option<Self> {
+        self.is_tombstone()
+            .then(|| self.with_randomness(self.randomness() & ENTITY_MASK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn tombstone_roundtrip() {
+        for randomness in [0, 42, ENTITY_MASK] {
+            let id =
+                Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, randomness).unwrap();
+            assert!(!id.is_tombstone());
+            assert_eq!(id.tombstoned_entity(), None);
+
+            let tombstone = id.tombstone().unwrap();
+            assert!(tombstone.is_tombstone());
+            assert_eq!(tombstone.timestamp_ms(), id.timestamp_ms());
+            assert_eq!(tombstone.id_type(), id.id_type());
+            assert_eq!(tombstone.randomness(), TOMBSTONE_FLAG | randomness);
+            assert_eq!(tombstone.tombstone(), None);
+            assert_eq!(tombstone.tombstoned_entity(), Some(id));
+        }
+
+        for _ in 0..64 {
+            assert!(!Ttid::new_live(MyType::User).unwrap().is_tombstone());
+        }
+    }
+}
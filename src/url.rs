@@ -0,0 +1,144 @@
+//! URL path and query components.
+
+use crate::{IdType, ParseTtidError, Ttid, TypeNameError};
+
+/// Default base58 alphabet of the `short-uuid` crate, used for the shortuuid
+/// part of TTID strings.
+pub(crate) const SHORT_UUID_ALPHABET: &[u8; 58] =
+    b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// Whether `byte` is an RFC 3986 unreserved character, which never needs
+/// percent-encoding in any URL component.
+const fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+// Every byte of a TTID string is unreserved: type names are restricted to
+// `[A-Za-z0-9-]` by `validate_type_name`, the separator is `_` and the
+// shortuuid uses the alphabet checked here.
+const _: () = {
+    let mut index = 0;
+    while index < SHORT_UUID_ALPHABET.len() {
+        assert!(is_unreserved(SHORT_UUID_ALPHABET[index]));
+        index += 1;
+    }
+    assert!(is_unreserved(b'_') && is_unreserved(b'-'));
+};
+
+impl<T: IdType> Ttid<T> {
+    /// Render for use as a URL path segment or query value.
+    ///
+    /// The result is the `<type-name>_<shortuuid>` string form and consists
+    /// of RFC 3986 unreserved characters only, so it never needs
+    /// percent-encoding and round-trips through any router or proxy
+    /// unchanged.
+    ///
+    /// Fails if the domain returns a type name failing
+    /// [`validate_type_name`](crate::validate_type_name), which violates the
+    /// [`IdType`] contract.
+    pub fn to_url_component(&self) -> Result<String, TypeNameError> {
+        self.to_key_string()
+    }
+
+    /// Parse a component produced by [`Self::to_url_component`].
+    ///
+    /// Strict: input containing anything but unreserved characters, in
+    /// particular percent-encoded input, is rejected with
+    /// [`ParseTtidError::InvalidFormat`] instead of being decoded. Routers
+    /// that decode `%` sequences before matching cannot smuggle a different
+    /// id past this check.
+    pub fn from_url_component(component: &str) -> Result<Self, ParseTtidError> {
+        if !component.bytes().all(is_unreserved) {
+            return Err(ParseTtidError::InvalidFormat);
+        }
+        component.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use short_uuid::ShortUuid;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn components_need_no_encoding() {
+        for (ty, randomness) in [
+            (MyType::User, 0),
+            (MyType::Session, 42),
+            (MyType::Max, crate::RANDOM_MASK),
+        ] {
+            let id = Ttid::<MyType>::from_parts(crate::TIMESTAMP_MAX, ty, randomness).unwrap();
+            let component = id.to_url_component().unwrap();
+            assert!(component.bytes().all(is_unreserved), "{component}");
+            assert_eq!(Ttid::<MyType>::from_url_component(&component).unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn alphabet_matches_short_uuid() {
+        assert_eq!(short_uuid::FLICKR_BASE_58.as_bytes(), SHORT_UUID_ALPHABET);
+        for (digit, expected) in SHORT_UUID_ALPHABET.iter().enumerate() {
+            let encoded = ShortUuid::from_uuid(&Uuid::from_u128(digit as u128)).to_string();
+            assert_eq!(encoded.as_bytes().last(), Some(expected), "{digit}");
+        }
+        let id = Ttid::new(MyType::Session).unwrap();
+        assert!(
+            id.short_uuid()
+                .to_string()
+                .bytes()
+                .all(|byte| SHORT_UUID_ALPHABET.contains(&byte))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_type_names() {
+        #[derive(Clone, Copy)]
+        struct Slashed;
+
+        impl IdType for Slashed {
+            fn to_type_id(self) -> u16 {
+                1
+            }
+
+            fn from_type_id(id: u16) -> Option<Self> {
+                (id == 1).then_some(Slashed)
+            }
+
+            fn as_type_name(self) -> &'static str {
+                "a/b"
+            }
+
+            fn from_type_name(name: &str) -> Option<Self> {
+                (name == "a/b").then_some(Slashed)
+            }
+        }
+
+        let id = Ttid::from_parts(1_700_000_000_000, Slashed, 1).unwrap();
+        assert_eq!(
+            id.to_url_component(),
+            Err(TypeNameError::InvalidCharacter { index: 1 })
+        );
+    }
+
+    #[test]
+    fn rejects_encoded_input() {
+        let id = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        let short = id.short_uuid().to_string();
+        for input in [
+            format!("org%5F{short}"),
+            format!("%6Frg_{short}"),
+            format!("org_{short}%20"),
+            format!("org_{short}/"),
+            format!(" org_{short}"),
+        ] {
+            assert_eq!(
+                Ttid::<MyType>::from_url_component(&input),
+                Err(ParseTtidError::InvalidFormat),
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Declarative validation of raw id strings in request structs, for
+//! [`validator`](https://docs.rs/validator) and [`garde`](https://docs.rs/garde).
+//!
+//! Structs that keep ids as strings, e.g. to report all invalid fields at
+//! once before parsing, check them with `is_type` (`validator` feature) or
+//! `garde::is_type` (`garde` feature). Both accept exactly the strings
+//! that parse as a [`Ttid<T>`].
+//!
+//! ```
+//! # use ttid::IdType;
+//! # #[derive(Clone, Copy)]
+//! # struct User;
+//! # impl IdType for User {
+//! #     const TYPE_NAMES: &'static [&'static str] = &["user"];
+//! #     fn to_type_id(self) -> u16 { 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+//! #     fn as_type_name(self) -> &'static str { "user" }
+//! #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+//! # }
+//! # #[cfg(feature = "validator")]
+//! # {
+//! use validator::Validate;
+//!
+//! #[derive(Validate)]
+//! struct Invite {
+//!     #[validate(custom(function = "ttid::validate::is_type::<User>"))]
+//!     user: String,
+//! }
+//!
+//! assert!(Invite { user: "user_1".into() }.validate().is_err());
+//! # }
+//! ```
+
+use crate::integrations::expected_prefixes;
+use crate::{IdType, Ttid};
+
+/// Description of why `value` is not an id of domain `T`, `None` if it is.
+fn rejection<T: IdType>(value: &str) -> Option<String> {
+    let error = value.parse::<Ttid<T>>().err()?;
+    Some(match expected_prefixes::<T>() {
+        Some(expected) => format!("{error}, {expected}"),
+        None => error.to_string(),
+    })
+}
+
+/// `validator` custom function accepting strings that parse as a
+/// [`Ttid<T>`].
+///
+/// Fails with the code `ttid` and a message describing the expected form.
+#[cfg(feature = "validator")]
+pub fn is_type<T: IdType>(value: &str) -> Result<(), ::validator::ValidationError> {
+    match rejection::<T>(value) {
+        None => Ok(()),
+        Some(message) => {
+            let mut error = ::validator::ValidationError::new("ttid");
+            error.message = Some(message.into());
+            Err(error)
+        }
+    }
+}
+
+/// Rules for `garde`.
+#[cfg(feature = "garde")]
+pub mod garde {
+    use crate::IdType;
+
+    /// `garde` custom rule accepting strings that parse as a
+    /// [`Ttid<T>`](crate::Ttid), usable with any context:
+    ///
+    /// ```
+    /// # use ttid::IdType;
+    /// # #[derive(Clone, Copy)]
+    /// # struct User;
+    /// # impl IdType for User {
+    /// #     fn to_type_id(self) -> u16 { 1 }
+    /// #     fn from_type_id(id: u16) -> Option<Self> { (id == 1).then_some(User) }
+    /// #     fn as_type_name(self) -> &'static str { "user" }
+    /// #     fn from_type_name(name: &str) -> Option<Self> { (name == "user").then_some(User) }
+    /// # }
+    /// use garde::Validate;
+    ///
+    /// #[derive(Validate)]
+    /// struct Invite {
+    ///     #[garde(custom(ttid::validate::garde::is_type::<User, _>))]
+    ///     user: String,
+    /// }
+    ///
+    /// assert!(Invite { user: "user_1".into() }.validate().is_err());
+    /// ```
+    pub fn is_type<T: IdType, C: ?Sized>(value: &str, _context: &C) -> ::garde::Result {
+        match super::rejection::<T>(value) {
+            None => Ok(()),
+            Some(message) => Err(::garde::Error::new(message)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MyType, NarrowType};
+
+    #[cfg(feature = "validator")]
+    #[test]
+    fn validator_accepts_ids_of_the_domain() {
+        let org = Ttid::<MyType>::from_parts(1_700_000_000_000, MyType::Org, 7).unwrap();
+        assert!(is_type::<MyType>(&org.to_string()).is_ok());
+
+        let error = is_type::<NarrowType>(&org.to_string()).unwrap_err();
+        assert_eq!(error.code, "ttid");
+        assert_eq!(error.message.as_deref(), Some("unknown TTID type name"));
+
+        let error = is_type::<MyType>("org_0").unwrap_err();
+        assert_eq!(
+            error.message.as_deref(),
+            Some(
+                "invalid shortuuid value, expected one of the prefixes \
+                 `user_`, `org_`, `session_`, `max_`"
+            )
+        );
+    }
+
+    #[cfg(feature = "garde")]
+    #[test]
+    fn garde_accepts_ids_of_the_domain() {
+        let user = Ttid::<NarrowType>::from_parts(1_700_000_000_000, NarrowType::User, 7).unwrap();
+        assert!(garde::is_type::<NarrowType, ()>(&user.to_string(), &()).is_ok());
+
+        let error = garde::is_type::<MyType, ()>("nope", &()).unwrap_err();
+        assert_eq!(
+            error.message(),
+            "invalid TTID string format, expected <type>_<shortuuid>, expected one of the \
+             prefixes `user_`, `org_`, `session_`, `max_`"
+        );
+    }
+}
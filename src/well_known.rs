@@ -0,0 +1,153 @@
+//! Standard infrastructure id types shared across projects.
+//!
+//! Type ids in [`WELL_KNOWN_TYPE_IDS`] are reserved for this domain.
+//! Application domains should not assign ids from this range, so
+//! infrastructure ids and application ids never collide when stored together.
+//!
+//! ```
+//! use ttid::Ttid;
+//! use ttid::well_known::WellKnown;
+//!
+//! let request_id = Ttid::new(WellKnown::Request).unwrap();
+//! assert!(request_id.to_string().starts_with("request_"));
+//! ```
+
+use std::ops::RangeInclusive;
+
+use crate::IdType;
+
+/// Type ids reserved for [`WellKnown`] types.
+pub const WELL_KNOWN_TYPE_IDS: RangeInclusive<u16> = 0xFF00..=0xFFFF;
+
+/// Well-known infrastructure id types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WellKnown {
+    /// Inbound request, e.g. an HTTP or RPC call.
+    Request,
+    /// Distributed trace.
+    Trace,
+    /// Background job.
+    Job,
+    /// Domain or integration event.
+    Event,
+    /// Message on a queue or bus.
+    Message,
+    /// Command sent to a handler or actor.
+    Command,
+    /// Scheduled or queued task.
+    Task,
+    /// Batch of jobs, tasks or messages.
+    Batch,
+}
+
+impl WellKnown {
+    /// All well-known types, in type id order.
+    pub const ALL: [WellKnown; 8] = [
+        Self::Request,
+        Self::Trace,
+        Self::Job,
+        Self::Event,
+        Self::Message,
+        Self::Command,
+        Self::Task,
+        Self::Batch,
+    ];
+
+    /// Whether `type_id` falls into the range reserved for well-known types.
+    pub fn is_reserved(type_id: u16) -> bool {
+        WELL_KNOWN_TYPE_IDS.contains(&type_id)
+    }
+}
+
+impl IdType for WellKnown {
+    const MAX_TYPE_NAME_LEN: usize = 7;
+    const TYPE_NAMES: &'static [&'static str] = &[
+        "request", "trace", "job", "event", "message", "command", "task", "batch",
+    ];
+
+    fn to_type_id(self) -> u16 {
+        match self {
+            Self::Request => 0xFF00,
+            Self::Trace => 0xFF01,
+            Self::Job => 0xFF02,
+            Self::Event => 0xFF03,
+            Self::Message => 0xFF04,
+            Self::Command => 0xFF05,
+            Self::Task => 0xFF06,
+            Self::Batch => 0xFF07,
+        }
+    }
+
+    fn from_type_id(id: u16) -> Option<Self> {
+        match id {
+            0xFF00 => Some(Self::Request),
+            0xFF01 => Some(Self::Trace),
+            0xFF02 => Some(Self::Job),
+            0xFF03 => Some(Self::Event),
+            0xFF04 => Some(Self::Message),
+            0xFF05 => Some(Self::Command),
+            0xFF06 => Some(Self::Task),
+            0xFF07 => Some(Self::Batch),
+            _ => None,
+        }
+    }
+
+    fn as_type_name(self) -> &'static str {
+        match self {
+            Self::Request => "request",
+            Self::Trace => "trace",
+            Self::Job => "job",
+            Self::Event => "event",
+            Self::Message => "message",
+            Self::Command => "command",
+            Self::Task => "task",
+            Self::Batch => "batch",
+        }
+    }
+
+    fn from_type_name(name: &str) -> Option<Self> {
+        match name {
+            "request" => Some(Self::Request),
+            "trace" => Some(Self::Trace),
+            "job" => Some(Self::Job),
+            "event" => Some(Self::Event),
+            "message" => Some(Self::Message),
+            "command" => Some(Self::Command),
+            "task" => Some(Self::Task),
+            "batch" => Some(Self::Batch),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ttid, validate_type_name};
+
+    #[test]
+    fn mapping_is_bijective_and_reserved() {
+        for ty in WellKnown::ALL {
+            let id = ty.to_type_id();
+            let name = ty.as_type_name();
+
+            assert!(WellKnown::is_reserved(id));
+            assert_eq!(validate_type_name(name), Ok(()));
+            assert!(name.len() <= WellKnown::MAX_TYPE_NAME_LEN);
+            assert_eq!(WellKnown::from_type_id(id), Some(ty));
+            assert_eq!(WellKnown::from_type_name(name), Some(ty));
+        }
+
+        let names: Vec<_> = WellKnown::ALL.iter().map(|ty| ty.as_type_name()).collect();
+        assert_eq!(WellKnown::TYPE_NAMES, names);
+    }
+
+    #[test]
+    fn string_roundtrip() {
+        let id = Ttid::<WellKnown>::from_parts(1_700_000_000_000, WellKnown::Job, 7).unwrap();
+        let text = id.to_string();
+
+        assert!(text.starts_with("job_"));
+        assert_eq!(text.parse::<Ttid<WellKnown>>().unwrap(), id);
+    }
+}
@@ -0,0 +1,185 @@
+//! Correlated ids for sagas and workflows.
+//!
+//! A [`CorrelationSet`] mints a root workflow id and derives the ids of its
+//! steps deterministically: a step id keeps the root's timestamp and
+//! randomness, has the step type, and holds the step number in the low
+//! [`STEP_BITS`] randomness bits, which are zero in the root. Any step id
+//! thus leads back to its root and number without a lookup:
+//!
+//! ```
+//! # use ttid::{IdType, Ttid};
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! # enum Kind { Workflow, Step }
+//! # impl IdType for Kind {
+//! #     fn to_type_id(self) -> u16 { self as u16 + 1 }
+//! #     fn from_type_id(id: u16) -> Option<Self> {
+//! #         match id { 1 => Some(Kind::Workflow), 2 => Some(Kind::Step), _ => None }
+//! #     }
+//! #     fn as_type_name(self) -> &'static str {
+//! #         match self { Kind::Workflow => "wf", Kind::Step => "step" }
+//! #     }
+//! #     fn from_type_name(name: &str) -> Option<Self> {
+//! #         match name { "wf" => Some(Kind::Workflow), "step" => Some(Kind::Step), _ => None }
+//! #     }
+//! # }
+//! use ttid::workflow::{self, CorrelationSet};
+//!
+//! let saga = CorrelationSet::new(Kind::Workflow, Kind::Step).unwrap();
+//! let reserve = saga.step(1);
+//! let charge = saga.step(2);
+//!
+//! let parsed: Ttid<Kind> = charge.to_string().parse().unwrap();
+//! assert_eq!(workflow::step_number(&parsed), 2);
+//! assert_eq!(workflow::root_of(&parsed, Kind::Workflow).unwrap(), saga.root());
+//! assert!(saga.contains(&reserve));
+//! ```
+//!
+//! Roots keep `58 - STEP_BITS` random bits.
+
+use crate::{IdType, Ttid, TtidError, UNTYPED_TYPE_ID};
+
+/// Number of low randomness bits holding the step number.
+pub const STEP_BITS: u32 = 16;
+
+/// Mask of the step number bits.
+const STEP_MASK: u64 = (1 << STEP_BITS) - 1;
+
+/// A root workflow id and the ids of its steps, see the
+/// [module docs](self).
+#[derive(Clone, Copy, Debug)]
+pub struct CorrelationSet<T: IdType> {
+    root: Ttid<T>,
+    step_type: T,
+}
+
+impl<T: IdType> CorrelationSet<T> {
+    /// Mint a new root of type `workflow_type`, with steps of type
+    /// `step_type`.
+    ///
+    /// Use distinct types, otherwise step `0` equals the root. Fails with
+    /// [`TtidError::ReservedTypeId`] if either type has the reserved type
+    /// id, like [`Ttid::new`] for the workflow type.
+    pub fn new(workflow_type: T, step_type: T) -> Result<Self, TtidError> {
+        if step_type.to_type_id() == UNTYPED_TYPE_ID {
+            return Err(TtidError::ReservedTypeId);
+        }
+        let root = Ttid::new(workflow_type)?;
+        Ok(Self {
+            root: root.with_randomness(root.randomness() & !STEP_MASK),
+            step_type,
+        })
+    }
+
+    /// Resume the set of an existing root, e.g. one loaded from storage.
+    ///
+    /// `None` if `root` was not minted by [`Self::new`], i.e. has step bits
+    /// set, or `step_type` has the reserved type id.
+    pub fn from_root(root: Ttid<T>, step_type: T) -> Option<Self> {
+        (step_number(&root) == 0 && root.with_type(step_type).is_ok())
+            .then_some(Self { root, step_type })
+    }
+
+    /// The root workflow id.
+    pub fn root(&self) -> Ttid<T> {
+        self.root
+    }
+
+    /// The id of step `n`, the same for every call.
+    pub fn step(&self, n: u16) -> Ttid<T> {
+        let step = self
+            .root
+            .with_type(self.step_type)
+            .expect("step type validated on construction");
+        step.with_randomness(step.randomness() | u64::from(n))
+    }
+
+    /// Whether `id` is the root or one of the steps of this set.
+    pub fn contains(&self, id: &Ttid<T>) -> bool {
+        id.as_uuid() == self.root.as_uuid() || id.as_uuid() == self.step(step_number(id)).as_uuid()
+    }
+}
+
+/// Step number of a step id, `0` for roots.
+pub fn step_number<T: IdType>(id: &Ttid<T>) -> u16 {
+    (id.randomness() & STEP_MASK) as u16
+}
+
+/// The root of the workflow a step id belongs to, given the workflow type.
+pub fn root_of<T: IdType>(step: &Ttid<T>, workflow_type: T) -> Result<Ttid<T>, TtidError> {
+    let root = step.with_type(workflow_type)?;
+    Ok(root.with_randomness(root.randomness() & !STEP_MASK))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::MyType;
+
+    #[test]
+    fn steps_lead_back_to_the_root() {
+        let set = CorrelationSet::new(MyType::Org, MyType::Session).unwrap();
+        assert_eq!(step_number(&set.root()), 0);
+
+        for n in [0, 1, 2, u16::MAX] {
+            let step = set.step(n);
+            assert_eq!(step, set.step(n));
+            assert_eq!(step.id_type(), MyType::Session);
+            assert_eq!(step.timestamp_ms(), set.root().timestamp_ms());
+            assert_eq!(step_number(&step), n);
+            assert_eq!(root_of(&step, MyType::Org).unwrap(), set.root());
+            assert!(set.contains(&step));
+        }
+        assert_ne!(set.step(1), set.step(2));
+        assert!(set.contains(&set.root()));
+
+        let other = CorrelationSet::new(MyType::Org, MyType::Session).unwrap();
+        assert!(!set.contains(&other.step(1)));
+        assert!(!set.contains(&set.step(1).with_type(MyType::User).unwrap()));
+    }
+
+    #[test]
+    fn resumes_from_stored_roots() {
+        let set = CorrelationSet::new(MyType::Org, MyType::Session).unwrap();
+        let resumed = CorrelationSet::from_root(set.root(), MyType::Session).unwrap();
+        assert_eq!(resumed.step(7), set.step(7));
+
+        assert!(CorrelationSet::from_root(set.step(7), MyType::Session).is_none());
+    }
+
+    #[test]
+    fn rejects_reserved_step_types() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        enum Flow {
+            Workflow,
+            Reserved,
+        }
+
+        impl IdType for Flow {
+            fn to_type_id(self) -> u16 {
+                match self {
+                    Flow::Workflow => 1,
+                    Flow::Reserved => UNTYPED_TYPE_ID,
+                }
+            }
+
+            fn from_type_id(id: u16) -> Option<Self> {
+                (id == 1).then_some(Flow::Workflow)
+            }
+
+            fn as_type_name(self) -> &'static str {
+                "flow"
+            }
+
+            fn from_type_name(name: &str) -> Option<Self> {
+                (name == "flow").then_some(Flow::Workflow)
+            }
+        }
+
+        let err = CorrelationSet::new(Flow::Workflow, Flow::Reserved).unwrap_err();
+        assert!(matches!(err, TtidError::ReservedTypeId));
+        let root = CorrelationSet::new(Flow::Workflow, Flow::Workflow)
+            .unwrap()
+            .root();
+        assert!(CorrelationSet::from_root(root, Flow::Reserved).is_none());
+    }
+}